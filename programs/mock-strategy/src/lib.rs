@@ -0,0 +1,213 @@
+#![allow(unexpected_cfgs, clippy::diverging_sub_expression)]
+
+//! # Mock Strategy
+//!
+//! Smallest program that satisfies the vault's strategy adapter interface
+//! (see `vault::adapter`). Funds simply sit in a token account owned by the
+//! strategy PDA; tests mint straight into it to simulate yield.
+
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+declare_id!("ESWWuqRZL9X4F97dVUPZV54Lh32p98q2g3wAhbge4WDa");
+
+/// The vault program, whose strategy authority PDA must sign `withdraw`
+pub const VAULT_PROGRAM_ID: Pubkey = pubkey!("31mDBe7jLM8UVoqfBCUpC5yKsijh3uoKekKgRR1Z7VCJ");
+
+#[program]
+pub mod mock_strategy {
+    use super::*;
+
+    /// Create the strategy state and token account for `vault`.
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let state = &mut ctx.accounts.strategy_state;
+        state.vault = ctx.accounts.vault.key();
+        state.bump = ctx.bumps.strategy_state;
+        msg!("[strategy] initialized vault={}", state.vault);
+        Ok(())
+    }
+
+    /// The vault has already transferred `amount` into the strategy ATA;
+    /// the accounts check is all there is to do.
+    pub fn deposit(_ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        msg!("[strategy] deposit amount={}", amount);
+        Ok(())
+    }
+
+    /// Send `amount` back to the vault ATA; only the vault's strategy
+    /// authority may ask.
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let vault = ctx.accounts.vault.key();
+        let seeds = &[
+            b"strategy",
+            vault.as_ref(),
+            &[ctx.accounts.strategy_state.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.key(),
+            TransferChecked {
+                from: ctx.accounts.strategy_ata.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.vault_ata.to_account_info(),
+                authority: ctx.accounts.strategy_state.to_account_info(),
+            },
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+        msg!("[strategy] withdraw amount={}", amount);
+        Ok(())
+    }
+
+    /// Everything held for the vault, including simulated yield.
+    pub fn report_assets(ctx: Context<ReportAssets>) -> Result<u64> {
+        Ok(ctx.accounts.strategy_ata.amount)
+    }
+}
+
+#[account]
+pub struct StrategyState {
+    pub vault: Pubkey,
+    pub bump: u8,
+}
+
+impl StrategyState {
+    pub const LEN: usize = 8 // discriminator
+        + 32 // vault
+        + 1; // bump
+}
+
+#[error_code]
+pub enum StrategyError {
+    #[msg("Account does not belong to this strategy position")]
+    Mismatch,
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: the vault this position belongs to
+    pub vault: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = StrategyState::LEN,
+        seeds = [b"strategy", vault.key().as_ref()],
+        bump,
+    )]
+    pub strategy_state: Account<'info, StrategyState>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = strategy_state,
+        associated_token::token_program = token_program,
+    )]
+    pub strategy_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    /// CHECK: only used to derive the strategy PDA
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"strategy", vault.key().as_ref()],
+        bump  = strategy_state.bump,
+    )]
+    pub strategy_state: Account<'info, StrategyState>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = strategy_state,
+        associated_token::token_program = token_program,
+    )]
+    pub strategy_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = vault_ata.owner == vault.key() @ StrategyError::Mismatch,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    /// CHECK: only used to derive the strategy PDA
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"strategy", vault.key().as_ref()],
+        bump  = strategy_state.bump,
+    )]
+    pub strategy_state: Account<'info, StrategyState>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = strategy_state,
+        associated_token::token_program = token_program,
+    )]
+    pub strategy_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// Funds only ever go back to the vault
+    #[account(
+        mut,
+        constraint = vault_ata.owner == vault.key() @ StrategyError::Mismatch,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// The vault program's strategy authority for `vault`, so only the vault
+    /// can pull funds back
+    #[account(
+        seeds = [b"strategy_authority", vault.key().as_ref()],
+        bump,
+        seeds::program = VAULT_PROGRAM_ID,
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ReportAssets<'info> {
+    /// CHECK: only used to derive the strategy PDA
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"strategy", vault.key().as_ref()],
+        bump  = strategy_state.bump,
+    )]
+    pub strategy_state: Account<'info, StrategyState>,
+
+    #[account(
+        associated_token::mint = mint,
+        associated_token::authority = strategy_state,
+        associated_token::token_program = token_program,
+    )]
+    pub strategy_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
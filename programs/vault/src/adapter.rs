@@ -0,0 +1,130 @@
+//! Yield-strategy adapter interface.
+//!
+//! A strategy is any program exposing three instructions, named (and so
+//! discriminated) as Anchor would name them, that all take the same accounts
+//! in this order:
+//!
+//! 0. vault PDA (`VaultState`) — never a signer
+//! 1. strategy state — owned and validated by the strategy
+//! 2. strategy token account — held by the strategy state
+//! 3. vault ATA
+//! 4. mint
+//! 5. token program
+//! 6. strategy authority PDA (`[b"strategy_authority", vault]`)
+//!
+//! Accounts 1–3 are writable for `deposit` and `withdraw`; `report_assets`
+//! gets every account read-only.
+//!
+//! - `deposit(amount: u64)` is told that the vault has just transferred
+//!   `amount` into the strategy token account
+//! - `withdraw(amount: u64)` sends `amount` back to the vault ATA. Only here
+//!   does the strategy authority sign, and a strategy must refuse a
+//!   `withdraw` it does not sign: any other caller would move funds the vault
+//!   still counts as `allocated`. The PDA controls nothing else, so signing
+//!   with it hands the strategy no power over the vault
+//! - `report_assets() -> u64` returns the position's current value as return data
+
+use crate::errors::VaultError;
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::Instruction,
+        program::{get_return_data, invoke, invoke_signed},
+    },
+};
+
+/// `sha256("global:deposit")[..8]`
+pub const DEPOSIT_DISCRIMINATOR: [u8; 8] = [242, 35, 198, 137, 82, 225, 242, 182];
+/// `sha256("global:withdraw")[..8]`
+pub const WITHDRAW_DISCRIMINATOR: [u8; 8] = [183, 18, 70, 156, 148, 109, 161, 34];
+/// `sha256("global:report_assets")[..8]`
+pub const REPORT_ASSETS_DISCRIMINATOR: [u8; 8] = [159, 190, 205, 181, 109, 163, 7, 36];
+
+/// Accounts passed to every adapter call.
+pub struct StrategyCpi<'info> {
+    pub program: AccountInfo<'info>,
+    pub vault: AccountInfo<'info>,
+    pub strategy_state: AccountInfo<'info>,
+    pub strategy_ata: AccountInfo<'info>,
+    pub vault_ata: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    pub authority_bump: u8,
+}
+
+impl<'info> StrategyCpi<'info> {
+    fn instruction(&self, data: Vec<u8>, writable: bool, signed: bool) -> Instruction {
+        let meta = |info: &AccountInfo, is_writable: bool| AccountMeta {
+            pubkey: info.key(),
+            is_signer: false,
+            is_writable,
+        };
+        Instruction {
+            program_id: self.program.key(),
+            accounts: vec![
+                AccountMeta::new_readonly(self.vault.key(), false),
+                meta(&self.strategy_state, writable),
+                meta(&self.strategy_ata, writable),
+                meta(&self.vault_ata, writable),
+                meta(&self.mint, false),
+                meta(&self.token_program, false),
+                AccountMeta::new_readonly(self.authority.key(), signed),
+            ],
+            data,
+        }
+    }
+
+    fn infos(&self) -> [AccountInfo<'info>; 8] {
+        [
+            self.vault.clone(),
+            self.strategy_state.clone(),
+            self.strategy_ata.clone(),
+            self.vault_ata.clone(),
+            self.mint.clone(),
+            self.token_program.clone(),
+            self.authority.clone(),
+            self.program.clone(),
+        ]
+    }
+
+    /// Tell the strategy `amount` has been transferred to it. The vault does
+    /// not sign: its PDA also controls the vault ATA and share mint.
+    pub fn deposit(&self, amount: u64) -> Result<()> {
+        let mut data = DEPOSIT_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&amount.to_le_bytes());
+        invoke(&self.instruction(data, true, false), &self.infos())?;
+        Ok(())
+    }
+
+    /// Ask the strategy to return `amount`, signed by the strategy authority
+    /// rather than the vault.
+    pub fn withdraw(&self, amount: u64) -> Result<()> {
+        let mut data = WITHDRAW_DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&amount.to_le_bytes());
+        let vault = self.vault.key();
+        let seeds: &[&[u8]] = &[
+            b"strategy_authority",
+            vault.as_ref(),
+            &[self.authority_bump],
+        ];
+        invoke_signed(&self.instruction(data, true, true), &self.infos(), &[seeds])?;
+        Ok(())
+    }
+
+    /// Current value of the vault's position, as reported by the strategy.
+    pub fn report_assets(&self) -> Result<u64> {
+        invoke(
+            &self.instruction(REPORT_ASSETS_DISCRIMINATOR.to_vec(), false, false),
+            &self.infos(),
+        )?;
+
+        let (program, data) = get_return_data().ok_or(VaultError::StrategyMisbehaved)?;
+        require_keys_eq!(program, self.program.key(), VaultError::StrategyMisbehaved);
+        let bytes: [u8; 8] = data
+            .as_slice()
+            .try_into()
+            .map_err(|_| error!(VaultError::StrategyMisbehaved))?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+}
@@ -22,4 +22,178 @@ pub enum VaultError {
     Unauthorised,
     #[msg("Vault must be empty before closing")]
     VaultNotEmpty,
+    #[msg("Vault is multisig-controlled — submit a proposal instead")]
+    MultisigRequired,
+    #[msg("Signer set must be 1..=10 distinct keys")]
+    InvalidSignerSet,
+    #[msg("Threshold must be between 1 and the number of signers")]
+    InvalidThreshold,
+    #[msg("Signer is not a member of the vault multisig")]
+    NotMultisigSigner,
+    #[msg("Signer has already approved this proposal")]
+    AlreadyApproved,
+    #[msg("Proposal has not reached the approval threshold")]
+    ThresholdNotMet,
+    #[msg("Proposal action does not match the instruction")]
+    ProposalActionMismatch,
+    #[msg("Proposal was created for an earlier multisig on this vault")]
+    StaleProposal,
+    #[msg("Vault still has open proposals — execute or cancel them first")]
+    ProposalsOutstanding,
+    #[msg("No owner transfer is pending")]
+    NoPendingOwner,
+    #[msg("No configuration change is queued")]
+    NoPendingConfig,
+    #[msg("Queued configuration change is still timelocked")]
+    ConfigTimelockActive,
+    #[msg("Deposits are paused")]
+    DepositsPaused,
+    #[msg("Owner withdrawals are paused")]
+    WithdrawalsPaused,
+    #[msg("Delegate withdrawals are paused")]
+    DelegateWithdrawalsPaused,
+    #[msg("Pause flags must be a non-empty subset of PAUSE_ALL")]
+    InvalidPauseFlags,
+    #[msg("Guardian unpause is still within its delay")]
+    UnpauseDelayActive,
+    #[msg("Guardian has not requested these flags to be lifted")]
+    NoPendingUnpause,
+    #[msg("Depositor is not permitted by the vault's deposit mode")]
+    DepositorNotAllowed,
+    #[msg("Delegate period must be non-negative, and set whenever max_periods is")]
+    InvalidDelegatePeriod,
+    #[msg("Delegate has used all of its allowance periods")]
+    DelegatePeriodsExhausted,
+    #[msg("Allowance cannot be lowered below the amount already used")]
+    AllowanceBelowUsed,
+    #[msg("Delegate activation time must be before its expiry")]
+    InvalidDelegateWindow,
+    #[msg("Delegate is not active yet")]
+    DelegateNotActive,
+    #[msg("Delegate is paused")]
+    DelegatePaused,
+    #[msg("Delegate record is still usable and cannot be pruned")]
+    DelegateStillActive,
+    #[msg("Vault still has delegate records — pass them to close_vault or remove them first")]
+    DelegatesOutstanding,
+    #[msg("Stream schedule must satisfy now <= start <= cliff <= end and start < end")]
+    InvalidStreamSchedule,
+    #[msg("Nothing has accrued to claim yet")]
+    NothingToClaim,
+    #[msg("Vault still has open streams")]
+    StreamsOutstanding,
+    #[msg("Unlock time must be in the future and at most MAX_LOCKUP_SECONDS away")]
+    InvalidUnlockTime,
+    #[msg("Pass a tranche account exactly when unlock_at is set")]
+    TrancheAccountMismatch,
+    #[msg("Tranche has not reached its unlock time")]
+    TrancheNotMatured,
+    #[msg("Vault still has locked tranches")]
+    TranchesOutstanding,
+    #[msg("Mint or account does not match the vault asset")]
+    AssetMismatch,
+    #[msg("The vault's own mint cannot be added as an asset")]
+    DuplicateAsset,
+    #[msg("Vault still has registered assets — remove them first")]
+    AssetsOutstanding,
+    #[msg("Not available in pooled mode — holders redeem with withdraw_shares")]
+    PooledVault,
+    #[msg("Vault is not in pooled mode")]
+    NotPooled,
+    #[msg("Pass the share accounts exactly when the vault is pooled")]
+    ShareAccountMismatch,
+    #[msg("Deposit is too small to mint any shares")]
+    ZeroShares,
+    #[msg("Not enough liquid balance — deallocate from the strategy first")]
+    InsufficientLiquidity,
+    #[msg("Vault has no strategy set")]
+    NoStrategy,
+    #[msg("Strategy program does not match the vault's strategy")]
+    StrategyMismatch,
+    #[msg("Funds are still allocated to the strategy")]
+    StrategyFundsOutstanding,
+    #[msg("Strategy moved a different amount than requested")]
+    StrategyMisbehaved,
+    #[msg("Pooled vaults cannot use a yield strategy")]
+    PooledStrategy,
+    #[msg("Account uses an old layout — run migrate_vault / migrate_delegate first")]
+    AccountNotMigrated,
+    #[msg("Account is already at the current layout version")]
+    AlreadyMigrated,
+    #[msg("Permissions must be a non-empty set of ROLE_* bits")]
+    InvalidPermissions,
+    #[msg("Vault still has granted roles — revoke them first")]
+    RolesOutstanding,
+    #[msg("Policy rule MaxPerTransaction: amount exceeds the per-withdrawal maximum")]
+    PolicyMaxPerTransaction,
+    #[msg("Policy rule MinAmount: amount is below the minimum withdrawal")]
+    PolicyMinAmount,
+    #[msg("Policy rule RecipientCap: recipient's 24-h cap would be exceeded")]
+    PolicyRecipientCap,
+    #[msg("Policy rule MaxWithdrawals: too many withdrawals in the current window")]
+    PolicyMaxWithdrawals,
+    #[msg("Pass the vault's policy account whenever it has policy rules")]
+    PolicyAccountMismatch,
+    #[msg("Policy rule parameters must be non-zero")]
+    InvalidPolicyRule,
+    #[msg("Policy already holds the maximum number of rules")]
+    PolicyFull,
+    #[msg("No policy rule at that index")]
+    PolicyRuleNotFound,
+    #[msg("Vault still has policy rules — remove them first")]
+    PolicyOutstanding,
+    #[msg("Vault is in queued mode — use request_withdraw / execute_withdraw")]
+    WithdrawQueueRequired,
+    #[msg("Nothing to queue: no delay tiers are set and the amount is under the veto threshold")]
+    WithdrawQueueDisabled,
+    #[msg("Delay tiers need increasing min_amount, positive non-decreasing delays, at most MAX_WITHDRAW_TIERS")]
+    InvalidWithdrawTiers,
+    #[msg("Queued withdrawal is still in its cooldown")]
+    WithdrawCooldownActive,
+    #[msg("Vault still has pending withdrawals — execute or cancel them first")]
+    PendingWithdrawalsOutstanding,
+    #[msg("Withdrawal was requested before the vault's last recovery — cancel it")]
+    PendingWithdrawalStale,
+    #[msg("Vault still has withdrawal delay tiers — clear them first")]
+    WithdrawTiersOutstanding,
+    #[msg("Withdrawal is above the co-sign threshold and the co-signer did not sign")]
+    CosignatureRequired,
+    #[msg("Pass the vault's withdraw tiers account whenever it is in queued mode")]
+    WithdrawTiersAccountMismatch,
+    #[msg("Amount is above the veto threshold — use request_withdraw")]
+    VetoWindowRequired,
+    #[msg("Veto window must not be negative")]
+    InvalidVetoWindow,
+    #[msg("Veto threshold must be positive when a veto window is set")]
+    InvalidVetoThreshold,
+    #[msg("Veto window for this withdrawal has closed")]
+    VetoWindowClosed,
+    #[msg("Withdrawal is not above the veto threshold")]
+    VetoNotRequired,
+    #[msg("Vault still has veto keys — remove them first")]
+    VetoKeysOutstanding,
+    #[msg("Signer is not a recovery guardian of this vault")]
+    NotRecoveryGuardian,
+    #[msg("Recovery delay must be positive")]
+    InvalidRecoveryDelay,
+    #[msg("A recovery is already in progress — cancel or complete it first")]
+    RecoveryInProgress,
+    #[msg("No recovery is in progress")]
+    NoRecoveryInProgress,
+    #[msg("Recovery has not reached its threshold or is still in its delay")]
+    RecoveryNotReady,
+    #[msg("Vault still has recovery guardians — remove them first")]
+    RecoveryOutstanding,
+    #[msg("Inactivity period must be positive when a beneficiary is set")]
+    InvalidInactivityPeriod,
+    #[msg("Owner has been active within the inactivity period")]
+    OwnerStillActive,
+    #[msg("Vault still has allowlisted depositors — remove them first")]
+    DepositorsOutstanding,
+    #[msg("Delegate record was left behind by a closed vault at this address")]
+    StaleDelegateRecord,
+    #[msg(
+        "Asset withdrawals are unavailable while a co-signer, withdrawal queue or veto window guards the vault"
+    )]
+    AssetOutflowsDisabled,
 }
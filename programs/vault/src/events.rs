@@ -1,3 +1,4 @@
+use crate::state::{DelayTier, DepositMode, ProposalAction, Rule};
 use anchor_lang::prelude::*;
 
 #[event]
@@ -12,8 +13,15 @@ pub struct VaultInitialised {
 #[event]
 pub struct DepositMade {
     pub vault: Pubkey,
+    /// The vault's own mint, or that of a `VaultAsset`
+    pub mint: Pubkey,
     pub depositor: Pubkey,
+    /// Amount credited to the vault ATA, net of any transfer fee
     pub amount: u64,
+    /// Transfer fee withheld by a Token-2022 mint (0 for SPL Token)
+    pub fee: u64,
+    /// Pool shares minted to the depositor (0 unless pooled)
+    pub shares: u64,
     pub total_deposited: u64,
     pub timestamp: i64,
 }
@@ -21,8 +29,16 @@ pub struct DepositMade {
 #[event]
 pub struct WithdrawMade {
     pub vault: Pubkey,
+    /// The vault's own mint, or that of a `VaultAsset`
+    pub mint: Pubkey,
+    /// Owner of the destination token account
     pub recipient: Pubkey,
+    /// Amount credited to the recipient, net of any transfer fee
     pub amount: u64,
+    /// Transfer fee withheld by a Token-2022 mint (0 for SPL Token)
+    pub fee: u64,
+    /// Pool shares burned (0 unless redeemed with `withdraw_shares`)
+    pub shares: u64,
     pub by_delegate: bool,
     pub timestamp: i64,
 }
@@ -31,8 +47,28 @@ pub struct WithdrawMade {
 pub struct DelegateAdded {
     pub vault: Pubkey,
     pub delegate: Pubkey,
+    /// Mint the allowance is denominated in
+    pub mint: Pubkey,
     pub allowance: u64,
     pub expires_at: i64,
+    pub period: i64,
+    pub max_periods: u32,
+}
+
+#[event]
+pub struct DelegateUpdated {
+    pub vault: Pubkey,
+    pub delegate: Pubkey,
+    pub old_allowance: u64,
+    pub new_allowance: u64,
+    pub old_expires_at: i64,
+    pub new_expires_at: i64,
+    pub old_not_before: i64,
+    pub new_not_before: i64,
+    pub old_paused: bool,
+    pub new_paused: bool,
+    pub old_exempt_from_daily_limit: bool,
+    pub new_exempt_from_daily_limit: bool,
 }
 
 #[event]
@@ -47,3 +83,466 @@ pub struct VaultClosed {
     pub owner: Pubkey,
     pub timestamp: i64,
 }
+
+#[event]
+pub struct MultisigEnabled {
+    pub vault: Pubkey,
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub vault: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+}
+
+#[event]
+pub struct ProposalApproved {
+    pub vault: Pubkey,
+    pub proposal: Pubkey,
+    pub signer: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct ProposalCancelled {
+    pub vault: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u64,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub vault: Pubkey,
+    pub proposal: Pubkey,
+    pub index: u64,
+}
+
+#[event]
+pub struct OwnerTransferProposed {
+    pub vault: Pubkey,
+    pub owner: Pubkey,
+    /// `Pubkey::default()` when a pending transfer is cancelled
+    pub pending_owner: Pubkey,
+}
+
+#[event]
+pub struct OwnerTransferred {
+    pub vault: Pubkey,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigChangeQueued {
+    pub vault: Pubkey,
+    pub max_deposit: u64,
+    pub daily_withdraw_limit: u64,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct ConfigChanged {
+    pub vault: Pubkey,
+    pub old_max_deposit: u64,
+    pub new_max_deposit: u64,
+    pub old_daily_withdraw_limit: u64,
+    pub new_daily_withdraw_limit: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GuardianSet {
+    pub vault: Pubkey,
+    /// `Pubkey::default()` when the guardian is removed
+    pub guardian: Pubkey,
+}
+
+#[event]
+pub struct VaultPaused {
+    pub vault: Pubkey,
+    pub by: Pubkey,
+    /// Bits paused by this call
+    pub flags: u8,
+    /// All bits paused after this call
+    pub paused: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UnpauseRequested {
+    pub vault: Pubkey,
+    pub guardian: Pubkey,
+    pub flags: u8,
+    pub available_at: i64,
+}
+
+#[event]
+pub struct VaultUnpaused {
+    pub vault: Pubkey,
+    pub by: Pubkey,
+    /// Bits lifted by this call
+    pub flags: u8,
+    /// Bits still paused after this call
+    pub paused: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DepositModeChanged {
+    pub vault: Pubkey,
+    pub old_mode: DepositMode,
+    pub new_mode: DepositMode,
+}
+
+#[event]
+pub struct DepositorAdded {
+    pub vault: Pubkey,
+    pub depositor: Pubkey,
+}
+
+#[event]
+pub struct DepositorRemoved {
+    pub vault: Pubkey,
+    pub depositor: Pubkey,
+}
+
+#[event]
+pub struct StreamCreated {
+    pub vault: Pubkey,
+    pub stream: Pubkey,
+    pub recipient: Pubkey,
+    pub total: u64,
+    pub start: i64,
+    pub cliff: i64,
+    pub end: i64,
+}
+
+#[event]
+pub struct StreamClaimed {
+    pub vault: Pubkey,
+    pub stream: Pubkey,
+    pub recipient: Pubkey,
+    /// Amount credited to the destination, net of any transfer fee
+    pub amount: u64,
+    pub fee: u64,
+    /// Cumulative amount taken from the stream
+    pub withdrawn: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StreamCancelled {
+    pub vault: Pubkey,
+    pub stream: Pubkey,
+    pub recipient: Pubkey,
+    /// Amount that had vested and stays claimable by the recipient
+    pub vested: u64,
+    /// Unvested amount released back to the vault
+    pub refunded: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TrancheCreated {
+    pub vault: Pubkey,
+    pub tranche: Pubkey,
+    pub depositor: Pubkey,
+    /// Amount locked, net of any transfer fee
+    pub amount: u64,
+    pub unlock_at: i64,
+}
+
+#[event]
+pub struct TrancheReleased {
+    pub vault: Pubkey,
+    pub tranche: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AssetAdded {
+    pub vault: Pubkey,
+    pub asset: Pubkey,
+    pub mint: Pubkey,
+    pub max_deposit: u64,
+    pub daily_withdraw_limit: u64,
+}
+
+#[event]
+pub struct AssetRemoved {
+    pub vault: Pubkey,
+    pub asset: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct PoolEnabled {
+    pub vault: Pubkey,
+    pub share_mint: Pubkey,
+}
+
+#[event]
+pub struct StrategySet {
+    pub vault: Pubkey,
+    pub old_strategy: Pubkey,
+    pub new_strategy: Pubkey,
+    pub strategy_state: Pubkey,
+}
+
+#[event]
+pub struct StrategyChangeQueued {
+    pub vault: Pubkey,
+    pub strategy: Pubkey,
+    pub strategy_state: Pubkey,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct StrategyAllocated {
+    pub vault: Pubkey,
+    pub strategy: Pubkey,
+    pub amount: u64,
+    /// Principal allocated after this call
+    pub allocated: u64,
+}
+
+#[event]
+pub struct StrategyDeallocated {
+    pub vault: Pubkey,
+    pub strategy: Pubkey,
+    /// Amount the vault ATA actually gained
+    pub amount: u64,
+    pub allocated: u64,
+}
+
+#[event]
+pub struct StrategyReported {
+    pub vault: Pubkey,
+    pub strategy: Pubkey,
+    pub value: u64,
+    pub allocated: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub old_version: u8,
+    pub new_version: u8,
+    /// Account size after the realloc
+    pub len: u64,
+}
+
+#[event]
+pub struct RoleGranted {
+    pub vault: Pubkey,
+    pub member: Pubkey,
+    pub old_permissions: u8,
+    pub new_permissions: u8,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub vault: Pubkey,
+    pub member: Pubkey,
+    pub permissions: u8,
+}
+
+#[event]
+pub struct PolicyRuleAdded {
+    pub vault: Pubkey,
+    pub index: u8,
+    pub rule: Rule,
+}
+
+#[event]
+pub struct PolicyRuleRemoved {
+    pub vault: Pubkey,
+    pub index: u8,
+    pub rule: Rule,
+}
+
+#[event]
+pub struct WithdrawTiersChanged {
+    pub vault: Pubkey,
+    pub tiers: Vec<DelayTier>,
+}
+
+#[event]
+pub struct WithdrawTiersQueued {
+    pub vault: Pubkey,
+    pub tiers: Vec<DelayTier>,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct WithdrawRequested {
+    pub vault: Pubkey,
+    pub request_id: u64,
+    pub requester: Pubkey,
+    /// Owner of the destination token account
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub execute_after: i64,
+}
+
+#[event]
+pub struct WithdrawExecuted {
+    pub vault: Pubkey,
+    pub request_id: u64,
+    pub recipient: Pubkey,
+    /// Amount the destination actually received
+    pub amount: u64,
+    pub fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct WithdrawCancelled {
+    pub vault: Pubkey,
+    pub request_id: u64,
+    pub amount: u64,
+    /// Owner or guardian
+    pub cancelled_by: Pubkey,
+}
+
+#[event]
+pub struct CosignerChangeQueued {
+    pub vault: Pubkey,
+    pub cosigner: Pubkey,
+    pub threshold: u64,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct CosignerChanged {
+    pub vault: Pubkey,
+    pub old_cosigner: Pubkey,
+    pub new_cosigner: Pubkey,
+    pub old_threshold: u64,
+    pub new_threshold: u64,
+}
+
+#[event]
+pub struct VetoConfigQueued {
+    pub vault: Pubkey,
+    pub threshold: u64,
+    pub window: i64,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct VetoConfigChanged {
+    pub vault: Pubkey,
+    pub old_threshold: u64,
+    pub new_threshold: u64,
+    pub old_window: i64,
+    pub new_window: i64,
+}
+
+#[event]
+pub struct VetoKeyAdded {
+    pub vault: Pubkey,
+    pub key: Pubkey,
+}
+
+#[event]
+pub struct VetoKeyRemovalQueued {
+    pub vault: Pubkey,
+    pub key: Pubkey,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct VetoKeyRemoved {
+    pub vault: Pubkey,
+    pub key: Pubkey,
+}
+
+#[event]
+pub struct WithdrawVetoed {
+    pub vault: Pubkey,
+    pub request_id: u64,
+    pub amount: u64,
+    pub veto_key: Pubkey,
+    /// Code chosen by the vetoing team; meaning is defined off-chain
+    pub reason: u8,
+}
+
+#[event]
+pub struct RecoveryConfigured {
+    pub vault: Pubkey,
+    pub guardians: Vec<Pubkey>,
+    pub threshold: u8,
+    pub delay: i64,
+}
+
+#[event]
+pub struct RecoveryRemoved {
+    pub vault: Pubkey,
+}
+
+#[event]
+pub struct RecoveryInitiated {
+    pub vault: Pubkey,
+    pub guardian: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct RecoveryApproved {
+    pub vault: Pubkey,
+    pub guardian: Pubkey,
+    pub approvals: u8,
+    /// When the recovery may complete (0 = threshold not reached yet)
+    pub ready_at: i64,
+}
+
+#[event]
+pub struct RecoveryCancelled {
+    pub vault: Pubkey,
+    pub new_owner: Pubkey,
+}
+
+#[event]
+pub struct RecoveryCompleted {
+    pub vault: Pubkey,
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct BeneficiarySet {
+    pub vault: Pubkey,
+    /// Default = feature off
+    pub beneficiary: Pubkey,
+    pub inactivity_period: i64,
+    pub claim_limit: u64,
+}
+
+#[event]
+pub struct OwnerHeartbeat {
+    pub vault: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InheritanceClaimed {
+    pub vault: Pubkey,
+    pub beneficiary: Pubkey,
+    /// Amount the destination actually received
+    pub amount: u64,
+    pub fee: u64,
+    /// Last owner heartbeat the claim relied on
+    pub last_heartbeat: i64,
+    pub timestamp: i64,
+}
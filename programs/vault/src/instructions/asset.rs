@@ -0,0 +1,620 @@
+use crate::{
+    errors::VaultError,
+    events::{AssetAdded, AssetRemoved, DepositMade, WithdrawMade},
+    instructions::delegate,
+    state::{
+        DelegateRecord, DepositorRecord, Role, VaultAsset, VaultState, PAUSE_DELEGATE_WITHDRAWALS,
+        PAUSE_DEPOSITS, PAUSE_WITHDRAWALS, ROLE_CONFIG_ADMIN, ROLE_DELEGATE_MANAGER,
+        ROLE_DEPOSITOR, ROLE_WITHDRAWER,
+    },
+    utils::{close_vault_ata, transfer_from_vault},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+// ─── AddAsset ────────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct AddAsset<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(constraint = mint.key() != vault_state.mint @ VaultError::DuplicateAsset)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_CONFIG_ADMIN) @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// `authority`'s role on this vault; not needed when it is the owner
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = VaultAsset::LEN,
+        seeds = [b"asset", vault_state.key().as_ref(), mint.key().as_ref()],
+        bump,
+    )]
+    pub asset: Account<'info, VaultAsset>,
+
+    /// The vault PDA's ATA for `mint`; anyone can create an ATA, so an
+    /// existing one is adopted rather than blocking registration
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = vault_state,
+        associated_token::token_program = token_program,
+    )]
+    pub asset_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Register another mint on the vault, with its own deposit and daily limits.
+pub fn add_handler(
+    ctx: Context<AddAsset>,
+    max_deposit: u64,
+    daily_withdraw_limit: u64,
+) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    let clock = Clock::get()?;
+    let vault = &mut ctx.accounts.vault_state;
+    vault.asset_count = vault
+        .asset_count
+        .checked_add(1)
+        .ok_or(VaultError::Overflow)?;
+
+    let asset = &mut ctx.accounts.asset;
+    asset.vault = vault.key();
+    asset.mint = ctx.accounts.mint.key();
+    asset.vault_ata = ctx.accounts.asset_ata.key();
+    asset.total_deposited = 0;
+    asset.total_withdrawn = 0;
+    asset.max_deposit = max_deposit;
+    asset.daily_withdraw_limit = daily_withdraw_limit;
+    asset.withdrawn_today = 0;
+    asset.window_start = clock.unix_timestamp;
+    asset.bump = ctx.bumps.asset;
+    asset.delegate_count = 0;
+
+    emit!(AssetAdded {
+        vault: asset.vault,
+        asset: asset.key(),
+        mint: asset.mint,
+        max_deposit,
+        daily_withdraw_limit,
+    });
+    msg!(
+        "[vault] asset added mint={} count={}",
+        asset.mint,
+        vault.asset_count
+    );
+    Ok(())
+}
+
+// ─── RemoveAsset ─────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct RemoveAsset<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_CONFIG_ADMIN) @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: rent destination, validated against `vault_state.owner`
+    #[account(mut, address = vault_state.owner @ VaultError::Unauthorised)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// `authority`'s role on this vault; not needed when it is the owner
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"asset", vault_state.key().as_ref(), mint.key().as_ref()],
+        bump  = asset.bump,
+        has_one = mint @ VaultError::AssetMismatch,
+        constraint = asset.delegate_count == 0 @ VaultError::DelegatesOutstanding,
+    )]
+    pub asset: Account<'info, VaultAsset>,
+
+    #[account(
+        mut,
+        address = asset.vault_ata @ VaultError::AssetMismatch,
+        constraint = asset_ata.amount == 0 @ VaultError::VaultNotEmpty,
+    )]
+    pub asset_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Unregister an emptied asset with no delegates left, closing its token account.
+pub fn remove_handler(ctx: Context<RemoveAsset>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    close_vault_ata(
+        &ctx.accounts.vault_state,
+        &ctx.accounts.asset_ata,
+        ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.token_program,
+    )?;
+
+    let vault = &mut ctx.accounts.vault_state;
+    vault.asset_count = vault
+        .asset_count
+        .checked_sub(1)
+        .ok_or(VaultError::Overflow)?;
+
+    emit!(AssetRemoved {
+        vault: vault.key(),
+        asset: ctx.accounts.asset.key(),
+        mint: ctx.accounts.mint.key(),
+    });
+    msg!("[vault] asset removed mint={}", ctx.accounts.mint.key());
+    Ok(())
+}
+
+// ─── DepositAsset ────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct DepositAsset<'info> {
+    /// Owner, or any third party permitted by `vault_state.deposit_mode`
+    pub depositor: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"asset", vault_state.key().as_ref(), mint.key().as_ref()],
+        bump  = asset.bump,
+        has_one = mint @ VaultError::AssetMismatch,
+    )]
+    pub asset: Account<'info, VaultAsset>,
+
+    /// Source — must be owned by the depositor signer
+    #[account(
+        mut,
+        constraint = depositor_ata.owner == depositor.key() @ VaultError::Unauthorised,
+        constraint = depositor_ata.mint  == mint.key()      @ VaultError::Unauthorised,
+    )]
+    pub depositor_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = asset.vault_ata @ VaultError::AssetMismatch,
+    )]
+    pub asset_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// Required only for non-owner deposits in `DepositMode::Allowlist`
+    #[account(
+        seeds = [b"depositor", vault_state.key().as_ref(), depositor.key().as_ref()],
+        bump  = depositor_record.bump,
+    )]
+    pub depositor_record: Option<Account<'info, DepositorRecord>>,
+
+    /// Depositor's role; lets it deposit whatever the `deposit_mode`
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), depositor.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn deposit_handler(ctx: Context<DepositAsset>, amount: u64) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.depositor.key(), Clock::get()?.unix_timestamp);
+
+    require!(amount > 0, VaultError::ZeroAmount);
+
+    let vault = &mut ctx.accounts.vault_state;
+    require!(!vault.locked, VaultError::VaultLocked);
+    vault.require_not_paused(PAUSE_DEPOSITS)?;
+
+    let depositor = ctx.accounts.depositor.key();
+    require!(
+        vault.authorized(&depositor, ctx.accounts.role.as_deref(), ROLE_DEPOSITOR)
+            || vault.may_deposit(depositor, ctx.accounts.depositor_record.is_some()),
+        VaultError::DepositorNotAllowed
+    );
+
+    let asset = &mut ctx.accounts.asset;
+    if asset.max_deposit > 0 {
+        require!(amount <= asset.max_deposit, VaultError::DepositTooLarge);
+    }
+
+    vault.locked = true;
+
+    let balance_before = ctx.accounts.asset_ata.amount;
+
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.key(),
+        TransferChecked {
+            from: ctx.accounts.depositor_ata.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.asset_ata.to_account_info(),
+            authority: ctx.accounts.depositor.to_account_info(),
+        },
+    );
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+    ctx.accounts.asset_ata.reload()?;
+    let received = ctx
+        .accounts
+        .asset_ata
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(VaultError::Overflow)?;
+
+    asset.total_deposited = asset
+        .total_deposited
+        .checked_add(received)
+        .ok_or(VaultError::Overflow)?;
+    vault.locked = false;
+
+    let clock = Clock::get()?;
+    emit!(DepositMade {
+        vault: vault.key(),
+        mint: asset.mint,
+        depositor,
+        amount: received,
+        fee: amount.saturating_sub(received),
+        shares: 0,
+        total_deposited: asset.total_deposited,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "[vault] asset deposit mint={} amount={} received={}",
+        asset.mint,
+        amount,
+        received
+    );
+    Ok(())
+}
+
+// ─── WithdrawAsset ───────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct WithdrawAsset<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_WITHDRAWER) @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.asset_outflows_allowed() @ VaultError::AssetOutflowsDisabled,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// `authority`'s role on this vault; not needed when it is the owner
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    #[account(
+        mut,
+        seeds = [b"asset", vault_state.key().as_ref(), mint.key().as_ref()],
+        bump  = asset.bump,
+        has_one = mint @ VaultError::AssetMismatch,
+    )]
+    pub asset: Account<'info, VaultAsset>,
+
+    #[account(
+        mut,
+        address = asset.vault_ata @ VaultError::AssetMismatch,
+    )]
+    pub asset_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// Any token account of the asset's mint
+    #[account(
+        mut,
+        constraint = destination.mint == mint.key() @ VaultError::Unauthorised,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn withdraw_handler(ctx: Context<WithdrawAsset>, amount: u64) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    require!(amount > 0, VaultError::ZeroAmount);
+
+    let vault = &mut ctx.accounts.vault_state;
+    require!(!vault.locked, VaultError::VaultLocked);
+    vault.require_not_paused(PAUSE_WITHDRAWALS)?;
+
+    let clock = Clock::get()?;
+    let asset = &mut ctx.accounts.asset;
+    asset.charge_daily_limit(amount, clock.unix_timestamp)?;
+
+    require!(
+        ctx.accounts.asset_ata.amount >= amount,
+        VaultError::InsufficientFunds
+    );
+
+    vault.locked = true;
+
+    let received = transfer_from_vault(
+        vault,
+        &ctx.accounts.asset_ata,
+        &mut ctx.accounts.destination,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+
+    asset.total_withdrawn = asset
+        .total_withdrawn
+        .checked_add(received)
+        .ok_or(VaultError::Overflow)?;
+    vault.locked = false;
+
+    emit!(WithdrawMade {
+        vault: vault.key(),
+        mint: asset.mint,
+        recipient: ctx.accounts.destination.owner,
+        amount: received,
+        fee: amount.saturating_sub(received),
+        shares: 0,
+        by_delegate: false,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "[vault] asset withdraw mint={} amount={} received={}",
+        asset.mint,
+        amount,
+        received
+    );
+    Ok(())
+}
+
+// ─── AddAssetDelegate ────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+#[instruction(allowance: u64, expires_at: i64, period: i64, max_periods: u32)]
+pub struct AddAssetDelegate<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_DELEGATE_MANAGER) @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// `authority`'s role on this vault; not needed when it is the owner
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    #[account(
+        mut,
+        seeds = [b"asset", vault_state.key().as_ref(), mint.key().as_ref()],
+        bump  = asset.bump,
+        has_one = mint @ VaultError::AssetMismatch,
+    )]
+    pub asset: Account<'info, VaultAsset>,
+
+    /// CHECK: arbitrary pubkey we're granting access to
+    pub delegate: UncheckedAccount<'info>,
+
+    /// Seeded by the mint too, so a delegate can hold an allowance per asset
+    #[account(
+        init,
+        payer = authority,
+        space = DelegateRecord::LEN,
+        seeds = [b"delegate", vault_state.key().as_ref(), delegate.key().as_ref(), mint.key().as_ref()],
+        bump,
+    )]
+    pub delegate_record: Account<'info, DelegateRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Same as `add_delegate`, with the allowance denominated in the asset's mint.
+pub fn add_delegate_handler(
+    ctx: Context<AddAssetDelegate>,
+    allowance: u64,
+    expires_at: i64,
+    period: i64,
+    max_periods: u32,
+) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    let asset = &mut ctx.accounts.asset;
+    asset.delegate_count = asset
+        .delegate_count
+        .checked_add(1)
+        .ok_or(VaultError::Overflow)?;
+
+    delegate::grant(
+        &mut ctx.accounts.delegate_record,
+        &mut ctx.accounts.vault_state,
+        ctx.accounts.delegate.key(),
+        asset.mint,
+        allowance,
+        expires_at,
+        period,
+        max_periods,
+        ctx.bumps.delegate_record,
+    )
+}
+
+// ─── DelegateWithdrawAsset ───────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct DelegateWithdrawAsset<'info> {
+    /// Must be the exact delegate pubkey stored in the record
+    pub delegate_signer: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.asset_outflows_allowed() @ VaultError::AssetOutflowsDisabled,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"asset", vault_state.key().as_ref(), mint.key().as_ref()],
+        bump  = asset.bump,
+        has_one = mint @ VaultError::AssetMismatch,
+    )]
+    pub asset: Account<'info, VaultAsset>,
+
+    #[account(
+        mut,
+        address = asset.vault_ata @ VaultError::AssetMismatch,
+    )]
+    pub asset_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = delegate_ata.owner == delegate_signer.key() @ VaultError::Unauthorised,
+        constraint = delegate_ata.mint  == mint.key()            @ VaultError::Unauthorised,
+    )]
+    pub delegate_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"delegate", vault_state.key().as_ref(), delegate_signer.key().as_ref(), mint.key().as_ref()],
+        bump  = delegate_record.bump,
+        constraint = delegate_record.delegate == delegate_signer.key() @ VaultError::Unauthorised,
+        constraint = delegate_record.vault    == vault_state.key()     @ VaultError::Unauthorised,
+        constraint = delegate_record.mint     == mint.key()            @ VaultError::AssetMismatch,
+        constraint = delegate_record.version == DelegateRecord::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub delegate_record: Account<'info, DelegateRecord>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn delegate_withdraw_handler(ctx: Context<DelegateWithdrawAsset>, amount: u64) -> Result<()> {
+    require!(amount > 0, VaultError::ZeroAmount);
+
+    let clock = Clock::get()?;
+    let rec = &mut ctx.accounts.delegate_record;
+    let new_used = rec.check_draw(amount, clock.unix_timestamp)?;
+
+    let vault = &mut ctx.accounts.vault_state;
+    require!(!vault.locked, VaultError::VaultLocked);
+    vault.require_not_paused(PAUSE_DELEGATE_WITHDRAWALS)?;
+    let asset = &mut ctx.accounts.asset;
+    if !rec.exempt_from_daily_limit {
+        asset.charge_daily_limit(amount, clock.unix_timestamp)?;
+    }
+    require!(
+        ctx.accounts.asset_ata.amount >= amount,
+        VaultError::InsufficientFunds
+    );
+
+    vault.locked = true;
+    rec.used = new_used;
+
+    let received = transfer_from_vault(
+        vault,
+        &ctx.accounts.asset_ata,
+        &mut ctx.accounts.delegate_ata,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+
+    asset.total_withdrawn = asset
+        .total_withdrawn
+        .checked_add(received)
+        .ok_or(VaultError::Overflow)?;
+    vault.locked = false;
+
+    emit!(WithdrawMade {
+        vault: vault.key(),
+        mint: asset.mint,
+        recipient: ctx.accounts.delegate_signer.key(),
+        amount: received,
+        fee: amount.saturating_sub(received),
+        shares: 0,
+        by_delegate: true,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "[vault] asset delegate_withdraw delegate={} mint={} amount={}",
+        rec.delegate,
+        asset.mint,
+        amount
+    );
+    Ok(())
+}
@@ -1,22 +1,31 @@
-use crate::{errors::VaultError, events::VaultClosed, state::VaultState};
+use crate::{
+    errors::VaultError,
+    events::VaultClosed,
+    state::VaultState,
+    utils::{close_delegate_records, close_vault_ata},
+};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
+/// Any remaining `DelegateRecord`s are passed (writable) in `remaining_accounts`
+/// and closed along with the vault.
 #[derive(Accounts)]
 pub struct CloseVault<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
         close = owner,
-        seeds = [b"vault", owner.key().as_ref(), mint.key().as_ref()],
+        seeds = [b"vault", vault_state.creator.as_ref(), mint.key().as_ref()],
         bump  = vault_state.bump,
         has_one = owner @ VaultError::Unauthorised,
         has_one = mint  @ VaultError::Unauthorised,
         constraint = vault_state.vault_ata == vault_ata.key() @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
     )]
     pub vault_state: Account<'info, VaultState>,
 
@@ -24,33 +33,80 @@ pub struct CloseVault<'info> {
         mut,
         constraint = vault_ata.amount == 0 @ VaultError::VaultNotEmpty,
     )]
-    pub vault_ata: Account<'info, TokenAccount>,
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<CloseVault>) -> Result<()> {
-    let vault = &ctx.accounts.vault_state;
+pub fn handler<'info>(ctx: Context<'info, CloseVault<'info>>) -> Result<()> {
     let clock = Clock::get()?;
+    let owner_key = ctx.accounts.vault_state.owner;
 
-    let owner_key = vault.owner;
-    let mint_key = vault.mint;
-    let bump = vault.bump;
-    let seeds = &[b"vault", owner_key.as_ref(), mint_key.as_ref(), &[bump]];
-    let signer = &[&seeds[..]];
+    // Orphaned records would come back to life if the vault were re-created
+    let owner = ctx.accounts.owner.to_account_info();
+    close_delegate_records(
+        &mut ctx.accounts.vault_state,
+        ctx.remaining_accounts,
+        &owner,
+        None,
+    )?;
+    require!(
+        ctx.accounts.vault_state.delegate_count == 0,
+        VaultError::DelegatesOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.stream_count == 0,
+        VaultError::StreamsOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.tranche_count == 0,
+        VaultError::TranchesOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.asset_count == 0,
+        VaultError::AssetsOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.role_count == 0,
+        VaultError::RolesOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.depositor_count == 0,
+        VaultError::DepositorsOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.policy_rule_count == 0,
+        VaultError::PolicyOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.withdraw_tier_count == 0,
+        VaultError::WithdrawTiersOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.pending_withdrawal_count == 0,
+        VaultError::PendingWithdrawalsOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.veto_key_count == 0,
+        VaultError::VetoKeysOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.recovery_guardian_count == 0,
+        VaultError::RecoveryOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.allocated == 0 && ctx.accounts.vault_state.strategy_value == 0,
+        VaultError::StrategyFundsOutstanding
+    );
 
     // Close the ATA and return rent to owner
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.key(),
-        CloseAccount {
-            account: ctx.accounts.vault_ata.to_account_info(),
-            destination: ctx.accounts.owner.to_account_info(),
-            authority: ctx.accounts.vault_state.to_account_info(),
-        },
-        signer,
-    );
-    token::close_account(cpi_ctx)?;
+    close_vault_ata(
+        &ctx.accounts.vault_state,
+        &ctx.accounts.vault_ata,
+        ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.token_program,
+    )?;
 
     emit!(VaultClosed {
         vault: ctx.accounts.vault_state.key(),
@@ -0,0 +1,126 @@
+use crate::{
+    errors::VaultError,
+    events::{ConfigChangeQueued, ConfigChanged},
+    state::{Role, VaultState, CONFIG_TIMELOCK_SECONDS, ROLE_CONFIG_ADMIN},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_CONFIG_ADMIN) @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// `authority`'s role on this vault; not needed when it is the owner
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+}
+
+fn emit_changed(vault: &VaultState, key: Pubkey, old_max: u64, old_daily: u64, now: i64) {
+    emit!(ConfigChanged {
+        vault: key,
+        old_max_deposit: old_max,
+        new_max_deposit: vault.max_deposit,
+        old_daily_withdraw_limit: old_daily,
+        new_daily_withdraw_limit: vault.daily_withdraw_limit,
+        timestamp: now,
+    });
+    msg!(
+        "[vault] config max_deposit={} daily_withdraw_limit={}",
+        vault.max_deposit,
+        vault.daily_withdraw_limit
+    );
+}
+
+/// Tightened limits apply at once; loosened ones are queued for
+/// `CONFIG_TIMELOCK_SECONDS`. Any call replaces a previously queued change,
+/// so re-submitting the current limits cancels it.
+pub fn update_handler(
+    ctx: Context<UpdateConfig>,
+    max_deposit: u64,
+    daily_withdraw_limit: u64,
+) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    let key = ctx.accounts.vault_state.key();
+    let vault = &mut ctx.accounts.vault_state;
+    let clock = Clock::get()?;
+
+    let old_max = vault.max_deposit;
+    let old_daily = vault.daily_withdraw_limit;
+    let loosen_max = VaultState::loosens_limit(old_max, max_deposit);
+    let loosen_daily = VaultState::loosens_limit(old_daily, daily_withdraw_limit);
+
+    if !loosen_max {
+        vault.max_deposit = max_deposit;
+    }
+    if !loosen_daily {
+        vault.daily_withdraw_limit = daily_withdraw_limit;
+    }
+    if vault.max_deposit != old_max || vault.daily_withdraw_limit != old_daily {
+        emit_changed(vault, key, old_max, old_daily, clock.unix_timestamp);
+    }
+
+    if loosen_max || loosen_daily {
+        let effective_at = clock
+            .unix_timestamp
+            .checked_add(CONFIG_TIMELOCK_SECONDS)
+            .ok_or(VaultError::Overflow)?;
+        vault.pending_max_deposit = max_deposit;
+        vault.pending_daily_withdraw_limit = daily_withdraw_limit;
+        vault.config_effective_at = effective_at;
+
+        emit!(ConfigChangeQueued {
+            vault: key,
+            max_deposit,
+            daily_withdraw_limit,
+            effective_at,
+        });
+        msg!("[vault] config change queued effective_at={}", effective_at);
+    } else {
+        vault.pending_max_deposit = 0;
+        vault.pending_daily_withdraw_limit = 0;
+        vault.config_effective_at = 0;
+    }
+    Ok(())
+}
+
+pub fn apply_handler(ctx: Context<UpdateConfig>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    let key = ctx.accounts.vault_state.key();
+    let vault = &mut ctx.accounts.vault_state;
+    let clock = Clock::get()?;
+
+    require!(vault.config_effective_at != 0, VaultError::NoPendingConfig);
+    require!(
+        clock.unix_timestamp >= vault.config_effective_at,
+        VaultError::ConfigTimelockActive
+    );
+
+    let old_max = vault.max_deposit;
+    let old_daily = vault.daily_withdraw_limit;
+    vault.max_deposit = vault.pending_max_deposit;
+    vault.daily_withdraw_limit = vault.pending_daily_withdraw_limit;
+    vault.pending_max_deposit = 0;
+    vault.pending_daily_withdraw_limit = 0;
+    vault.config_effective_at = 0;
+
+    emit_changed(vault, key, old_max, old_daily, clock.unix_timestamp);
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use crate::{
+    errors::VaultError,
+    events::{CosignerChangeQueued, CosignerChanged},
+    state::{VaultState, CONFIG_TIMELOCK_SECONDS},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetCosigner<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+fn apply(vault: &mut VaultState, key: Pubkey, cosigner: Pubkey, threshold: u64) {
+    let old_cosigner = vault.cosigner;
+    let old_threshold = vault.cosign_threshold;
+    vault.cosigner = cosigner;
+    vault.cosign_threshold = threshold;
+
+    emit!(CosignerChanged {
+        vault: key,
+        old_cosigner,
+        new_cosigner: cosigner,
+        old_threshold,
+        new_threshold: threshold,
+    });
+    msg!(
+        "[vault] cosigner={} cosign_threshold={}",
+        cosigner,
+        threshold
+    );
+}
+
+/// Adding a co-signer or lowering the threshold applies at once; removing or
+/// replacing the co-signer, or raising the threshold, is queued for
+/// `CONFIG_TIMELOCK_SECONDS`. Any call replaces a previously queued change.
+pub fn set_handler(ctx: Context<SetCosigner>, cosigner: Pubkey, threshold: u64) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    let key = ctx.accounts.vault_state.key();
+    let vault = &mut ctx.accounts.vault_state;
+
+    if !vault.loosens_cosigner(cosigner, threshold) {
+        vault.pending_cosigner = Pubkey::default();
+        vault.pending_cosign_threshold = 0;
+        vault.cosigner_effective_at = 0;
+        apply(vault, key, cosigner, threshold);
+        return Ok(());
+    }
+
+    let effective_at = Clock::get()?
+        .unix_timestamp
+        .checked_add(CONFIG_TIMELOCK_SECONDS)
+        .ok_or(VaultError::Overflow)?;
+    vault.pending_cosigner = cosigner;
+    vault.pending_cosign_threshold = threshold;
+    vault.cosigner_effective_at = effective_at;
+
+    emit!(CosignerChangeQueued {
+        vault: key,
+        cosigner,
+        threshold,
+        effective_at,
+    });
+    msg!(
+        "[vault] cosigner change queued effective_at={}",
+        effective_at
+    );
+    Ok(())
+}
+
+pub fn apply_handler(ctx: Context<SetCosigner>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    let key = ctx.accounts.vault_state.key();
+    let vault = &mut ctx.accounts.vault_state;
+
+    require!(
+        vault.cosigner_effective_at != 0,
+        VaultError::NoPendingConfig
+    );
+    require!(
+        Clock::get()?.unix_timestamp >= vault.cosigner_effective_at,
+        VaultError::ConfigTimelockActive
+    );
+
+    let (cosigner, threshold) = (vault.pending_cosigner, vault.pending_cosign_threshold);
+    vault.pending_cosigner = Pubkey::default();
+    vault.pending_cosign_threshold = 0;
+    vault.cosigner_effective_at = 0;
+    apply(vault, key, cosigner, threshold);
+    Ok(())
+}
@@ -1,35 +1,49 @@
 use crate::{
     errors::VaultError,
-    events::{DelegateAdded, DelegateRemoved, WithdrawMade},
-    state::{DelegateRecord, VaultState},
+    events::{DelegateAdded, DelegateRemoved, DelegateUpdated, WithdrawMade},
+    policy::{check_outflow, Outflow},
+    state::{
+        DelegateRecord, DelegateUpdate, Policy, Role, VaultAsset, VaultState,
+        PAUSE_DELEGATE_WITHDRAWALS, ROLE_DELEGATE_MANAGER,
+    },
+    utils::transfer_from_vault,
 };
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 // ─── AddDelegate ─────────────────────────────────────────────────────────────
 
 #[derive(Accounts)]
-#[instruction(allowance: u64, expires_at: i64)]
+#[instruction(allowance: u64, expires_at: i64, period: i64, max_periods: u32)]
 pub struct AddDelegate<'info> {
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub authority: Signer<'info>,
 
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
-        seeds = [b"vault", owner.key().as_ref(), mint.key().as_ref()],
+        seeds = [b"vault", vault_state.creator.as_ref(), mint.key().as_ref()],
         bump  = vault_state.bump,
-        has_one = owner @ VaultError::Unauthorised,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_DELEGATE_MANAGER) @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
     )]
     pub vault_state: Account<'info, VaultState>,
 
+    /// `authority`'s role on this vault; not needed when it is the owner
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+
     /// CHECK: arbitrary pubkey we're granting access to
     pub delegate: UncheckedAccount<'info>,
 
     #[account(
         init,
-        payer = owner,
+        payer = authority,
         space = DelegateRecord::LEN,
         seeds = [b"delegate", vault_state.key().as_ref(), delegate.key().as_ref()],
         bump,
@@ -39,8 +53,56 @@ pub struct AddDelegate<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn add_handler(ctx: Context<AddDelegate>, allowance: u64, expires_at: i64) -> Result<()> {
+pub fn add_handler(
+    ctx: Context<AddDelegate>,
+    allowance: u64,
+    expires_at: i64,
+    period: i64,
+    max_periods: u32,
+) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    let mint = ctx.accounts.vault_state.mint;
+    grant(
+        &mut ctx.accounts.delegate_record,
+        &mut ctx.accounts.vault_state,
+        ctx.accounts.delegate.key(),
+        mint,
+        allowance,
+        expires_at,
+        period,
+        max_periods,
+        ctx.bumps.delegate_record,
+    )
+}
+
+/// Fill a freshly initialised `DelegateRecord` and count it on the vault —
+/// shared by `add_delegate`, `add_asset_delegate` and the multisig
+/// `AddDelegate` proposal. Refused in queued mode, where a new delegate
+/// would be an instant way around the delay tiers.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn grant(
+    rec: &mut DelegateRecord,
+    vault_state: &mut Account<VaultState>,
+    delegate: Pubkey,
+    mint: Pubkey,
+    allowance: u64,
+    expires_at: i64,
+    period: i64,
+    max_periods: u32,
+    bump: u8,
+) -> Result<()> {
+    require!(
+        vault_state.withdraw_tier_count == 0,
+        VaultError::WithdrawQueueRequired
+    );
     require!(allowance > 0, VaultError::ZeroAmount);
+    require!(
+        period >= 0 && (max_periods == 0 || period > 0),
+        VaultError::InvalidDelegatePeriod
+    );
 
     let clock = Clock::get()?;
     require!(
@@ -48,25 +110,150 @@ pub fn add_handler(ctx: Context<AddDelegate>, allowance: u64, expires_at: i64) -
         VaultError::DelegateExpired
     );
 
-    let rec = &mut ctx.accounts.delegate_record;
-    rec.vault = ctx.accounts.vault_state.key();
-    rec.delegate = ctx.accounts.delegate.key();
+    let vault = vault_state.key();
+    vault_state.delegate_count = vault_state
+        .delegate_count
+        .checked_add(1)
+        .ok_or(VaultError::Overflow)?;
+
+    rec.vault = vault;
+    rec.delegate = delegate;
     rec.allowance = allowance;
     rec.used = 0;
     rec.expires_at = expires_at;
-    rec.bump = ctx.bumps.delegate_record;
+    rec.bump = bump;
+    rec.period = period;
+    rec.max_periods = max_periods;
+    rec.period_index = 0;
+    rec.period_start = clock.unix_timestamp;
+    rec.not_before = 0;
+    rec.paused = false;
+    rec.exempt_from_daily_limit = false;
+    rec.mint = mint;
+    rec.version = DelegateRecord::VERSION;
 
     emit!(DelegateAdded {
-        vault: ctx.accounts.vault_state.key(),
-        delegate: rec.delegate,
+        vault,
+        delegate,
+        mint,
         allowance,
         expires_at,
+        period,
+        max_periods,
+    });
+
+    msg!(
+        "[vault] delegate added={} allowance={} period={}",
+        delegate,
+        allowance,
+        period
+    );
+    Ok(())
+}
+
+// ─── UpdateDelegate ───────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct UpdateDelegate<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_DELEGATE_MANAGER) @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// `authority`'s role on this vault; not needed when it is the owner
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    #[account(
+        mut,
+        seeds = [b"delegate", vault_state.key().as_ref(), delegate_record.delegate.as_ref(), delegate_record.mint_seed(&vault_state.mint)],
+        bump  = delegate_record.bump,
+        constraint = delegate_record.vault == vault_state.key() @ VaultError::Unauthorised,
+        constraint = delegate_record.version == DelegateRecord::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub delegate_record: Account<'info, DelegateRecord>,
+}
+
+pub fn update_handler(ctx: Context<UpdateDelegate>, update: DelegateUpdate) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    let queued = ctx.accounts.vault_state.withdraw_tier_count > 0;
+    amend(&mut ctx.accounts.delegate_record, update, queued)
+}
+
+/// Apply `update` to an existing `DelegateRecord`, keeping `used` — shared by
+/// `update_delegate` and the multisig `UpdateDelegate` proposal. When
+/// `queued`, the update may only rein the delegate in.
+pub(crate) fn amend(rec: &mut DelegateRecord, update: DelegateUpdate, queued: bool) -> Result<()> {
+    let clock = Clock::get()?;
+
+    let allowance = update.allowance.unwrap_or(rec.allowance);
+    let expires_at = update.expires_at.unwrap_or(rec.expires_at);
+    let not_before = update.not_before.unwrap_or(rec.not_before);
+    let paused = update.paused.unwrap_or(rec.paused);
+    let exempt = update
+        .exempt_from_daily_limit
+        .unwrap_or(rec.exempt_from_daily_limit);
+
+    require!(allowance > 0, VaultError::ZeroAmount);
+    require!(allowance >= rec.used, VaultError::AllowanceBelowUsed);
+    if update.expires_at.is_some() {
+        require!(
+            expires_at > clock.unix_timestamp,
+            VaultError::DelegateExpired
+        );
+    }
+    require!(not_before < expires_at, VaultError::InvalidDelegateWindow);
+    if queued {
+        require!(
+            allowance <= rec.allowance
+                && expires_at <= rec.expires_at
+                && not_before >= rec.not_before
+                && (paused || !rec.paused)
+                && (!exempt || rec.exempt_from_daily_limit),
+            VaultError::WithdrawQueueRequired
+        );
+    }
+
+    emit!(DelegateUpdated {
+        vault: rec.vault,
+        delegate: rec.delegate,
+        old_allowance: rec.allowance,
+        new_allowance: allowance,
+        old_expires_at: rec.expires_at,
+        new_expires_at: expires_at,
+        old_not_before: rec.not_before,
+        new_not_before: not_before,
+        old_paused: rec.paused,
+        new_paused: paused,
+        old_exempt_from_daily_limit: rec.exempt_from_daily_limit,
+        new_exempt_from_daily_limit: exempt,
     });
 
+    rec.allowance = allowance;
+    rec.expires_at = expires_at;
+    rec.not_before = not_before;
+    rec.paused = paused;
+    rec.exempt_from_daily_limit = exempt;
+
     msg!(
-        "[vault] delegate added={} allowance={}",
+        "[vault] delegate updated={} allowance={} expires_at={} paused={}",
         rec.delegate,
-        allowance
+        allowance,
+        expires_at,
+        paused
     );
     Ok(())
 }
@@ -76,33 +263,66 @@ pub fn add_handler(ctx: Context<AddDelegate>, allowance: u64, expires_at: i64) -
 #[derive(Accounts)]
 pub struct RemoveDelegate<'info> {
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub authority: Signer<'info>,
 
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
-        seeds = [b"vault", owner.key().as_ref(), mint.key().as_ref()],
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), mint.key().as_ref()],
         bump  = vault_state.bump,
-        has_one = owner @ VaultError::Unauthorised,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_DELEGATE_MANAGER) @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
     )]
     pub vault_state: Account<'info, VaultState>,
 
+    /// CHECK: rent destination, validated against `vault_state.owner`
+    #[account(mut, address = vault_state.owner @ VaultError::Unauthorised)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// `authority`'s role on this vault; not needed when it is the owner
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+
     /// CHECK: the delegate being removed
     pub delegate: UncheckedAccount<'info>,
 
     #[account(
         mut,
         close = owner,
-        seeds = [b"delegate", vault_state.key().as_ref(), delegate.key().as_ref()],
+        seeds = [b"delegate", vault_state.key().as_ref(), delegate.key().as_ref(), delegate_record.mint_seed(&vault_state.mint)],
         bump  = delegate_record.bump,
         constraint = delegate_record.vault == vault_state.key() @ VaultError::Unauthorised,
+        constraint = delegate_record.version == DelegateRecord::VERSION @ VaultError::AccountNotMigrated,
     )]
     pub delegate_record: Account<'info, DelegateRecord>,
 
+    /// The record's `VaultAsset`; required when its allowance is in an asset's mint
+    #[account(
+        mut,
+        seeds = [b"asset", vault_state.key().as_ref(), delegate_record.mint.as_ref()],
+        bump  = asset.bump,
+    )]
+    pub asset: Option<Account<'info, VaultAsset>>,
+
     pub system_program: Program<'info, System>,
 }
 
 pub fn remove_handler(ctx: Context<RemoveDelegate>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    release(
+        &mut ctx.accounts.vault_state,
+        ctx.accounts.asset.as_deref_mut(),
+        &ctx.accounts.delegate_record,
+    )?;
+
     emit!(DelegateRemoved {
         vault: ctx.accounts.vault_state.key(),
         delegate: ctx.accounts.delegate.key(),
@@ -111,6 +331,87 @@ pub fn remove_handler(ctx: Context<RemoveDelegate>) -> Result<()> {
     Ok(())
 }
 
+/// Uncount a `DelegateRecord` that is being closed — shared by
+/// `remove_delegate`, `renounce_delegate`, `prune_delegate` and the multisig
+/// `RemoveDelegate` proposal. A record in an asset's mint is also uncounted
+/// on that `VaultAsset`, which must then be passed.
+pub(crate) fn release(
+    vault_state: &mut VaultState,
+    asset: Option<&mut VaultAsset>,
+    rec: &DelegateRecord,
+) -> Result<()> {
+    vault_state.delegate_count = vault_state
+        .delegate_count
+        .checked_sub(1)
+        .ok_or(VaultError::Overflow)?;
+
+    if rec.mint != vault_state.mint {
+        let asset = asset.ok_or(VaultError::AssetMismatch)?;
+        asset.delegate_count = asset
+            .delegate_count
+            .checked_sub(1)
+            .ok_or(VaultError::Overflow)?;
+    }
+    Ok(())
+}
+
+// ─── RenounceDelegate ────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct RenounceDelegate<'info> {
+    pub delegate_signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: paid the record's rent, so it gets it back; validated against `vault_state.owner`
+    #[account(mut, address = vault_state.owner @ VaultError::Unauthorised)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"delegate", vault_state.key().as_ref(), delegate_signer.key().as_ref(), delegate_record.mint_seed(&vault_state.mint)],
+        bump  = delegate_record.bump,
+        constraint = delegate_record.delegate == delegate_signer.key() @ VaultError::Unauthorised,
+        constraint = delegate_record.vault    == vault_state.key()     @ VaultError::Unauthorised,
+        constraint = delegate_record.version == DelegateRecord::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub delegate_record: Account<'info, DelegateRecord>,
+
+    /// The record's `VaultAsset`; required when its allowance is in an asset's mint
+    #[account(
+        mut,
+        seeds = [b"asset", vault_state.key().as_ref(), delegate_record.mint.as_ref()],
+        bump  = asset.bump,
+    )]
+    pub asset: Option<Account<'info, VaultAsset>>,
+}
+
+/// Delegate gives up its own record.
+pub fn renounce_handler(ctx: Context<RenounceDelegate>) -> Result<()> {
+    release(
+        &mut ctx.accounts.vault_state,
+        ctx.accounts.asset.as_deref_mut(),
+        &ctx.accounts.delegate_record,
+    )?;
+
+    emit!(DelegateRemoved {
+        vault: ctx.accounts.vault_state.key(),
+        delegate: ctx.accounts.delegate_signer.key(),
+    });
+    msg!(
+        "[vault] delegate renounced={}",
+        ctx.accounts.delegate_signer.key()
+    );
+    Ok(())
+}
+
 // ─── DelegateWithdraw ─────────────────────────────────────────────────────────
 
 #[derive(Accounts)]
@@ -118,16 +419,15 @@ pub struct DelegateWithdraw<'info> {
     /// Must be the exact delegate pubkey stored in the record
     pub delegate_signer: Signer<'info>,
 
-    pub mint: Account<'info, Mint>,
-
-    /// CHECK: vault owner — used only in seed derivation, validated via has_one
-    pub owner: UncheckedAccount<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
-        seeds = [b"vault", owner.key().as_ref(), mint.key().as_ref()],
+        seeds = [b"vault", vault_state.creator.as_ref(), mint.key().as_ref()],
         bump  = vault_state.bump,
         has_one = mint @ VaultError::Unauthorised,
+        constraint = !vault_state.pooled() @ VaultError::PooledVault,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
     )]
     pub vault_state: Account<'info, VaultState>,
 
@@ -135,14 +435,14 @@ pub struct DelegateWithdraw<'info> {
         mut,
         address = vault_state.vault_ata @ VaultError::Unauthorised,
     )]
-    pub vault_ata: Account<'info, TokenAccount>,
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         constraint = delegate_ata.owner == delegate_signer.key() @ VaultError::Unauthorised,
         constraint = delegate_ata.mint  == mint.key()            @ VaultError::Unauthorised,
     )]
-    pub delegate_ata: Account<'info, TokenAccount>,
+    pub delegate_ata: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
@@ -150,64 +450,71 @@ pub struct DelegateWithdraw<'info> {
         bump  = delegate_record.bump,
         constraint = delegate_record.delegate == delegate_signer.key() @ VaultError::Unauthorised,
         constraint = delegate_record.vault    == vault_state.key()     @ VaultError::Unauthorised,
+        constraint = delegate_record.mint     == mint.key()            @ VaultError::AssetMismatch,
+        constraint = delegate_record.version == DelegateRecord::VERSION @ VaultError::AccountNotMigrated,
     )]
     pub delegate_record: Account<'info, DelegateRecord>,
 
-    pub token_program: Program<'info, Token>,
+    /// The vault's `Policy`; required while it has rules
+    #[account(
+        mut,
+        seeds = [b"policy", vault_state.key().as_ref()],
+        bump  = policy.bump,
+    )]
+    pub policy: Option<Account<'info, Policy>>,
+
+    /// The vault's co-signer; required above `cosign_threshold`
+    #[account(address = vault_state.cosigner @ VaultError::Unauthorised)]
+    pub cosigner: Option<Signer<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn withdraw_handler(ctx: Context<DelegateWithdraw>, amount: u64) -> Result<()> {
-    require!(amount > 0, VaultError::ZeroAmount);
-
     let clock = Clock::get()?;
     let rec = &mut ctx.accounts.delegate_record;
-
-    require!(
-        clock.unix_timestamp < rec.expires_at,
-        VaultError::DelegateExpired
-    );
-
-    let new_used = rec.used.checked_add(amount).ok_or(VaultError::Overflow)?;
-    require!(new_used <= rec.allowance, VaultError::AllowanceExceeded);
-
-    let vault_state_ai = ctx.accounts.vault_state.to_account_info();
     let vault = &mut ctx.accounts.vault_state;
     require!(!vault.locked, VaultError::VaultLocked);
-    require!(
-        ctx.accounts.vault_ata.amount >= amount,
-        VaultError::InsufficientFunds
-    );
+    vault.require_not_paused(PAUSE_DELEGATE_WITHDRAWALS)?;
+
+    check_outflow(
+        vault,
+        ctx.accounts.policy.as_deref_mut(),
+        Some(rec),
+        &Outflow {
+            amount,
+            recipient: ctx.accounts.delegate_signer.key(),
+            now: clock.unix_timestamp,
+            cosigned: ctx.accounts.cosigner.is_some(),
+            vetted: false,
+        },
+    )?;
+    vault.require_available(ctx.accounts.vault_ata.amount, amount)?;
 
     vault.locked = true;
-    rec.used = new_used;
-
-    let owner_key = vault.owner;
-    let mint_key = vault.mint;
-    let bump = vault.bump;
-    let seeds = &[b"vault", owner_key.as_ref(), mint_key.as_ref(), &[bump]];
-    let signer = &[&seeds[..]];
-
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.key(),
-        Transfer {
-            from: ctx.accounts.vault_ata.to_account_info(),
-            to: ctx.accounts.delegate_ata.to_account_info(),
-            authority: vault_state_ai,
-        },
-        signer,
-    );
-    token::transfer(cpi_ctx, amount)?;
+
+    let received = transfer_from_vault(
+        vault,
+        &ctx.accounts.vault_ata,
+        &mut ctx.accounts.delegate_ata,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
 
     vault.total_withdrawn = vault
         .total_withdrawn
-        .checked_add(amount)
+        .checked_add(received)
         .ok_or(VaultError::Overflow)?;
     vault.locked = false;
 
     emit!(WithdrawMade {
         vault: vault.key(),
+        mint: vault.mint,
         recipient: ctx.accounts.delegate_signer.key(),
-        amount,
+        amount: received,
+        fee: amount.saturating_sub(received),
+        shares: 0,
         by_delegate: true,
         timestamp: clock.unix_timestamp,
     });
@@ -1,45 +1,123 @@
-use crate::{errors::VaultError, events::DepositMade, state::VaultState};
+use crate::{
+    errors::VaultError,
+    events::{DepositMade, TrancheCreated},
+    state::{
+        DepositorRecord, Role, Tranche, VaultState, MAX_LOCKUP_SECONDS, PAUSE_DEPOSITS,
+        ROLE_DEPOSITOR,
+    },
+    utils::mint_shares,
+};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
 
 #[derive(Accounts)]
 pub struct Deposit<'info> {
+    /// Owner, or any third party permitted by `vault_state.deposit_mode`
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub depositor: Signer<'info>,
 
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
-        seeds = [b"vault", owner.key().as_ref(), mint.key().as_ref()],
+        seeds = [b"vault", vault_state.creator.as_ref(), mint.key().as_ref()],
         bump  = vault_state.bump,
-        has_one = owner @ VaultError::Unauthorised,
-        has_one = mint   @ VaultError::Unauthorised,
+        has_one = mint @ VaultError::Unauthorised,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
     )]
     pub vault_state: Account<'info, VaultState>,
 
-    /// Source — must be owned by the owner signer
+    /// Source — must be owned by the depositor signer
     #[account(
         mut,
-        constraint = owner_ata.owner == owner.key()   @ VaultError::Unauthorised,
-        constraint = owner_ata.mint  == mint.key()    @ VaultError::Unauthorised,
+        constraint = depositor_ata.owner == depositor.key() @ VaultError::Unauthorised,
+        constraint = depositor_ata.mint  == mint.key()      @ VaultError::Unauthorised,
     )]
-    pub owner_ata: Account<'info, TokenAccount>,
+    pub depositor_ata: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         address = vault_state.vault_ata @ VaultError::Unauthorised,
     )]
-    pub vault_ata: Account<'info, TokenAccount>,
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    /// Required only for non-owner deposits in `DepositMode::Allowlist`
+    #[account(
+        seeds = [b"depositor", vault_state.key().as_ref(), depositor.key().as_ref()],
+        bump  = depositor_record.bump,
+    )]
+    pub depositor_record: Option<Account<'info, DepositorRecord>>,
+
+    /// Depositor's role; lets it deposit whatever the `deposit_mode`
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), depositor.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    /// Lock-up tranche — pass exactly when `unlock_at` is set
+    #[account(
+        init,
+        payer = depositor,
+        space = Tranche::LEN,
+        seeds = [b"tranche", vault_state.key().as_ref(), vault_state.next_tranche_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub tranche: Option<Account<'info, Tranche>>,
+
+    /// Vault share mint — pass exactly when the vault is pooled
+    #[account(mut, address = vault_state.share_mint @ VaultError::Unauthorised)]
+    pub share_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Receives the minted shares — pass exactly when the vault is pooled
+    #[account(
+        mut,
+        constraint = depositor_shares.mint == vault_state.share_mint @ VaultError::Unauthorised,
+    )]
+    pub depositor_shares: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+pub fn handler(ctx: Context<Deposit>, amount: u64, unlock_at: Option<i64>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.depositor.key(), Clock::get()?.unix_timestamp);
+
     require!(amount > 0, VaultError::ZeroAmount);
+    require!(
+        unlock_at.is_some() == ctx.accounts.tranche.is_some(),
+        VaultError::TrancheAccountMismatch
+    );
+    let pooled = ctx.accounts.vault_state.pooled();
+    require!(
+        ctx.accounts.share_mint.is_some() == pooled
+            && ctx.accounts.depositor_shares.is_some() == pooled,
+        VaultError::ShareAccountMismatch
+    );
+
+    let clock = Clock::get()?;
+    if let Some(unlock_at) = unlock_at {
+        // `enable_pool` refuses vaults with open tranches, so pooled ones never get any
+        require!(!pooled, VaultError::PooledVault);
+        require!(
+            unlock_at > clock.unix_timestamp
+                && unlock_at - clock.unix_timestamp <= MAX_LOCKUP_SECONDS,
+            VaultError::InvalidUnlockTime
+        );
+    }
 
     let vault = &mut ctx.accounts.vault_state;
     require!(!vault.locked, VaultError::VaultLocked);
+    vault.require_not_paused(PAUSE_DEPOSITS)?;
+
+    let depositor = ctx.accounts.depositor.key();
+    require!(
+        vault.authorized(&depositor, ctx.accounts.role.as_deref(), ROLE_DEPOSITOR)
+            || vault.may_deposit(depositor, ctx.accounts.depositor_record.is_some()),
+        VaultError::DepositorNotAllowed
+    );
 
     if vault.max_deposit > 0 {
         require!(amount <= vault.max_deposit, VaultError::DepositTooLarge);
@@ -48,34 +126,105 @@ pub fn handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
     // reentrancy lock
     vault.locked = true;
 
+    let balance_before = ctx.accounts.vault_ata.amount;
+
     let cpi_ctx = CpiContext::new(
         ctx.accounts.token_program.key(),
-        Transfer {
-            from: ctx.accounts.owner_ata.to_account_info(),
+        TransferChecked {
+            from: ctx.accounts.depositor_ata.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
             to: ctx.accounts.vault_ata.to_account_info(),
-            authority: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.depositor.to_account_info(),
         },
     );
-    token::transfer(cpi_ctx, amount)?;
+    token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+    // A transfer-fee mint withholds part of the transfer in the destination
+    // account, so credit only what the vault ATA actually gained.
+    ctx.accounts.vault_ata.reload()?;
+    let received = ctx
+        .accounts
+        .vault_ata
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(VaultError::Overflow)?;
 
     vault.total_deposited = vault
         .total_deposited
-        .checked_add(amount)
+        .checked_add(received)
         .ok_or(VaultError::Overflow)?;
+
+    // Price against the pool as it stood before this deposit
+    let mut shares = 0;
+    if let (Some(share_mint), Some(depositor_shares)) = (
+        ctx.accounts.share_mint.as_ref(),
+        ctx.accounts.depositor_shares.as_ref(),
+    ) {
+        shares = VaultState::shares_for_deposit(
+            received,
+            vault.pool_assets(balance_before)?,
+            share_mint.supply,
+        )?;
+        require!(shares > 0, VaultError::ZeroShares);
+        mint_shares(
+            vault,
+            share_mint,
+            depositor_shares,
+            &ctx.accounts.token_program,
+            shares,
+        )?;
+    }
     vault.locked = false;
 
-    let clock = Clock::get()?;
+    if let (Some(tranche), Some(unlock_at)) = (ctx.accounts.tranche.as_mut(), unlock_at) {
+        tranche.vault = vault.key();
+        tranche.depositor = depositor;
+        tranche.tranche_id = vault.next_tranche_id;
+        tranche.amount = received;
+        tranche.unlock_at = unlock_at;
+        tranche.bump = ctx
+            .bumps
+            .tranche
+            .ok_or(VaultError::TrancheAccountMismatch)?;
+
+        vault.next_tranche_id = vault
+            .next_tranche_id
+            .checked_add(1)
+            .ok_or(VaultError::Overflow)?;
+        vault.tranche_count = vault
+            .tranche_count
+            .checked_add(1)
+            .ok_or(VaultError::Overflow)?;
+        vault.locked_amount = vault
+            .locked_amount
+            .checked_add(received)
+            .ok_or(VaultError::Overflow)?;
+
+        emit!(TrancheCreated {
+            vault: tranche.vault,
+            tranche: tranche.key(),
+            depositor,
+            amount: received,
+            unlock_at,
+        });
+    }
+
     emit!(DepositMade {
         vault: vault.key(),
-        depositor: ctx.accounts.owner.key(),
-        amount,
+        mint: vault.mint,
+        depositor,
+        amount: received,
+        fee: amount.saturating_sub(received),
+        shares,
         total_deposited: vault.total_deposited,
         timestamp: clock.unix_timestamp,
     });
 
     msg!(
-        "[vault] deposit amount={} total_deposited={}",
+        "[vault] deposit amount={} received={} shares={} total_deposited={}",
         amount,
+        received,
+        shares,
         vault.total_deposited
     );
     Ok(())
@@ -0,0 +1,170 @@
+use crate::{
+    errors::VaultError,
+    events::{DepositModeChanged, DepositorAdded, DepositorRemoved},
+    state::{DepositMode, DepositorRecord, Role, VaultState, ROLE_CONFIG_ADMIN},
+};
+use anchor_lang::prelude::*;
+
+// ─── SetDepositMode ──────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct SetDepositMode<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_CONFIG_ADMIN) @ VaultError::Unauthorised,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// `authority`'s role on this vault; not needed when it is the owner
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+}
+
+pub fn set_mode_handler(ctx: Context<SetDepositMode>, mode: DepositMode) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    let vault = &mut ctx.accounts.vault_state;
+    let old_mode = vault.deposit_mode;
+    vault.deposit_mode = mode;
+
+    emit!(DepositModeChanged {
+        vault: vault.key(),
+        old_mode,
+        new_mode: mode,
+    });
+    msg!("[vault] deposit mode={:?}", mode);
+    Ok(())
+}
+
+// ─── AddDepositor ────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct AddDepositor<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_CONFIG_ADMIN) @ VaultError::Unauthorised,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// `authority`'s role on this vault; not needed when it is the owner
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    /// CHECK: arbitrary pubkey being allowlisted
+    pub depositor: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = DepositorRecord::LEN,
+        seeds = [b"depositor", vault_state.key().as_ref(), depositor.key().as_ref()],
+        bump,
+    )]
+    pub depositor_record: Account<'info, DepositorRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_handler(ctx: Context<AddDepositor>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    let vault = &mut ctx.accounts.vault_state;
+    vault.depositor_count = vault
+        .depositor_count
+        .checked_add(1)
+        .ok_or(VaultError::Overflow)?;
+
+    let rec = &mut ctx.accounts.depositor_record;
+    rec.vault = vault.key();
+    rec.depositor = ctx.accounts.depositor.key();
+    rec.bump = ctx.bumps.depositor_record;
+
+    emit!(DepositorAdded {
+        vault: rec.vault,
+        depositor: rec.depositor,
+    });
+    msg!("[vault] depositor added={}", rec.depositor);
+    Ok(())
+}
+
+// ─── RemoveDepositor ─────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct RemoveDepositor<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_CONFIG_ADMIN) @ VaultError::Unauthorised,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: rent destination, validated against `vault_state.owner`
+    #[account(mut, address = vault_state.owner @ VaultError::Unauthorised)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// `authority`'s role on this vault; not needed when it is the owner
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    /// CHECK: the depositor being removed
+    pub depositor: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"depositor", vault_state.key().as_ref(), depositor.key().as_ref()],
+        bump  = depositor_record.bump,
+        constraint = depositor_record.vault == vault_state.key() @ VaultError::Unauthorised,
+    )]
+    pub depositor_record: Account<'info, DepositorRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn remove_handler(ctx: Context<RemoveDepositor>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+    ctx.accounts.vault_state.depositor_count = ctx
+        .accounts
+        .vault_state
+        .depositor_count
+        .checked_sub(1)
+        .ok_or(VaultError::Overflow)?;
+
+    emit!(DepositorRemoved {
+        vault: ctx.accounts.vault_state.key(),
+        depositor: ctx.accounts.depositor.key(),
+    });
+    msg!("[vault] depositor removed={}", ctx.accounts.depositor.key());
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use crate::{
+    errors::VaultError,
+    events::{GuardianSet, UnpauseRequested, VaultPaused, VaultUnpaused},
+    state::{Role, VaultState, GUARDIAN_UNPAUSE_DELAY, PAUSE_ALL, ROLE_PAUSER},
+};
+use anchor_lang::prelude::*;
+
+fn validate_flags(flags: u8) -> Result<()> {
+    require!(
+        flags != 0 && flags & !PAUSE_ALL == 0,
+        VaultError::InvalidPauseFlags
+    );
+    Ok(())
+}
+
+// ─── SetGuardian ─────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+/// Set or replace the guardian; `Pubkey::default()` removes it.
+pub fn set_handler(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    let vault = &mut ctx.accounts.vault_state;
+    vault.guardian = guardian;
+    // A request made by the previous guardian must not carry over
+    vault.pending_unpause = 0;
+    vault.unpause_available_at = 0;
+
+    emit!(GuardianSet {
+        vault: vault.key(),
+        guardian,
+    });
+    msg!("[vault] guardian set={}", guardian);
+    Ok(())
+}
+
+// ─── Pause ───────────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    /// Owner, pauser or guardian
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_PAUSER)
+            || (vault_state.guardian != Pubkey::default()
+                && authority.key() == vault_state.guardian) @ VaultError::Unauthorised,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// `authority`'s role on this vault; not needed for the owner or guardian
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+}
+
+pub fn pause_handler(ctx: Context<Pause>, flags: u8) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    validate_flags(flags)?;
+
+    let vault = &mut ctx.accounts.vault_state;
+    let clock = Clock::get()?;
+
+    vault.paused |= flags;
+    // Re-pausing withdraws any outstanding guardian unpause request for these bits
+    vault.pending_unpause &= !flags;
+
+    emit!(VaultPaused {
+        vault: vault.key(),
+        by: ctx.accounts.authority.key(),
+        flags,
+        paused: vault.paused,
+        timestamp: clock.unix_timestamp,
+    });
+    msg!("[vault] paused flags={} paused={}", flags, vault.paused);
+    Ok(())
+}
+
+// ─── RequestUnpause ──────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct RequestUnpause<'info> {
+    pub guardian: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.guardian != Pubkey::default() @ VaultError::Unauthorised,
+        has_one = guardian @ VaultError::Unauthorised,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+/// Guardian starts the `GUARDIAN_UNPAUSE_DELAY` countdown for `flags`.
+pub fn request_unpause_handler(ctx: Context<RequestUnpause>, flags: u8) -> Result<()> {
+    validate_flags(flags)?;
+
+    let vault = &mut ctx.accounts.vault_state;
+    let clock = Clock::get()?;
+
+    let available_at = clock
+        .unix_timestamp
+        .checked_add(GUARDIAN_UNPAUSE_DELAY)
+        .ok_or(VaultError::Overflow)?;
+    vault.pending_unpause = flags;
+    vault.unpause_available_at = available_at;
+
+    emit!(UnpauseRequested {
+        vault: vault.key(),
+        guardian: vault.guardian,
+        flags,
+        available_at,
+    });
+    msg!(
+        "[vault] unpause requested flags={} available_at={}",
+        flags,
+        available_at
+    );
+    Ok(())
+}
+
+// ─── Unpause ─────────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct Unpause<'info> {
+    /// Owner (immediate) or guardian (after `request_unpause` + delay)
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = authority.key() == vault_state.owner
+            || (vault_state.guardian != Pubkey::default()
+                && authority.key() == vault_state.guardian) @ VaultError::Unauthorised,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn unpause_handler(ctx: Context<Unpause>, flags: u8) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    validate_flags(flags)?;
+
+    let vault = &mut ctx.accounts.vault_state;
+    let clock = Clock::get()?;
+    let authority = ctx.accounts.authority.key();
+
+    if authority != vault.owner {
+        require!(
+            vault.pending_unpause & flags == flags,
+            VaultError::NoPendingUnpause
+        );
+        require!(
+            clock.unix_timestamp >= vault.unpause_available_at,
+            VaultError::UnpauseDelayActive
+        );
+    }
+
+    vault.paused &= !flags;
+    vault.pending_unpause &= !flags;
+    if vault.pending_unpause == 0 {
+        vault.unpause_available_at = 0;
+    }
+
+    emit!(VaultUnpaused {
+        vault: vault.key(),
+        by: authority,
+        flags,
+        paused: vault.paused,
+        timestamp: clock.unix_timestamp,
+    });
+    msg!("[vault] unpaused flags={} paused={}", flags, vault.paused);
+    Ok(())
+}
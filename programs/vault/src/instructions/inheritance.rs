@@ -0,0 +1,210 @@
+use crate::{
+    errors::VaultError,
+    events::{BeneficiarySet, InheritanceClaimed, OwnerHeartbeat},
+    policy::{check_outflow, Outflow},
+    state::{Policy, VaultState, PAUSE_WITHDRAWALS},
+    utils::transfer_from_vault,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+// ─── SetBeneficiary ──────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct SetBeneficiary<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+/// Set or replace the beneficiary; `Pubkey::default()` turns the dead-man
+/// switch off. Applies at once: the owner signing proves it is still around.
+pub fn set_handler(
+    ctx: Context<SetBeneficiary>,
+    beneficiary: Pubkey,
+    inactivity_period: i64,
+    claim_limit: u64,
+) -> Result<()> {
+    let off = beneficiary == Pubkey::default();
+    require!(
+        off || inactivity_period > 0,
+        VaultError::InvalidInactivityPeriod
+    );
+
+    let vault = &mut ctx.accounts.vault_state;
+    vault.heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+    vault.beneficiary = beneficiary;
+    vault.inactivity_period = if off { 0 } else { inactivity_period };
+    vault.claim_limit = if off { 0 } else { claim_limit };
+    vault.claimed_today = 0;
+    vault.claim_window_start = 0;
+
+    emit!(BeneficiarySet {
+        vault: vault.key(),
+        beneficiary,
+        inactivity_period: vault.inactivity_period,
+        claim_limit: vault.claim_limit,
+    });
+    msg!(
+        "[vault] beneficiary={} inactivity_period={} claim_limit={}",
+        beneficiary,
+        vault.inactivity_period,
+        vault.claim_limit
+    );
+    Ok(())
+}
+
+// ─── Ping ────────────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct Ping<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+/// Refresh the heartbeat without doing anything else.
+pub fn ping_handler(ctx: Context<Ping>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let vault = &mut ctx.accounts.vault_state;
+    vault.heartbeat(&ctx.accounts.owner.key(), now);
+
+    emit!(OwnerHeartbeat {
+        vault: vault.key(),
+        timestamp: now,
+    });
+    msg!("[vault] heartbeat at={}", now);
+    Ok(())
+}
+
+// ─── ClaimInheritance ────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct ClaimInheritance<'info> {
+    pub beneficiary: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), mint.key().as_ref()],
+        bump  = vault_state.bump,
+        has_one = beneficiary @ VaultError::Unauthorised,
+        has_one = mint  @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = !vault_state.pooled() @ VaultError::PooledVault,
+        // In queued mode, claims wait out the delay tiers via request_withdraw
+        constraint = vault_state.withdraw_tier_count == 0 @ VaultError::WithdrawQueueRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        address = vault_state.vault_ata @ VaultError::Unauthorised,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// Any token account of the vault's mint
+    #[account(
+        mut,
+        constraint = destination.mint == mint.key() @ VaultError::Unauthorised,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// The vault's `Policy`; required while it has rules
+    #[account(
+        mut,
+        seeds = [b"policy", vault_state.key().as_ref()],
+        bump  = policy.bump,
+    )]
+    pub policy: Option<Account<'info, Policy>>,
+
+    /// The vault's co-signer; required above `cosign_threshold`
+    #[account(address = vault_state.cosigner @ VaultError::Unauthorised)]
+    pub cosigner: Option<Signer<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Beneficiary draws `amount` once the owner has been silent for
+/// `inactivity_period`. Charged against both `daily_withdraw_limit` and
+/// `claim_limit`; an owner heartbeat stops further claims at once. Claims
+/// above the veto threshold or in queued mode go through `request_withdraw`.
+pub fn claim_handler(ctx: Context<ClaimInheritance>, amount: u64) -> Result<()> {
+    require!(amount > 0, VaultError::ZeroAmount);
+
+    let vault = &mut ctx.accounts.vault_state;
+    require!(!vault.locked, VaultError::VaultLocked);
+    vault.require_not_paused(PAUSE_WITHDRAWALS)?;
+
+    let clock = Clock::get()?;
+    require!(
+        vault.inheritance_claimable(clock.unix_timestamp),
+        VaultError::OwnerStillActive
+    );
+
+    check_outflow(
+        vault,
+        ctx.accounts.policy.as_deref_mut(),
+        None,
+        &Outflow {
+            amount,
+            recipient: ctx.accounts.destination.owner,
+            now: clock.unix_timestamp,
+            cosigned: ctx.accounts.cosigner.is_some(),
+            vetted: false,
+        },
+    )?;
+    vault.charge_claim_limit(amount, clock.unix_timestamp)?;
+    vault.require_available(ctx.accounts.vault_ata.amount, amount)?;
+
+    vault.locked = true;
+
+    let received = transfer_from_vault(
+        vault,
+        &ctx.accounts.vault_ata,
+        &mut ctx.accounts.destination,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+
+    vault.total_withdrawn = vault
+        .total_withdrawn
+        .checked_add(received)
+        .ok_or(VaultError::Overflow)?;
+    vault.locked = false;
+
+    emit!(InheritanceClaimed {
+        vault: vault.key(),
+        beneficiary: vault.beneficiary,
+        amount: received,
+        fee: amount.saturating_sub(received),
+        last_heartbeat: vault.last_heartbeat,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "[vault] inheritance claimed amount={} received={} claimed_today={}",
+        amount,
+        received,
+        vault.claimed_today
+    );
+    Ok(())
+}
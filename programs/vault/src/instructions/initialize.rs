@@ -2,7 +2,7 @@ use crate::{events::VaultInitialised, state::VaultState};
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
 #[derive(Accounts)]
@@ -10,7 +10,7 @@ pub struct Initialize<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
 
     /// PDA vault state (seeds validated by Anchor constraint)
     #[account(
@@ -28,10 +28,12 @@ pub struct Initialize<'info> {
         payer = owner,
         associated_token::mint = mint,
         associated_token::authority = vault_state,
+        associated_token::token_program = token_program,
     )]
-    pub vault_ata: Account<'info, TokenAccount>,
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
+    /// SPL Token or Token-2022 — whichever program owns `mint`
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
@@ -46,13 +48,16 @@ pub fn handler(
     let clock = Clock::get()?;
 
     vault.owner = ctx.accounts.owner.key();
+    vault.creator = vault.owner;
     vault.mint = ctx.accounts.mint.key();
     vault.vault_ata = ctx.accounts.vault_ata.key();
     vault.max_deposit = max_deposit;
     vault.daily_withdraw_limit = daily_withdraw_limit;
     vault.window_start = clock.unix_timestamp;
+    vault.last_heartbeat = clock.unix_timestamp;
     vault.bump = ctx.bumps.vault_state;
     vault.locked = false;
+    vault.version = VaultState::VERSION;
 
     emit!(VaultInitialised {
         owner: vault.owner,
@@ -0,0 +1,205 @@
+use crate::{
+    errors::VaultError,
+    events::AccountMigrated,
+    state::{DelegateRecord, VaultState},
+};
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Transfer},
+    Discriminator,
+};
+
+// ─── MigrateVault ────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    /// Pays the extra rent; anyone may migrate
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: may be in a layout `Account` cannot deserialize yet; the
+    /// discriminator is checked in the handler
+    #[account(mut, owner = crate::ID @ VaultError::Unauthorised)]
+    pub vault_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Grow `vault_state` to `VaultState::LEN` and bring it to the current version.
+pub fn vault_handler(ctx: Context<MigrateVault>) -> Result<()> {
+    let info = ctx.accounts.vault_state.to_account_info();
+    grow(
+        &info,
+        VaultState::DISCRIMINATOR,
+        VaultState::LEN,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+    )?;
+
+    let mut vault = VaultState::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    // Legacy accounts read back as version 0 from the zero-filled tail
+    let old_version = vault.version;
+    require!(
+        old_version < VaultState::VERSION,
+        VaultError::AlreadyMigrated
+    );
+
+    // Layouts from before `creator` existed were seeded by the owner, which
+    // cannot have changed since ownership transfer came with `creator`
+    if vault.creator == Pubkey::default() {
+        vault.creator = vault.owner;
+    }
+    let expected = Pubkey::create_program_address(
+        &[
+            b"vault",
+            vault.creator.as_ref(),
+            vault.mint.as_ref(),
+            &[vault.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| error!(VaultError::Unauthorised))?;
+    require_keys_eq!(expected, info.key(), VaultError::Unauthorised);
+
+    if old_version == 0 {
+        vault.legacy_delegates = true;
+    }
+    vault.version = VaultState::VERSION;
+    vault.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+    emit!(AccountMigrated {
+        account: info.key(),
+        old_version,
+        new_version: VaultState::VERSION,
+        len: VaultState::LEN as u64,
+    });
+    msg!(
+        "[vault] migrated vault {} v{} -> v{}",
+        info.key(),
+        old_version,
+        VaultState::VERSION
+    );
+    Ok(())
+}
+
+// ─── MigrateDelegate ─────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct MigrateDelegate<'info> {
+    /// Pays the extra rent; anyone may migrate
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: may be in a layout `Account` cannot deserialize yet; the
+    /// discriminator is checked in the handler
+    #[account(mut, owner = crate::ID @ VaultError::Unauthorised)]
+    pub delegate_record: UncheckedAccount<'info>,
+
+    /// The record's vault, migrated first
+    #[account(
+        mut,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Size of a `DelegateRecord` in the original layout, written before the
+/// vault counted its delegates.
+const BASELINE_DELEGATE_LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1;
+
+/// Grow `delegate_record` to `DelegateRecord::LEN` and bring it to the
+/// current version. Records from before multi-asset vaults get the vault's
+/// `mint`, and original-layout records are added to `delegate_count`.
+/// `close_vault` cannot have counted those, so they are only accepted by a
+/// vault that was itself migrated from before versioning, never by one
+/// re-created at the same address.
+pub fn delegate_handler(ctx: Context<MigrateDelegate>) -> Result<()> {
+    let info = ctx.accounts.delegate_record.to_account_info();
+    let old_len = info.data_len();
+    grow(
+        &info,
+        DelegateRecord::DISCRIMINATOR,
+        DelegateRecord::LEN,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+    )?;
+
+    let mut rec = DelegateRecord::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    // Legacy accounts read back as version 0 from the zero-filled tail
+    let old_version = rec.version;
+    require!(
+        old_version < DelegateRecord::VERSION,
+        VaultError::AlreadyMigrated
+    );
+
+    require_keys_eq!(
+        rec.vault,
+        ctx.accounts.vault_state.key(),
+        VaultError::Unauthorised
+    );
+    if rec.mint == Pubkey::default() {
+        rec.mint = ctx.accounts.vault_state.mint;
+    }
+    if old_len == BASELINE_DELEGATE_LEN {
+        let vault = &mut ctx.accounts.vault_state;
+        require!(vault.legacy_delegates, VaultError::StaleDelegateRecord);
+        vault.delegate_count = vault
+            .delegate_count
+            .checked_add(1)
+            .ok_or(VaultError::Overflow)?;
+    }
+
+    rec.version = DelegateRecord::VERSION;
+    rec.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+    emit!(AccountMigrated {
+        account: info.key(),
+        old_version,
+        new_version: DelegateRecord::VERSION,
+        len: DelegateRecord::LEN as u64,
+    });
+    msg!(
+        "[vault] migrated delegate record {} v{} -> v{}",
+        info.key(),
+        old_version,
+        DelegateRecord::VERSION
+    );
+    Ok(())
+}
+
+/// Check `info` holds a `discriminator` account, then realloc it (zero-filled)
+/// to `len` and top its rent up from `payer`.
+fn grow<'info>(
+    info: &AccountInfo<'info>,
+    discriminator: &[u8],
+    len: usize,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let old_len = info.data_len();
+    require!(
+        info.try_borrow_data()?.starts_with(discriminator),
+        ErrorCode::AccountDiscriminatorMismatch
+    );
+    require!(old_len <= len, VaultError::AlreadyMigrated);
+    if old_len == len {
+        return Ok(());
+    }
+
+    let rent = Rent::get()?.minimum_balance(len);
+    let top_up = rent.saturating_sub(info.lamports());
+    if top_up > 0 {
+        let cpi_ctx = CpiContext::new(
+            system_program.key(),
+            Transfer {
+                from: payer.to_account_info(),
+                to: info.clone(),
+            },
+        );
+        system_program::transfer(cpi_ctx, top_up)?;
+    }
+    info.resize(len)?;
+    Ok(())
+}
@@ -1,13 +1,51 @@
 #![allow(ambiguous_glob_reexports)]
 
+pub mod asset;
 pub mod close;
+pub mod config;
+pub mod cosigner;
 pub mod delegate;
 pub mod deposit;
+pub mod depositor;
+pub mod guardian;
+pub mod inheritance;
 pub mod initialize;
+pub mod migrate;
+pub mod multisig;
+pub mod ownership;
+pub mod pool;
+pub mod prune;
+pub mod queue;
+pub mod recovery;
+pub mod role;
+pub mod rule;
+pub mod strategy;
+pub mod stream;
+pub mod tranche;
+pub mod veto;
 pub mod withdraw;
 
+pub use asset::*;
 pub use close::*;
+pub use config::*;
+pub use cosigner::*;
 pub use delegate::*;
 pub use deposit::*;
+pub use depositor::*;
+pub use guardian::*;
+pub use inheritance::*;
 pub use initialize::*;
+pub use migrate::*;
+pub use multisig::*;
+pub use ownership::*;
+pub use pool::*;
+pub use prune::*;
+pub use queue::*;
+pub use recovery::*;
+pub use role::*;
+pub use rule::*;
+pub use strategy::*;
+pub use stream::*;
+pub use tranche::*;
+pub use veto::*;
 pub use withdraw::*;
@@ -0,0 +1,832 @@
+use crate::{
+    errors::VaultError,
+    events::{
+        DelegateRemoved, MultisigEnabled, ProposalApproved, ProposalCancelled, ProposalCreated,
+        ProposalExecuted, VaultClosed, WithdrawMade,
+    },
+    instructions::delegate,
+    policy::{check_outflow, Outflow},
+    state::{
+        DelegateRecord, Multisig, Policy, Proposal, ProposalAction, VaultAsset, VaultState,
+        MAX_MULTISIG_SIGNERS, PAUSE_WITHDRAWALS,
+    },
+    utils::{close_delegate_records, close_vault_ata, transfer_from_vault},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+// ─── EnableMultisig ──────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct EnableMultisig<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), mint.key().as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = Multisig::LEN,
+        seeds = [b"multisig", vault_state.key().as_ref()],
+        bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn enable_handler(
+    ctx: Context<EnableMultisig>,
+    signers: Vec<Pubkey>,
+    threshold: u8,
+) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    require!(
+        !signers.is_empty() && signers.len() <= MAX_MULTISIG_SIGNERS,
+        VaultError::InvalidSignerSet
+    );
+    for (i, s) in signers.iter().enumerate() {
+        require!(!signers[..i].contains(s), VaultError::InvalidSignerSet);
+    }
+    require!(
+        threshold > 0 && threshold as usize <= signers.len(),
+        VaultError::InvalidThreshold
+    );
+
+    let ms = &mut ctx.accounts.multisig;
+    ms.vault = ctx.accounts.vault_state.key();
+    ms.signers = signers.clone();
+    ms.threshold = threshold;
+    ms.proposal_count = 0;
+    ms.open_proposals = 0;
+    ms.generation = Clock::get()?.slot;
+    ms.bump = ctx.bumps.multisig;
+
+    ctx.accounts.vault_state.multisig = true;
+
+    emit!(MultisigEnabled {
+        vault: ms.vault,
+        signers,
+        threshold,
+    });
+
+    msg!(
+        "[vault] multisig enabled signers={} threshold={}",
+        ms.signers.len(),
+        threshold
+    );
+    Ok(())
+}
+
+// ─── CreateProposal ──────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig", vault_state.key().as_ref()],
+        bump  = multisig.bump,
+        constraint = multisig.signer_index(&proposer.key()).is_some() @ VaultError::NotMultisigSigner,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::LEN,
+        seeds = [
+            b"proposal",
+            vault_state.key().as_ref(),
+            multisig.proposal_count.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_handler(ctx: Context<CreateProposal>, action: ProposalAction) -> Result<()> {
+    match &action {
+        ProposalAction::Withdraw { amount, .. } => require!(*amount > 0, VaultError::ZeroAmount),
+        ProposalAction::AddDelegate {
+            allowance,
+            period,
+            max_periods,
+            ..
+        } => {
+            require!(*allowance > 0, VaultError::ZeroAmount);
+            require!(
+                *period >= 0 && (*max_periods == 0 || *period > 0),
+                VaultError::InvalidDelegatePeriod
+            );
+        }
+        ProposalAction::UpdateDelegate { update, .. } => {
+            require!(update.allowance != Some(0), VaultError::ZeroAmount)
+        }
+        ProposalAction::RemoveDelegate { .. } | ProposalAction::CloseVault => {}
+    }
+
+    let ms = &mut ctx.accounts.multisig;
+    let proposer = ctx.accounts.proposer.key();
+    let signer_index = ms
+        .signer_index(&proposer)
+        .ok_or(VaultError::NotMultisigSigner)?;
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.vault = ctx.accounts.vault_state.key();
+    proposal.proposer = proposer;
+    proposal.index = ms.proposal_count;
+    proposal.generation = ms.generation;
+    proposal.action = action.clone();
+    // The proposer's approval is implied
+    proposal.approvals = 1 << signer_index;
+    proposal.bump = ctx.bumps.proposal;
+
+    ms.proposal_count = ms
+        .proposal_count
+        .checked_add(1)
+        .ok_or(VaultError::Overflow)?;
+    ms.open_proposals = ms
+        .open_proposals
+        .checked_add(1)
+        .ok_or(VaultError::Overflow)?;
+
+    emit!(ProposalCreated {
+        vault: proposal.vault,
+        proposal: proposal.key(),
+        index: proposal.index,
+        proposer,
+        action,
+    });
+
+    msg!("[vault] proposal created index={}", proposal.index);
+    Ok(())
+}
+
+// ─── ApproveProposal ─────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        seeds = [b"multisig", vault_state.key().as_ref()],
+        bump  = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", vault_state.key().as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump  = proposal.bump,
+        constraint = proposal.vault == vault_state.key() @ VaultError::Unauthorised,
+        constraint = proposal.generation == multisig.generation @ VaultError::StaleProposal,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+pub fn approve_handler(ctx: Context<ApproveProposal>) -> Result<()> {
+    let signer = ctx.accounts.signer.key();
+    let signer_index = ctx
+        .accounts
+        .multisig
+        .signer_index(&signer)
+        .ok_or(VaultError::NotMultisigSigner)?;
+
+    let proposal = &mut ctx.accounts.proposal;
+    let bit = 1u16 << signer_index;
+    require!(proposal.approvals & bit == 0, VaultError::AlreadyApproved);
+    proposal.approvals |= bit;
+
+    emit!(ProposalApproved {
+        vault: proposal.vault,
+        proposal: proposal.key(),
+        signer,
+        approvals: proposal.approval_count() as u8,
+    });
+
+    msg!(
+        "[vault] proposal approved index={} approvals={}",
+        proposal.index,
+        proposal.approval_count()
+    );
+    Ok(())
+}
+
+// ─── CancelProposal ──────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig", vault_state.key().as_ref()],
+        bump  = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"proposal", vault_state.key().as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump  = proposal.bump,
+        has_one = proposer @ VaultError::Unauthorised,
+        constraint = proposal.vault == vault_state.key() @ VaultError::Unauthorised,
+        constraint = proposal.generation == multisig.generation @ VaultError::StaleProposal,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+pub fn cancel_handler(ctx: Context<CancelProposal>) -> Result<()> {
+    let ms = &mut ctx.accounts.multisig;
+    ms.open_proposals = ms
+        .open_proposals
+        .checked_sub(1)
+        .ok_or(VaultError::Overflow)?;
+
+    emit!(ProposalCancelled {
+        vault: ctx.accounts.vault_state.key(),
+        proposal: ctx.accounts.proposal.key(),
+        index: ctx.accounts.proposal.index,
+    });
+    msg!(
+        "[vault] proposal cancelled index={}",
+        ctx.accounts.proposal.index
+    );
+    Ok(())
+}
+
+// ─── Execution ───────────────────────────────────────────────────────────────
+//
+// One instruction per action so each gets exactly the accounts it needs.
+// All of them require an executor from the signer set, a proposal of the
+// current generation that has reached the threshold, and close the proposal
+// back to its proposer.
+
+fn check_executable(multisig: &mut Multisig, proposal: &Proposal) -> Result<()> {
+    require!(
+        proposal.generation == multisig.generation,
+        VaultError::StaleProposal
+    );
+    require!(
+        proposal.approval_count() >= multisig.threshold as u32,
+        VaultError::ThresholdNotMet
+    );
+    // The proposal is closed on success
+    multisig.open_proposals = multisig
+        .open_proposals
+        .checked_sub(1)
+        .ok_or(VaultError::Overflow)?;
+    Ok(())
+}
+
+fn emit_executed(vault: Pubkey, proposal: &Account<Proposal>) {
+    emit!(ProposalExecuted {
+        vault,
+        proposal: proposal.key(),
+        index: proposal.index,
+    });
+    msg!("[vault] proposal executed index={}", proposal.index);
+}
+
+#[derive(Accounts)]
+pub struct ExecuteWithdrawProposal<'info> {
+    #[account(
+        constraint = multisig.signer_index(&executor.key()).is_some() @ VaultError::NotMultisigSigner,
+    )]
+    pub executor: Signer<'info>,
+
+    /// CHECK: rent destination, validated against `proposal.proposer`
+    #[account(mut, address = proposal.proposer @ VaultError::Unauthorised)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(address = vault_state.mint @ VaultError::Unauthorised)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig", vault_state.key().as_ref()],
+        bump  = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"proposal", vault_state.key().as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump  = proposal.bump,
+        constraint = proposal.vault == vault_state.key() @ VaultError::Unauthorised,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        address = vault_state.vault_ata @ VaultError::Unauthorised,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == mint.key() @ VaultError::Unauthorised,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// The vault's `Policy`; required while it has rules
+    #[account(
+        mut,
+        seeds = [b"policy", vault_state.key().as_ref()],
+        bump  = policy.bump,
+    )]
+    pub policy: Option<Account<'info, Policy>>,
+
+    /// The vault's co-signer; required above `cosign_threshold`
+    #[account(address = vault_state.cosigner @ VaultError::Unauthorised)]
+    pub cosigner: Option<Signer<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn execute_withdraw_handler(ctx: Context<ExecuteWithdrawProposal>) -> Result<()> {
+    check_executable(&mut ctx.accounts.multisig, &ctx.accounts.proposal)?;
+    let ProposalAction::Withdraw {
+        amount,
+        destination,
+    } = ctx.accounts.proposal.action
+    else {
+        return err!(VaultError::ProposalActionMismatch);
+    };
+    require_keys_eq!(
+        ctx.accounts.destination.key(),
+        destination,
+        VaultError::Unauthorised
+    );
+
+    let vault = &mut ctx.accounts.vault_state;
+    require!(!vault.locked, VaultError::VaultLocked);
+    require!(!vault.pooled(), VaultError::PooledVault);
+    vault.require_not_paused(PAUSE_WITHDRAWALS)?;
+
+    let clock = Clock::get()?;
+    check_outflow(
+        vault,
+        ctx.accounts.policy.as_deref_mut(),
+        None,
+        &Outflow {
+            amount,
+            recipient: ctx.accounts.destination.owner,
+            now: clock.unix_timestamp,
+            cosigned: ctx.accounts.cosigner.is_some(),
+            vetted: false,
+        },
+    )?;
+
+    vault.require_available(ctx.accounts.vault_ata.amount, amount)?;
+
+    vault.locked = true;
+
+    let received = transfer_from_vault(
+        vault,
+        &ctx.accounts.vault_ata,
+        &mut ctx.accounts.destination,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+
+    vault.total_withdrawn = vault
+        .total_withdrawn
+        .checked_add(received)
+        .ok_or(VaultError::Overflow)?;
+    vault.locked = false;
+
+    emit!(WithdrawMade {
+        vault: vault.key(),
+        mint: vault.mint,
+        recipient: ctx.accounts.destination.owner,
+        amount: received,
+        fee: amount.saturating_sub(received),
+        shares: 0,
+        by_delegate: false,
+        timestamp: clock.unix_timestamp,
+    });
+    emit_executed(vault.key(), &ctx.accounts.proposal);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteAddDelegateProposal<'info> {
+    /// Pays the rent of the new delegate record
+    #[account(
+        mut,
+        constraint = multisig.signer_index(&executor.key()).is_some() @ VaultError::NotMultisigSigner,
+    )]
+    pub executor: Signer<'info>,
+
+    /// CHECK: rent destination, validated against `proposal.proposer`
+    #[account(mut, address = proposal.proposer @ VaultError::Unauthorised)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig", vault_state.key().as_ref()],
+        bump  = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"proposal", vault_state.key().as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump  = proposal.bump,
+        constraint = proposal.vault == vault_state.key() @ VaultError::Unauthorised,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: arbitrary pubkey we're granting access to, checked against the proposal
+    pub delegate: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = executor,
+        space = DelegateRecord::LEN,
+        seeds = [b"delegate", vault_state.key().as_ref(), delegate.key().as_ref()],
+        bump,
+    )]
+    pub delegate_record: Account<'info, DelegateRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn execute_add_delegate_handler(ctx: Context<ExecuteAddDelegateProposal>) -> Result<()> {
+    check_executable(&mut ctx.accounts.multisig, &ctx.accounts.proposal)?;
+    let ProposalAction::AddDelegate {
+        delegate,
+        allowance,
+        expires_at,
+        period,
+        max_periods,
+    } = ctx.accounts.proposal.action
+    else {
+        return err!(VaultError::ProposalActionMismatch);
+    };
+    require_keys_eq!(
+        ctx.accounts.delegate.key(),
+        delegate,
+        VaultError::Unauthorised
+    );
+
+    let vault = ctx.accounts.vault_state.key();
+    let mint = ctx.accounts.vault_state.mint;
+    delegate::grant(
+        &mut ctx.accounts.delegate_record,
+        &mut ctx.accounts.vault_state,
+        delegate,
+        mint,
+        allowance,
+        expires_at,
+        period,
+        max_periods,
+        ctx.bumps.delegate_record,
+    )?;
+    emit_executed(vault, &ctx.accounts.proposal);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteRemoveDelegateProposal<'info> {
+    #[account(
+        constraint = multisig.signer_index(&executor.key()).is_some() @ VaultError::NotMultisigSigner,
+    )]
+    pub executor: Signer<'info>,
+
+    /// CHECK: rent destination, validated against `proposal.proposer`
+    #[account(mut, address = proposal.proposer @ VaultError::Unauthorised)]
+    pub proposer: UncheckedAccount<'info>,
+
+    /// CHECK: receives the delegate record's rent, validated against `vault_state.owner`
+    #[account(mut, address = vault_state.owner @ VaultError::Unauthorised)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig", vault_state.key().as_ref()],
+        bump  = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"proposal", vault_state.key().as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump  = proposal.bump,
+        constraint = proposal.vault == vault_state.key() @ VaultError::Unauthorised,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"delegate", vault_state.key().as_ref(), delegate_record.delegate.as_ref(), delegate_record.mint_seed(&vault_state.mint)],
+        bump  = delegate_record.bump,
+        constraint = delegate_record.vault == vault_state.key() @ VaultError::Unauthorised,
+        constraint = delegate_record.version == DelegateRecord::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub delegate_record: Account<'info, DelegateRecord>,
+
+    /// The record's `VaultAsset`; required when its allowance is in an asset's mint
+    #[account(
+        mut,
+        seeds = [b"asset", vault_state.key().as_ref(), delegate_record.mint.as_ref()],
+        bump  = asset.bump,
+    )]
+    pub asset: Option<Account<'info, VaultAsset>>,
+}
+
+pub fn execute_remove_delegate_handler(ctx: Context<ExecuteRemoveDelegateProposal>) -> Result<()> {
+    check_executable(&mut ctx.accounts.multisig, &ctx.accounts.proposal)?;
+    let ProposalAction::RemoveDelegate { delegate } = ctx.accounts.proposal.action else {
+        return err!(VaultError::ProposalActionMismatch);
+    };
+    require_keys_eq!(
+        ctx.accounts.delegate_record.delegate,
+        delegate,
+        VaultError::Unauthorised
+    );
+
+    delegate::release(
+        &mut ctx.accounts.vault_state,
+        ctx.accounts.asset.as_deref_mut(),
+        &ctx.accounts.delegate_record,
+    )?;
+
+    let vault = ctx.accounts.vault_state.key();
+    emit!(DelegateRemoved { vault, delegate });
+    msg!("[vault] delegate removed={}", delegate);
+    emit_executed(vault, &ctx.accounts.proposal);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteUpdateDelegateProposal<'info> {
+    #[account(
+        constraint = multisig.signer_index(&executor.key()).is_some() @ VaultError::NotMultisigSigner,
+    )]
+    pub executor: Signer<'info>,
+
+    /// CHECK: rent destination, validated against `proposal.proposer`
+    #[account(mut, address = proposal.proposer @ VaultError::Unauthorised)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig", vault_state.key().as_ref()],
+        bump  = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"proposal", vault_state.key().as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump  = proposal.bump,
+        constraint = proposal.vault == vault_state.key() @ VaultError::Unauthorised,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// Seeded without a mint: the proposal names a delegate, not an asset, so
+    /// only its record in the vault's own mint can be updated
+    #[account(
+        mut,
+        seeds = [b"delegate", vault_state.key().as_ref(), delegate_record.delegate.as_ref()],
+        bump  = delegate_record.bump,
+        constraint = delegate_record.vault == vault_state.key() @ VaultError::Unauthorised,
+        constraint = delegate_record.version == DelegateRecord::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub delegate_record: Account<'info, DelegateRecord>,
+}
+
+pub fn execute_update_delegate_handler(ctx: Context<ExecuteUpdateDelegateProposal>) -> Result<()> {
+    check_executable(&mut ctx.accounts.multisig, &ctx.accounts.proposal)?;
+    let ProposalAction::UpdateDelegate { delegate, update } = ctx.accounts.proposal.action else {
+        return err!(VaultError::ProposalActionMismatch);
+    };
+    require_keys_eq!(
+        ctx.accounts.delegate_record.delegate,
+        delegate,
+        VaultError::Unauthorised
+    );
+
+    let queued = ctx.accounts.vault_state.withdraw_tier_count > 0;
+    delegate::amend(&mut ctx.accounts.delegate_record, update, queued)?;
+    emit_executed(ctx.accounts.vault_state.key(), &ctx.accounts.proposal);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteCloseVaultProposal<'info> {
+    #[account(
+        constraint = multisig.signer_index(&executor.key()).is_some() @ VaultError::NotMultisigSigner,
+    )]
+    pub executor: Signer<'info>,
+
+    /// CHECK: rent destination, validated against `proposal.proposer`
+    #[account(mut, address = proposal.proposer @ VaultError::Unauthorised)]
+    pub proposer: UncheckedAccount<'info>,
+
+    /// CHECK: receives the vault's rent, validated against `vault_state.owner`
+    #[account(mut, address = vault_state.owner @ VaultError::Unauthorised)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.vault_ata == vault_ata.key() @ VaultError::Unauthorised,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"multisig", vault_state.key().as_ref()],
+        bump  = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        close = proposer,
+        seeds = [b"proposal", vault_state.key().as_ref(), proposal.index.to_le_bytes().as_ref()],
+        bump  = proposal.bump,
+        constraint = proposal.vault == vault_state.key() @ VaultError::Unauthorised,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        constraint = vault_ata.amount == 0 @ VaultError::VaultNotEmpty,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn execute_close_vault_handler<'info>(
+    ctx: Context<'info, ExecuteCloseVaultProposal<'info>>,
+) -> Result<()> {
+    check_executable(&mut ctx.accounts.multisig, &ctx.accounts.proposal)?;
+    require!(
+        ctx.accounts.proposal.action == ProposalAction::CloseVault,
+        VaultError::ProposalActionMismatch
+    );
+    // Any other open proposal could otherwise be executed against a vault
+    // re-created at the same address
+    require!(
+        ctx.accounts.multisig.open_proposals == 0,
+        VaultError::ProposalsOutstanding
+    );
+
+    let clock = Clock::get()?;
+    let owner_key = ctx.accounts.vault_state.owner;
+
+    let owner = ctx.accounts.owner.to_account_info();
+    close_delegate_records(
+        &mut ctx.accounts.vault_state,
+        ctx.remaining_accounts,
+        &owner,
+        None,
+    )?;
+    require!(
+        ctx.accounts.vault_state.delegate_count == 0,
+        VaultError::DelegatesOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.stream_count == 0,
+        VaultError::StreamsOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.tranche_count == 0,
+        VaultError::TranchesOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.asset_count == 0,
+        VaultError::AssetsOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.role_count == 0,
+        VaultError::RolesOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.depositor_count == 0,
+        VaultError::DepositorsOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.policy_rule_count == 0,
+        VaultError::PolicyOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.withdraw_tier_count == 0,
+        VaultError::WithdrawTiersOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.pending_withdrawal_count == 0,
+        VaultError::PendingWithdrawalsOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.veto_key_count == 0,
+        VaultError::VetoKeysOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.recovery_guardian_count == 0,
+        VaultError::RecoveryOutstanding
+    );
+    require!(
+        ctx.accounts.vault_state.allocated == 0 && ctx.accounts.vault_state.strategy_value == 0,
+        VaultError::StrategyFundsOutstanding
+    );
+
+    close_vault_ata(
+        &ctx.accounts.vault_state,
+        &ctx.accounts.vault_ata,
+        ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.token_program,
+    )?;
+
+    let vault = ctx.accounts.vault_state.key();
+    emit!(VaultClosed {
+        vault,
+        owner: owner_key,
+        timestamp: clock.unix_timestamp,
+    });
+    msg!("[vault] vault closed owner={}", owner_key);
+    emit_executed(vault, &ctx.accounts.proposal);
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use crate::{
+    errors::VaultError,
+    events::{OwnerTransferProposed, OwnerTransferred},
+    state::VaultState,
+};
+use anchor_lang::prelude::*;
+
+// ─── ProposeOwnerTransfer ────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct ProposeOwnerTransfer<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+/// Nominate `new_owner`; passing `Pubkey::default()` cancels a pending nomination.
+pub fn propose_handler(ctx: Context<ProposeOwnerTransfer>, new_owner: Pubkey) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    let vault = &mut ctx.accounts.vault_state;
+    require_keys_neq!(new_owner, vault.owner, VaultError::Unauthorised);
+
+    vault.pending_owner = new_owner;
+
+    emit!(OwnerTransferProposed {
+        vault: vault.key(),
+        owner: vault.owner,
+        pending_owner: new_owner,
+    });
+
+    msg!(
+        "[vault] owner transfer proposed pending_owner={}",
+        new_owner
+    );
+    Ok(())
+}
+
+// ─── AcceptOwnerTransfer ─────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct AcceptOwnerTransfer<'info> {
+    /// Must be the nominated `pending_owner`
+    pub new_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.pending_owner != Pubkey::default() @ VaultError::NoPendingOwner,
+        constraint = vault_state.pending_owner == new_owner.key()   @ VaultError::Unauthorised,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+pub fn accept_handler(ctx: Context<AcceptOwnerTransfer>) -> Result<()> {
+    let vault = &mut ctx.accounts.vault_state;
+    let clock = Clock::get()?;
+
+    let previous_owner = vault.owner;
+    vault.owner = ctx.accounts.new_owner.key();
+    vault.pending_owner = Pubkey::default();
+    vault.heartbeat(&ctx.accounts.new_owner.key(), clock.unix_timestamp);
+
+    emit!(OwnerTransferred {
+        vault: vault.key(),
+        previous_owner,
+        new_owner: vault.owner,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "[vault] owner transferred from={} to={}",
+        previous_owner,
+        vault.owner
+    );
+    Ok(())
+}
@@ -0,0 +1,221 @@
+use crate::{
+    errors::VaultError,
+    events::{PoolEnabled, WithdrawMade},
+    policy::{check_outflow, Outflow},
+    state::{Policy, Role, VaultState, PAUSE_WITHDRAWALS, ROLE_CONFIG_ADMIN},
+    utils::transfer_from_vault,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Burn, Mint, TokenAccount, TokenInterface};
+
+// ─── EnablePool ──────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct EnablePool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), mint.key().as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_CONFIG_ADMIN) @ VaultError::Unauthorised,
+        has_one = mint  @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = !vault_state.pooled() @ VaultError::PooledVault,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// `authority`'s role on this vault; not needed when it is the owner
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    /// Must be empty: every token in a pool is backed by shares
+    #[account(
+        address = vault_state.vault_ata @ VaultError::Unauthorised,
+        constraint = vault_ata.amount == 0 @ VaultError::VaultNotEmpty,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"shares", vault_state.key().as_ref()],
+        bump,
+        mint::decimals = mint.decimals,
+        mint::authority = vault_state,
+        mint::token_program = token_program,
+    )]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/// One-way switch to pooled mode: deposits mint shares and holders redeem
+/// them with `withdraw_shares`; owner, delegate and multisig withdrawals stop.
+/// The vault must not have a strategy set or queued.
+pub fn enable_handler(ctx: Context<EnablePool>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    let vault = &mut ctx.accounts.vault_state;
+    require!(vault.delegate_count == 0, VaultError::DelegatesOutstanding);
+    require!(vault.stream_count == 0, VaultError::StreamsOutstanding);
+    require!(vault.tranche_count == 0, VaultError::TranchesOutstanding);
+    require!(
+        vault.strategy == Pubkey::default() && vault.pending_strategy == Pubkey::default(),
+        VaultError::PooledStrategy
+    );
+
+    vault.share_mint = ctx.accounts.share_mint.key();
+
+    emit!(PoolEnabled {
+        vault: vault.key(),
+        share_mint: vault.share_mint,
+    });
+    msg!("[vault] pooled share_mint={}", vault.share_mint);
+    Ok(())
+}
+
+// ─── WithdrawShares ──────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct WithdrawShares<'info> {
+    pub holder: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), mint.key().as_ref()],
+        bump  = vault_state.bump,
+        has_one = mint @ VaultError::Unauthorised,
+        constraint = vault_state.pooled() @ VaultError::NotPooled,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        address = vault_state.vault_ata @ VaultError::Unauthorised,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, address = vault_state.share_mint @ VaultError::Unauthorised)]
+    pub share_mint: InterfaceAccount<'info, Mint>,
+
+    /// Shares to burn — must be owned by the holder
+    #[account(
+        mut,
+        constraint = holder_shares.owner == holder.key()      @ VaultError::Unauthorised,
+        constraint = holder_shares.mint  == share_mint.key()  @ VaultError::Unauthorised,
+    )]
+    pub holder_shares: InterfaceAccount<'info, TokenAccount>,
+
+    /// Any token account of the vault's mint
+    #[account(
+        mut,
+        constraint = destination.mint == mint.key() @ VaultError::Unauthorised,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// The vault's `Policy`; required while it has rules
+    #[account(
+        mut,
+        seeds = [b"policy", vault_state.key().as_ref()],
+        bump  = policy.bump,
+    )]
+    pub policy: Option<Account<'info, Policy>>,
+
+    /// The vault's co-signer; required above `cosign_threshold`
+    #[account(address = vault_state.cosigner @ VaultError::Unauthorised)]
+    pub cosigner: Option<Signer<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Burn `shares` for their pro-rata part of the pool (rounded down).
+pub fn withdraw_handler(ctx: Context<WithdrawShares>, shares: u64) -> Result<()> {
+    require!(shares > 0, VaultError::ZeroAmount);
+
+    let vault = &mut ctx.accounts.vault_state;
+    require!(!vault.locked, VaultError::VaultLocked);
+    vault.require_not_paused(PAUSE_WITHDRAWALS)?;
+
+    let amount = VaultState::assets_for_shares(
+        shares,
+        vault.pool_assets(ctx.accounts.vault_ata.amount)?,
+        ctx.accounts.share_mint.supply,
+    )?;
+    require!(amount > 0, VaultError::ZeroAmount);
+
+    let clock = Clock::get()?;
+    check_outflow(
+        vault,
+        ctx.accounts.policy.as_deref_mut(),
+        None,
+        &Outflow {
+            amount,
+            recipient: ctx.accounts.destination.owner,
+            now: clock.unix_timestamp,
+            cosigned: ctx.accounts.cosigner.is_some(),
+            vetted: false,
+        },
+    )?;
+    vault.require_available(ctx.accounts.vault_ata.amount, amount)?;
+
+    vault.locked = true;
+
+    // Fails if the holder has fewer shares than requested
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.key(),
+        Burn {
+            mint: ctx.accounts.share_mint.to_account_info(),
+            from: ctx.accounts.holder_shares.to_account_info(),
+            authority: ctx.accounts.holder.to_account_info(),
+        },
+    );
+    token_interface::burn(cpi_ctx, shares)?;
+
+    let received = transfer_from_vault(
+        vault,
+        &ctx.accounts.vault_ata,
+        &mut ctx.accounts.destination,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+
+    vault.total_withdrawn = vault
+        .total_withdrawn
+        .checked_add(received)
+        .ok_or(VaultError::Overflow)?;
+    vault.locked = false;
+
+    emit!(WithdrawMade {
+        vault: vault.key(),
+        mint: vault.mint,
+        recipient: ctx.accounts.destination.owner,
+        amount: received,
+        fee: amount.saturating_sub(received),
+        shares,
+        by_delegate: false,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "[vault] withdraw_shares shares={} amount={} received={}",
+        shares,
+        amount,
+        received
+    );
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use crate::{
+    errors::VaultError,
+    events::DelegateRemoved,
+    instructions::delegate,
+    state::{DelegateRecord, Role, VaultAsset, VaultState, ROLE_DELEGATE_MANAGER},
+    utils::close_delegate_records,
+};
+use anchor_lang::prelude::*;
+
+// ─── PruneDelegate ───────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct PruneDelegate<'info> {
+    /// Anyone may crank; the rent goes to the vault owner, not the caller
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: rent destination, validated against `vault_state.owner`
+    #[account(mut, address = vault_state.owner @ VaultError::Unauthorised)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"delegate", vault_state.key().as_ref(), delegate_record.delegate.as_ref(), delegate_record.mint_seed(&vault_state.mint)],
+        bump  = delegate_record.bump,
+        constraint = delegate_record.vault == vault_state.key() @ VaultError::Unauthorised,
+        constraint = delegate_record.version == DelegateRecord::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub delegate_record: Account<'info, DelegateRecord>,
+
+    /// The record's `VaultAsset`; required when its allowance is in an asset's mint
+    #[account(
+        mut,
+        seeds = [b"asset", vault_state.key().as_ref(), delegate_record.mint.as_ref()],
+        bump  = asset.bump,
+    )]
+    pub asset: Option<Account<'info, VaultAsset>>,
+}
+
+pub fn prune_handler(ctx: Context<PruneDelegate>) -> Result<()> {
+    let clock = Clock::get()?;
+    let rec = &ctx.accounts.delegate_record;
+    require!(
+        rec.is_spent(clock.unix_timestamp),
+        VaultError::DelegateStillActive
+    );
+
+    delegate::release(
+        &mut ctx.accounts.vault_state,
+        ctx.accounts.asset.as_deref_mut(),
+        rec,
+    )?;
+
+    emit!(DelegateRemoved {
+        vault: rec.vault,
+        delegate: rec.delegate,
+    });
+    msg!(
+        "[vault] delegate pruned={} by={}",
+        rec.delegate,
+        ctx.accounts.cranker.key()
+    );
+    Ok(())
+}
+
+// ─── PruneDelegates ──────────────────────────────────────────────────────────
+
+/// Spent `DelegateRecord`s to close are passed (writable) in `remaining_accounts`.
+/// Only records in the vault's own mint; asset delegates go through `prune_delegate`.
+#[derive(Accounts)]
+pub struct PruneDelegates<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_DELEGATE_MANAGER) @ VaultError::Unauthorised,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: rent destination, validated against `vault_state.owner`
+    #[account(mut, address = vault_state.owner @ VaultError::Unauthorised)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// `authority`'s role on this vault; not needed when it is the owner
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+}
+
+pub fn prune_many_handler<'info>(ctx: Context<'info, PruneDelegates<'info>>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    let clock = Clock::get()?;
+    let owner = ctx.accounts.owner.to_account_info();
+    close_delegate_records(
+        &mut ctx.accounts.vault_state,
+        ctx.remaining_accounts,
+        &owner,
+        Some(clock.unix_timestamp),
+    )?;
+
+    msg!(
+        "[vault] pruned {} delegate records",
+        ctx.remaining_accounts.len()
+    );
+    Ok(())
+}
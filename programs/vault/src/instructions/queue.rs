@@ -0,0 +1,522 @@
+use crate::{
+    errors::VaultError,
+    events::{
+        InheritanceClaimed, WithdrawCancelled, WithdrawExecuted, WithdrawRequested,
+        WithdrawTiersChanged, WithdrawTiersQueued,
+    },
+    policy::{check_outflow, Outflow},
+    state::{
+        DelayTier, PendingWithdrawal, Policy, Role, VaultState, WithdrawTiers,
+        CONFIG_TIMELOCK_SECONDS, MAX_WITHDRAW_TIERS, PAUSE_WITHDRAWALS, ROLE_WITHDRAWER,
+    },
+    utils::transfer_from_vault,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+// ─── SetWithdrawTiers ────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct SetWithdrawTiers<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = WithdrawTiers::LEN,
+        seeds = [b"withdraw_tiers", vault_state.key().as_ref()],
+        bump,
+    )]
+    pub withdraw_tiers: Account<'info, WithdrawTiers>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Replace the cooldown schedule. A schedule that delays every amount at
+/// least as long applies at once; one that shortens any delay (including an
+/// empty one, which leaves queued mode) waits `CONFIG_TIMELOCK_SECONDS`.
+/// Any call replaces a previously queued schedule.
+pub fn set_tiers_handler(ctx: Context<SetWithdrawTiers>, tiers: Vec<DelayTier>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    require!(
+        tiers.len() <= MAX_WITHDRAW_TIERS
+            && tiers.iter().all(|t| t.delay > 0)
+            && tiers
+                .windows(2)
+                .all(|w| w[0].min_amount < w[1].min_amount && w[0].delay <= w[1].delay),
+        VaultError::InvalidWithdrawTiers
+    );
+
+    let vault = &mut ctx.accounts.vault_state;
+    let schedule = &mut ctx.accounts.withdraw_tiers;
+    if schedule.vault == Pubkey::default() {
+        schedule.vault = vault.key();
+        schedule.bump = ctx.bumps.withdraw_tiers;
+    }
+
+    if DelayTier::loosens(&schedule.tiers, &tiers) {
+        let effective_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(CONFIG_TIMELOCK_SECONDS)
+            .ok_or(VaultError::Overflow)?;
+        schedule.pending_tiers = tiers.clone();
+        schedule.tiers_effective_at = effective_at;
+
+        emit!(WithdrawTiersQueued {
+            vault: schedule.vault,
+            tiers,
+            effective_at,
+        });
+        msg!(
+            "[vault] withdraw tiers queued effective_at={}",
+            effective_at
+        );
+    } else {
+        schedule.pending_tiers.clear();
+        schedule.tiers_effective_at = 0;
+        apply_tiers(vault, schedule, tiers);
+    }
+
+    close_if_unused(schedule, &ctx.accounts.owner)
+}
+
+// ─── ApplyWithdrawTiers ──────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct ApplyWithdrawTiers<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"withdraw_tiers", vault_state.key().as_ref()],
+        bump  = withdraw_tiers.bump,
+    )]
+    pub withdraw_tiers: Account<'info, WithdrawTiers>,
+}
+
+/// Apply a queued schedule once its timelock has passed.
+pub fn apply_tiers_handler(ctx: Context<ApplyWithdrawTiers>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    let vault = &mut ctx.accounts.vault_state;
+    let schedule = &mut ctx.accounts.withdraw_tiers;
+    require!(
+        schedule.tiers_effective_at != 0,
+        VaultError::NoPendingConfig
+    );
+    require!(
+        Clock::get()?.unix_timestamp >= schedule.tiers_effective_at,
+        VaultError::ConfigTimelockActive
+    );
+
+    let tiers = std::mem::take(&mut schedule.pending_tiers);
+    schedule.tiers_effective_at = 0;
+    apply_tiers(vault, schedule, tiers);
+
+    close_if_unused(schedule, &ctx.accounts.owner)
+}
+
+fn apply_tiers(vault: &mut VaultState, schedule: &mut WithdrawTiers, tiers: Vec<DelayTier>) {
+    vault.withdraw_tier_count = tiers.len() as u32;
+    schedule.tiers = tiers.clone();
+
+    emit!(WithdrawTiersChanged {
+        vault: schedule.vault,
+        tiers,
+    });
+    msg!(
+        "[vault] withdraw tiers set count={}",
+        vault.withdraw_tier_count
+    );
+}
+
+/// Close the schedule once it neither holds nor queues any tier.
+fn close_if_unused<'info>(
+    schedule: &mut Account<'info, WithdrawTiers>,
+    owner: &Signer<'info>,
+) -> Result<()> {
+    if schedule.tiers.is_empty() && schedule.tiers_effective_at == 0 {
+        schedule.close(owner.to_account_info())?;
+    }
+    Ok(())
+}
+
+// ─── RequestWithdraw ─────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+#[instruction(request_id: u64)]
+pub struct RequestWithdraw<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_WITHDRAWER)
+            || authority.key() == vault_state.beneficiary @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = !vault_state.pooled() @ VaultError::PooledVault,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// `authority`'s role on this vault; not needed for the owner or the beneficiary
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    /// The vault's cooldown schedule; required in queued mode
+    #[account(
+        seeds = [b"withdraw_tiers", vault_state.key().as_ref()],
+        bump  = withdraw_tiers.bump,
+    )]
+    pub withdraw_tiers: Option<Account<'info, WithdrawTiers>>,
+
+    /// Any token account of the vault's mint
+    #[account(constraint = destination.mint == vault_state.mint @ VaultError::Unauthorised)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// The vault's co-signer; required above `cosign_threshold`
+    #[account(address = vault_state.cosigner @ VaultError::Unauthorised)]
+    pub cosigner: Option<Signer<'info>>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = PendingWithdrawal::LEN,
+        seeds = [b"pending_withdrawal", vault_state.key().as_ref(), request_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Queue `amount` for `destination`; it can be executed once the delay of
+/// the tier `amount` falls in has passed, or the veto window if `amount` is
+/// above the veto threshold, whichever is longer. The beneficiary may queue
+/// the claims `claim_inheritance` refuses once the owner has gone inactive.
+pub fn request_handler(ctx: Context<RequestWithdraw>, request_id: u64, amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), now);
+
+    require!(amount > 0, VaultError::ZeroAmount);
+
+    let vault = &mut ctx.accounts.vault_state;
+    vault.require_not_paused(PAUSE_WITHDRAWALS)?;
+
+    let inheritance = !vault.authorized(
+        &ctx.accounts.authority.key(),
+        ctx.accounts.role.as_deref(),
+        ROLE_WITHDRAWER,
+    );
+    if inheritance {
+        require!(
+            vault.inheritance_claimable(now),
+            VaultError::OwnerStillActive
+        );
+    }
+    require!(
+        ctx.accounts.cosigner.is_some() || !vault.requires_cosigner(amount),
+        VaultError::CosignatureRequired
+    );
+
+    let vetoable = vault.requires_veto(amount);
+    require!(
+        vault.withdraw_tier_count > 0 || vetoable,
+        VaultError::WithdrawQueueDisabled
+    );
+
+    let mut delay = match ctx.accounts.withdraw_tiers.as_deref() {
+        Some(schedule) => DelayTier::delay_for(&schedule.tiers, amount),
+        None => {
+            require!(
+                vault.withdraw_tier_count == 0,
+                VaultError::WithdrawTiersAccountMismatch
+            );
+            0
+        }
+    };
+    if vetoable {
+        delay = delay.max(vault.veto_window);
+    }
+
+    let execute_after = now.checked_add(delay).ok_or(VaultError::Overflow)?;
+
+    let pending = &mut ctx.accounts.pending_withdrawal;
+    pending.vault = vault.key();
+    pending.requester = ctx.accounts.authority.key();
+    pending.request_id = request_id;
+    pending.destination = ctx.accounts.destination.key();
+    pending.amount = amount;
+    pending.requested_at = now;
+    pending.execute_after = execute_after;
+    pending.inheritance = inheritance;
+    pending.recovery_count = vault.recovery_count;
+    pending.bump = ctx.bumps.pending_withdrawal;
+
+    vault.pending_withdrawal_count = vault
+        .pending_withdrawal_count
+        .checked_add(1)
+        .ok_or(VaultError::Overflow)?;
+
+    emit!(WithdrawRequested {
+        vault: pending.vault,
+        request_id,
+        requester: pending.requester,
+        recipient: ctx.accounts.destination.owner,
+        amount,
+        execute_after,
+    });
+    msg!(
+        "[vault] withdraw requested id={} amount={} execute_after={}",
+        request_id,
+        amount,
+        execute_after
+    );
+    Ok(())
+}
+
+// ─── ExecuteWithdraw ─────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct ExecuteWithdraw<'info> {
+    /// Anyone may execute; the destination was fixed at request time
+    pub executor: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), mint.key().as_ref()],
+        bump  = vault_state.bump,
+        has_one = mint @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = !vault_state.pooled() @ VaultError::PooledVault,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        address = vault_state.vault_ata @ VaultError::Unauthorised,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        close = requester,
+        seeds = [b"pending_withdrawal", vault_state.key().as_ref(), pending_withdrawal.request_id.to_le_bytes().as_ref()],
+        bump  = pending_withdrawal.bump,
+        constraint = pending_withdrawal.vault == vault_state.key() @ VaultError::Unauthorised,
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+
+    /// CHECK: rent destination; validated against `pending_withdrawal.requester`
+    #[account(mut, address = pending_withdrawal.requester @ VaultError::Unauthorised)]
+    pub requester: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = pending_withdrawal.destination @ VaultError::Unauthorised,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// The vault's `Policy`; required while it has rules
+    #[account(
+        mut,
+        seeds = [b"policy", vault_state.key().as_ref()],
+        bump  = policy.bump,
+    )]
+    pub policy: Option<Account<'info, Policy>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Pay out a pending withdrawal whose cooldown has passed. Pauses, policy
+/// rules, the daily limit and the available balance are checked now; the
+/// co-signature was checked at request time. Requests made before the last
+/// recovery may come from the replaced key and can only be cancelled. A
+/// beneficiary's request also needs the owner to still be inactive, and is
+/// charged against `claim_limit`.
+pub fn execute_handler(ctx: Context<ExecuteWithdraw>) -> Result<()> {
+    let pending = &ctx.accounts.pending_withdrawal;
+    let amount = pending.amount;
+    let vault = &mut ctx.accounts.vault_state;
+    require!(!vault.locked, VaultError::VaultLocked);
+    require!(
+        pending.recovery_count == vault.recovery_count,
+        VaultError::PendingWithdrawalStale
+    );
+    vault.require_not_paused(PAUSE_WITHDRAWALS)?;
+
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp >= pending.execute_after,
+        VaultError::WithdrawCooldownActive
+    );
+    if pending.inheritance {
+        require!(
+            vault.inheritance_claimable(clock.unix_timestamp),
+            VaultError::OwnerStillActive
+        );
+    }
+
+    // Vetoable for the whole window, unless veto mode was tightened since
+    let vetted = pending.execute_after - pending.requested_at >= vault.veto_window;
+    check_outflow(
+        vault,
+        ctx.accounts.policy.as_deref_mut(),
+        None,
+        &Outflow {
+            amount,
+            recipient: ctx.accounts.destination.owner,
+            now: clock.unix_timestamp,
+            // Checked when the withdrawal was requested
+            cosigned: true,
+            vetted,
+        },
+    )?;
+    if pending.inheritance {
+        vault.charge_claim_limit(amount, clock.unix_timestamp)?;
+    }
+    vault.require_available(ctx.accounts.vault_ata.amount, amount)?;
+
+    vault.locked = true;
+
+    let received = transfer_from_vault(
+        vault,
+        &ctx.accounts.vault_ata,
+        &mut ctx.accounts.destination,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+
+    vault.total_withdrawn = vault
+        .total_withdrawn
+        .checked_add(received)
+        .ok_or(VaultError::Overflow)?;
+    vault.pending_withdrawal_count = vault
+        .pending_withdrawal_count
+        .checked_sub(1)
+        .ok_or(VaultError::Overflow)?;
+    vault.locked = false;
+
+    emit!(WithdrawExecuted {
+        vault: vault.key(),
+        request_id: pending.request_id,
+        recipient: ctx.accounts.destination.owner,
+        amount: received,
+        fee: amount.saturating_sub(received),
+        timestamp: clock.unix_timestamp,
+    });
+    if pending.inheritance {
+        emit!(InheritanceClaimed {
+            vault: vault.key(),
+            beneficiary: pending.requester,
+            amount: received,
+            fee: amount.saturating_sub(received),
+            last_heartbeat: vault.last_heartbeat,
+            timestamp: clock.unix_timestamp,
+        });
+    }
+    msg!(
+        "[vault] withdraw executed id={} amount={} received={}",
+        pending.request_id,
+        amount,
+        received
+    );
+    Ok(())
+}
+
+// ─── CancelWithdraw ──────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct CancelWithdraw<'info> {
+    /// Owner or guardian
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = authority.key() == vault_state.owner
+            || (vault_state.guardian != Pubkey::default()
+                && authority.key() == vault_state.guardian) @ VaultError::Unauthorised,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        close = requester,
+        seeds = [b"pending_withdrawal", vault_state.key().as_ref(), pending_withdrawal.request_id.to_le_bytes().as_ref()],
+        bump  = pending_withdrawal.bump,
+        constraint = pending_withdrawal.vault == vault_state.key() @ VaultError::Unauthorised,
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+
+    /// CHECK: rent destination; validated against `pending_withdrawal.requester`
+    #[account(mut, address = pending_withdrawal.requester @ VaultError::Unauthorised)]
+    pub requester: UncheckedAccount<'info>,
+}
+
+/// Drop a pending withdrawal at any point before it executes.
+pub fn cancel_handler(ctx: Context<CancelWithdraw>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    let vault = &mut ctx.accounts.vault_state;
+    vault.pending_withdrawal_count = vault
+        .pending_withdrawal_count
+        .checked_sub(1)
+        .ok_or(VaultError::Overflow)?;
+
+    let pending = &ctx.accounts.pending_withdrawal;
+    emit!(WithdrawCancelled {
+        vault: pending.vault,
+        request_id: pending.request_id,
+        amount: pending.amount,
+        cancelled_by: ctx.accounts.authority.key(),
+    });
+    msg!(
+        "[vault] withdraw cancelled id={} by={}",
+        pending.request_id,
+        ctx.accounts.authority.key()
+    );
+    Ok(())
+}
@@ -0,0 +1,365 @@
+use crate::{
+    errors::VaultError,
+    events::{
+        OwnerTransferred, RecoveryApproved, RecoveryCancelled, RecoveryCompleted,
+        RecoveryConfigured, RecoveryInitiated, RecoveryRemoved,
+    },
+    state::{RecoveryConfig, VaultState, WithdrawTiers, MAX_RECOVERY_GUARDIANS},
+};
+use anchor_lang::prelude::*;
+
+// ─── SetRecovery ─────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct SetRecovery<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = RecoveryConfig::LEN,
+        seeds = [b"recovery", vault_state.key().as_ref()],
+        bump,
+        constraint = !recovery.in_progress() @ VaultError::RecoveryInProgress,
+    )]
+    pub recovery: Account<'info, RecoveryConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Register or replace the guardian set. Refused while a recovery is in
+/// progress, since its approvals index into the current set.
+pub fn set_handler(
+    ctx: Context<SetRecovery>,
+    guardians: Vec<Pubkey>,
+    threshold: u8,
+    delay: i64,
+) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    require!(
+        !guardians.is_empty() && guardians.len() <= MAX_RECOVERY_GUARDIANS,
+        VaultError::InvalidSignerSet
+    );
+    for (i, g) in guardians.iter().enumerate() {
+        require!(!guardians[..i].contains(g), VaultError::InvalidSignerSet);
+    }
+    require!(
+        threshold > 0 && threshold as usize <= guardians.len(),
+        VaultError::InvalidThreshold
+    );
+    require!(delay > 0, VaultError::InvalidRecoveryDelay);
+
+    let vault = &mut ctx.accounts.vault_state;
+    vault.recovery_guardian_count = guardians.len() as u32;
+
+    let recovery = &mut ctx.accounts.recovery;
+    recovery.vault = vault.key();
+    recovery.guardians = guardians.clone();
+    recovery.threshold = threshold;
+    recovery.delay = delay;
+    recovery.bump = ctx.bumps.recovery;
+
+    emit!(RecoveryConfigured {
+        vault: recovery.vault,
+        guardians,
+        threshold,
+        delay,
+    });
+    msg!(
+        "[vault] recovery guardians={} threshold={} delay={}",
+        recovery.guardians.len(),
+        threshold,
+        delay
+    );
+    Ok(())
+}
+
+// ─── RemoveRecovery ──────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct RemoveRecovery<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"recovery", vault_state.key().as_ref()],
+        bump  = recovery.bump,
+        constraint = !recovery.in_progress() @ VaultError::RecoveryInProgress,
+    )]
+    pub recovery: Account<'info, RecoveryConfig>,
+}
+
+/// Close the guardian set; rent returns to the owner.
+pub fn remove_handler(ctx: Context<RemoveRecovery>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    ctx.accounts.vault_state.recovery_guardian_count = 0;
+
+    emit!(RecoveryRemoved {
+        vault: ctx.accounts.vault_state.key(),
+    });
+    msg!("[vault] recovery removed");
+    Ok(())
+}
+
+// ─── Guardian actions ────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct GuardRecovery<'info> {
+    pub guardian: Signer<'info>,
+
+    #[account(
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"recovery", vault_state.key().as_ref()],
+        bump  = recovery.bump,
+        constraint = recovery.guardian_index(&guardian.key()).is_some() @ VaultError::NotRecoveryGuardian,
+    )]
+    pub recovery: Account<'info, RecoveryConfig>,
+}
+
+/// Record `guardian`'s approval; starts the delay once the threshold is met.
+fn approve(recovery: &mut RecoveryConfig, guardian: Pubkey) -> Result<()> {
+    let index = recovery
+        .guardian_index(&guardian)
+        .ok_or(VaultError::NotRecoveryGuardian)?;
+    let bit = 1u16 << index;
+    require!(recovery.approvals & bit == 0, VaultError::AlreadyApproved);
+    recovery.approvals |= bit;
+
+    if recovery.ready_at == 0 && recovery.approval_count() >= recovery.threshold as u32 {
+        recovery.ready_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(recovery.delay)
+            .ok_or(VaultError::Overflow)?;
+    }
+
+    emit!(RecoveryApproved {
+        vault: recovery.vault,
+        guardian,
+        approvals: recovery.approval_count() as u8,
+        ready_at: recovery.ready_at,
+    });
+    msg!(
+        "[vault] recovery approved approvals={} ready_at={}",
+        recovery.approval_count(),
+        recovery.ready_at
+    );
+    Ok(())
+}
+
+/// Start a recovery handing the vault to `new_owner` (counts as the
+/// initiating guardian's approval).
+pub fn initiate_handler(ctx: Context<GuardRecovery>, new_owner: Pubkey) -> Result<()> {
+    require_keys_neq!(new_owner, Pubkey::default(), VaultError::Unauthorised);
+    require_keys_neq!(
+        new_owner,
+        ctx.accounts.vault_state.owner,
+        VaultError::Unauthorised
+    );
+
+    let guardian = ctx.accounts.guardian.key();
+    let recovery = &mut ctx.accounts.recovery;
+    require!(!recovery.in_progress(), VaultError::RecoveryInProgress);
+    recovery.new_owner = new_owner;
+
+    emit!(RecoveryInitiated {
+        vault: recovery.vault,
+        guardian,
+        new_owner,
+    });
+    msg!("[vault] recovery initiated new_owner={}", new_owner);
+
+    approve(recovery, guardian)
+}
+
+pub fn approve_handler(ctx: Context<GuardRecovery>) -> Result<()> {
+    let recovery = &mut ctx.accounts.recovery;
+    require!(recovery.in_progress(), VaultError::NoRecoveryInProgress);
+    approve(recovery, ctx.accounts.guardian.key())
+}
+
+// ─── CancelRecovery ──────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct CancelRecovery<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"recovery", vault_state.key().as_ref()],
+        bump  = recovery.bump,
+        constraint = recovery.in_progress() @ VaultError::NoRecoveryInProgress,
+    )]
+    pub recovery: Account<'info, RecoveryConfig>,
+}
+
+/// The current owner proves it still holds its key. Not gated on
+/// `multisig`: the owner must always be able to stop a recovery it did not ask for.
+pub fn cancel_handler(ctx: Context<CancelRecovery>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    let recovery = &mut ctx.accounts.recovery;
+    let new_owner = recovery.new_owner;
+    recovery.reset();
+
+    emit!(RecoveryCancelled {
+        vault: recovery.vault,
+        new_owner,
+    });
+    msg!("[vault] recovery cancelled new_owner={}", new_owner);
+    Ok(())
+}
+
+// ─── CompleteRecovery ────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct CompleteRecovery<'info> {
+    /// Anyone may complete a recovery once it is ready
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"recovery", vault_state.key().as_ref()],
+        bump  = recovery.bump,
+        constraint = recovery.in_progress() @ VaultError::NoRecoveryInProgress,
+    )]
+    pub recovery: Account<'info, RecoveryConfig>,
+
+    /// The vault's delay tiers, whose queued change is dropped; required in
+    /// queued mode
+    #[account(
+        mut,
+        seeds = [b"withdraw_tiers", vault_state.key().as_ref()],
+        bump  = withdraw_tiers.bump,
+    )]
+    pub withdraw_tiers: Option<Account<'info, WithdrawTiers>>,
+}
+
+/// Make `new_owner` the owner. The vault PDA is seeded by `creator`, and
+/// its ATA and `DelegateRecord`s by the vault, so none of them move: they
+/// simply answer to the new owner, and record rent now returns to it.
+/// Changes the old key nominated or queued are dropped, since whoever held
+/// that key may have made them: pending config, co-signer, veto, strategy
+/// and delay-tier changes, and veto-key removals. Withdrawals already
+/// requested can no longer execute; the new owner cancels them. The
+/// beneficiary is cleared too, as the old key chose it. `Role`s the old key
+/// granted are left in place for the new owner to review and revoke.
+pub fn complete_handler(ctx: Context<CompleteRecovery>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let recovery = &mut ctx.accounts.recovery;
+    require!(
+        recovery.ready_at != 0 && now >= recovery.ready_at,
+        VaultError::RecoveryNotReady
+    );
+
+    let vault = &mut ctx.accounts.vault_state;
+    let previous_owner = vault.owner;
+    vault.owner = recovery.new_owner;
+    vault.pending_owner = Pubkey::default();
+    vault.pending_max_deposit = 0;
+    vault.pending_daily_withdraw_limit = 0;
+    vault.config_effective_at = 0;
+    vault.pending_cosigner = Pubkey::default();
+    vault.pending_cosign_threshold = 0;
+    vault.cosigner_effective_at = 0;
+    vault.pending_veto_threshold = 0;
+    vault.pending_veto_window = 0;
+    vault.veto_effective_at = 0;
+    vault.pending_strategy = Pubkey::default();
+    vault.pending_strategy_state = Pubkey::default();
+    vault.strategy_effective_at = 0;
+    vault.beneficiary = Pubkey::default();
+    vault.inactivity_period = 0;
+    vault.claim_limit = 0;
+    vault.claimed_today = 0;
+    vault.claim_window_start = 0;
+    match ctx.accounts.withdraw_tiers.as_deref_mut() {
+        Some(schedule) => {
+            schedule.pending_tiers.clear();
+            schedule.tiers_effective_at = 0;
+        }
+        None => require!(
+            vault.withdraw_tier_count == 0,
+            VaultError::WithdrawTiersAccountMismatch
+        ),
+    }
+    // Outstanding veto-key removal requests are stamped with the old count
+    vault.recovery_count = vault
+        .recovery_count
+        .checked_add(1)
+        .ok_or(VaultError::Overflow)?;
+    // The inactivity period restarts for the new owner
+    vault.last_heartbeat = now;
+    recovery.reset();
+
+    emit!(RecoveryCompleted {
+        vault: vault.key(),
+        previous_owner,
+        new_owner: vault.owner,
+        timestamp: now,
+    });
+    emit!(OwnerTransferred {
+        vault: vault.key(),
+        previous_owner,
+        new_owner: vault.owner,
+        timestamp: now,
+    });
+    msg!(
+        "[vault] recovery completed from={} to={}",
+        previous_owner,
+        vault.owner
+    );
+    Ok(())
+}
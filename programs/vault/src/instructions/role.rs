@@ -0,0 +1,126 @@
+use crate::{
+    errors::VaultError,
+    events::{RoleGranted, RoleRevoked},
+    state::{Role, VaultState, ROLE_ALL},
+};
+use anchor_lang::prelude::*;
+
+// ─── GrantRole ───────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct GrantRole<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: arbitrary pubkey receiving the role
+    pub member: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = Role::LEN,
+        seeds = [b"role", vault_state.key().as_ref(), member.key().as_ref()],
+        bump,
+    )]
+    pub role: Account<'info, Role>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Set `member`'s permissions to exactly `permissions`, creating the role if needed.
+pub fn grant_handler(ctx: Context<GrantRole>, permissions: u8) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    require!(
+        permissions != 0 && permissions & !ROLE_ALL == 0,
+        VaultError::InvalidPermissions
+    );
+
+    let vault = &mut ctx.accounts.vault_state;
+    let role = &mut ctx.accounts.role;
+    let old_permissions = role.permissions;
+    if old_permissions == 0 {
+        vault.role_count = vault
+            .role_count
+            .checked_add(1)
+            .ok_or(VaultError::Overflow)?;
+        role.vault = vault.key();
+        role.member = ctx.accounts.member.key();
+        role.bump = ctx.bumps.role;
+    }
+    role.permissions = permissions;
+
+    emit!(RoleGranted {
+        vault: role.vault,
+        member: role.member,
+        old_permissions,
+        new_permissions: permissions,
+    });
+    msg!(
+        "[vault] role granted member={} permissions={:#07b}",
+        role.member,
+        permissions
+    );
+    Ok(())
+}
+
+// ─── RevokeRole ──────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct RevokeRole<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"role", vault_state.key().as_ref(), role.member.as_ref()],
+        bump  = role.bump,
+        constraint = role.vault == vault_state.key() @ VaultError::Unauthorised,
+    )]
+    pub role: Account<'info, Role>,
+}
+
+/// Close `role`; rent returns to the owner.
+pub fn revoke_handler(ctx: Context<RevokeRole>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    let vault = &mut ctx.accounts.vault_state;
+    vault.role_count = vault
+        .role_count
+        .checked_sub(1)
+        .ok_or(VaultError::Overflow)?;
+
+    let role = &ctx.accounts.role;
+    emit!(RoleRevoked {
+        vault: role.vault,
+        member: role.member,
+        permissions: role.permissions,
+    });
+    msg!("[vault] role revoked member={}", role.member);
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use crate::{
+    errors::VaultError,
+    events::{PolicyRuleAdded, PolicyRuleRemoved},
+    state::{Policy, PolicyRule, Rule, VaultState, MAX_POLICY_RULES},
+};
+use anchor_lang::prelude::*;
+
+// ─── AddPolicyRule ───────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct AddPolicyRule<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = Policy::LEN,
+        seeds = [b"policy", vault_state.key().as_ref()],
+        bump,
+    )]
+    pub policy: Account<'info, Policy>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Append `rule` to the vault's policy, creating the policy if needed.
+pub fn add_handler(ctx: Context<AddPolicyRule>, rule: Rule) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    rule.validate()?;
+
+    let vault = &mut ctx.accounts.vault_state;
+    let policy = &mut ctx.accounts.policy;
+    require!(
+        policy.rules.len() < MAX_POLICY_RULES,
+        VaultError::PolicyFull
+    );
+    if policy.rules.is_empty() {
+        policy.vault = vault.key();
+        policy.bump = ctx.bumps.policy;
+    }
+
+    policy.rules.push(PolicyRule {
+        rule,
+        used: 0,
+        window_start: 0,
+    });
+    vault.policy_rule_count = policy.rules.len() as u32;
+
+    let index = (policy.rules.len() - 1) as u8;
+    emit!(PolicyRuleAdded {
+        vault: policy.vault,
+        index,
+        rule,
+    });
+    msg!("[vault] policy rule added index={} rule={:?}", index, rule);
+    Ok(())
+}
+
+// ─── RemovePolicyRule ────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct RemovePolicyRule<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"policy", vault_state.key().as_ref()],
+        bump  = policy.bump,
+    )]
+    pub policy: Account<'info, Policy>,
+}
+
+/// Remove the rule at `index`; later rules shift down by one. Removing the
+/// last rule closes the policy and returns its rent to the owner.
+pub fn remove_handler(ctx: Context<RemovePolicyRule>, index: u8) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    let vault = &mut ctx.accounts.vault_state;
+    let policy = &mut ctx.accounts.policy;
+    require!(
+        usize::from(index) < policy.rules.len(),
+        VaultError::PolicyRuleNotFound
+    );
+
+    let removed = policy.rules.remove(usize::from(index));
+    vault.policy_rule_count = policy.rules.len() as u32;
+
+    emit!(PolicyRuleRemoved {
+        vault: policy.vault,
+        index,
+        rule: removed.rule,
+    });
+    msg!(
+        "[vault] policy rule removed index={} rule={:?}",
+        index,
+        removed.rule
+    );
+
+    if policy.rules.is_empty() {
+        policy.close(ctx.accounts.owner.to_account_info())?;
+    }
+    Ok(())
+}
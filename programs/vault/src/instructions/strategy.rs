@@ -0,0 +1,429 @@
+use crate::{
+    adapter::StrategyCpi,
+    errors::VaultError,
+    events::{
+        StrategyAllocated, StrategyChangeQueued, StrategyDeallocated, StrategyReported, StrategySet,
+    },
+    policy::{check_outflow, Outflow},
+    state::{
+        Policy, Role, VaultState, CONFIG_TIMELOCK_SECONDS, PAUSE_WITHDRAWALS, ROLE_WITHDRAWER,
+    },
+    utils::transfer_from_vault,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+// ─── SetStrategy ─────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct SetStrategy<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(address = vault_state.vault_ata @ VaultError::Unauthorised)]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+}
+
+fn apply(vault: &mut Account<VaultState>, strategy: Pubkey, strategy_state: Pubkey) -> Result<()> {
+    require!(
+        vault.allocated == 0 && vault.strategy_value == 0,
+        VaultError::StrategyFundsOutstanding
+    );
+
+    let old_strategy = vault.strategy;
+    vault.strategy = strategy;
+    vault.strategy_state = strategy_state;
+
+    emit!(StrategySet {
+        vault: vault.key(),
+        old_strategy,
+        new_strategy: strategy,
+        strategy_state,
+    });
+    msg!("[vault] strategy set={}", strategy);
+    Ok(())
+}
+
+/// Clearing the strategy (default key), or setting one while the vault ATA
+/// is empty, applies at once; otherwise pointing the vault at a program is
+/// queued for `CONFIG_TIMELOCK_SECONDS`, since that program receives whatever
+/// `allocate` sends it. Either way nothing may be allocated. Any call
+/// replaces a previously queued change. `strategy_state` pins the one state
+/// account the strategy may be called with.
+pub fn set_handler(
+    ctx: Context<SetStrategy>,
+    strategy: Pubkey,
+    strategy_state: Pubkey,
+) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    let vault = &mut ctx.accounts.vault_state;
+    require!(
+        vault.allocated == 0 && vault.strategy_value == 0,
+        VaultError::StrategyFundsOutstanding
+    );
+
+    if strategy == Pubkey::default() {
+        vault.pending_strategy = Pubkey::default();
+        vault.pending_strategy_state = Pubkey::default();
+        vault.strategy_effective_at = 0;
+        return apply(vault, strategy, Pubkey::default());
+    }
+    require!(!vault.pooled(), VaultError::PooledStrategy);
+
+    if ctx.accounts.vault_ata.amount == 0 {
+        vault.pending_strategy = Pubkey::default();
+        vault.pending_strategy_state = Pubkey::default();
+        vault.strategy_effective_at = 0;
+        return apply(vault, strategy, strategy_state);
+    }
+
+    let effective_at = Clock::get()?
+        .unix_timestamp
+        .checked_add(CONFIG_TIMELOCK_SECONDS)
+        .ok_or(VaultError::Overflow)?;
+    vault.pending_strategy = strategy;
+    vault.pending_strategy_state = strategy_state;
+    vault.strategy_effective_at = effective_at;
+
+    emit!(StrategyChangeQueued {
+        vault: vault.key(),
+        strategy,
+        strategy_state,
+        effective_at,
+    });
+    msg!(
+        "[vault] strategy change queued strategy={} effective_at={}",
+        strategy,
+        effective_at
+    );
+    Ok(())
+}
+
+pub fn apply_handler(ctx: Context<SetStrategy>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    let vault = &mut ctx.accounts.vault_state;
+    require!(
+        vault.strategy_effective_at != 0,
+        VaultError::NoPendingConfig
+    );
+    require!(
+        Clock::get()?.unix_timestamp >= vault.strategy_effective_at,
+        VaultError::ConfigTimelockActive
+    );
+
+    let (strategy, strategy_state) = (vault.pending_strategy, vault.pending_strategy_state);
+    vault.pending_strategy = Pubkey::default();
+    vault.pending_strategy_state = Pubkey::default();
+    vault.strategy_effective_at = 0;
+    apply(vault, strategy, strategy_state)
+}
+
+// ─── Allocate / Deallocate ───────────────────────────────────────────────────
+
+/// Accounts for `allocate` and `deallocate`; see `adapter` for what the
+/// strategy receives.
+#[derive(Accounts)]
+pub struct MoveStrategyFunds<'info> {
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), mint.key().as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_WITHDRAWER) @ VaultError::Unauthorised,
+        has_one = mint  @ VaultError::Unauthorised,
+        constraint = vault_state.strategy != Pubkey::default() @ VaultError::NoStrategy,
+        constraint = !vault_state.pooled() @ VaultError::PooledStrategy,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// `authority`'s role on this vault; not needed when it is the owner
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    #[account(
+        mut,
+        address = vault_state.vault_ata @ VaultError::Unauthorised,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: must be the vault's strategy program
+    #[account(executable, address = vault_state.strategy @ VaultError::StrategyMismatch)]
+    pub strategy_program: UncheckedAccount<'info>,
+
+    /// CHECK: the state account pinned by `set_strategy`, owned by the strategy program
+    #[account(
+        mut,
+        address = vault_state.strategy_state @ VaultError::StrategyMismatch,
+        owner = vault_state.strategy @ VaultError::StrategyMismatch,
+    )]
+    pub strategy_state: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that signs the strategy's `withdraw`; it holds nothing
+    #[account(seeds = [b"strategy_authority", vault_state.key().as_ref()], bump)]
+    pub strategy_authority: UncheckedAccount<'info>,
+
+    /// Held by the strategy state, so `allocate` can only pay the strategy
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = strategy_state,
+        token::token_program = token_program,
+    )]
+    pub strategy_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// The vault's `Policy`; required while it has rules
+    #[account(
+        mut,
+        seeds = [b"policy", vault_state.key().as_ref()],
+        bump  = policy.bump,
+    )]
+    pub policy: Option<Account<'info, Policy>>,
+
+    /// The vault's co-signer; required above `cosign_threshold`
+    #[account(address = vault_state.cosigner @ VaultError::Unauthorised)]
+    pub cosigner: Option<Signer<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> MoveStrategyFunds<'info> {
+    fn strategy_cpi(&self, authority_bump: u8) -> StrategyCpi<'info> {
+        StrategyCpi {
+            program: self.strategy_program.to_account_info(),
+            vault: self.vault_state.to_account_info(),
+            strategy_state: self.strategy_state.to_account_info(),
+            strategy_ata: self.strategy_token_account.to_account_info(),
+            vault_ata: self.vault_ata.to_account_info(),
+            mint: self.mint.to_account_info(),
+            token_program: self.token_program.to_account_info(),
+            authority: self.strategy_authority.to_account_info(),
+            authority_bump,
+        }
+    }
+}
+
+/// Move idle funds into the strategy. Counts as an outflow: it charges the
+/// daily limit and is refused while withdrawals are paused.
+pub fn allocate_handler(ctx: Context<MoveStrategyFunds>, amount: u64) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    require!(amount > 0, VaultError::ZeroAmount);
+    require!(
+        !ctx.accounts.vault_state.multisig,
+        VaultError::MultisigRequired
+    );
+
+    let clock = Clock::get()?;
+    let vault = &mut ctx.accounts.vault_state;
+    require!(!vault.locked, VaultError::VaultLocked);
+    vault.require_not_paused(PAUSE_WITHDRAWALS)?;
+    check_outflow(
+        vault,
+        ctx.accounts.policy.as_deref_mut(),
+        None,
+        &Outflow {
+            amount,
+            recipient: ctx.accounts.strategy_token_account.owner,
+            now: clock.unix_timestamp,
+            cosigned: ctx.accounts.cosigner.is_some(),
+            vetted: false,
+        },
+    )?;
+    require!(
+        vault.available(ctx.accounts.vault_ata.amount) >= amount,
+        VaultError::InsufficientFunds
+    );
+
+    vault.locked = true;
+    vault.exit(&crate::ID)?;
+
+    // The vault pays the strategy itself and never lends it the PDA signature
+    let balance_before = ctx.accounts.vault_ata.amount;
+    let received = transfer_from_vault(
+        &ctx.accounts.vault_state,
+        &ctx.accounts.vault_ata,
+        &mut ctx.accounts.strategy_token_account,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+    ctx.accounts
+        .strategy_cpi(ctx.bumps.strategy_authority)
+        .deposit(received)?;
+
+    ctx.accounts.vault_ata.reload()?;
+    require!(
+        balance_before.checked_sub(ctx.accounts.vault_ata.amount) == Some(amount),
+        VaultError::StrategyMisbehaved
+    );
+
+    let vault = &mut ctx.accounts.vault_state;
+    vault.allocated = vault
+        .allocated
+        .checked_add(received)
+        .ok_or(VaultError::Overflow)?;
+    vault.strategy_value = vault
+        .strategy_value
+        .checked_add(received)
+        .ok_or(VaultError::Overflow)?;
+    vault.locked = false;
+
+    emit!(StrategyAllocated {
+        vault: vault.key(),
+        strategy: vault.strategy,
+        amount,
+        allocated: vault.allocated,
+    });
+    msg!(
+        "[vault] allocate amount={} allocated={}",
+        amount,
+        vault.allocated
+    );
+    Ok(())
+}
+
+/// Bring `amount` back from the strategy into the vault ATA.
+pub fn deallocate_handler(ctx: Context<MoveStrategyFunds>, amount: u64) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    require!(amount > 0, VaultError::ZeroAmount);
+    require!(!ctx.accounts.vault_state.locked, VaultError::VaultLocked);
+
+    ctx.accounts.vault_state.locked = true;
+    ctx.accounts.vault_state.exit(&crate::ID)?;
+
+    let cpi = ctx.accounts.strategy_cpi(ctx.bumps.strategy_authority);
+    let balance_before = ctx.accounts.vault_ata.amount;
+    cpi.withdraw(amount)?;
+
+    ctx.accounts.vault_ata.reload()?;
+    let received = ctx
+        .accounts
+        .vault_ata
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(VaultError::StrategyMisbehaved)?;
+
+    // Book what actually came back, whatever the strategy was asked for
+    let vault = &mut ctx.accounts.vault_state;
+    vault.allocated = vault.allocated.saturating_sub(received);
+    vault.strategy_value = vault.strategy_value.saturating_sub(received);
+    vault.locked = false;
+
+    emit!(StrategyDeallocated {
+        vault: vault.key(),
+        strategy: vault.strategy,
+        amount: received,
+        allocated: vault.allocated,
+    });
+    msg!(
+        "[vault] deallocate amount={} received={} allocated={}",
+        amount,
+        received,
+        vault.allocated
+    );
+    Ok(())
+}
+
+// ─── ReportStrategy ──────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct ReportStrategy<'info> {
+    /// Anyone may refresh the reported value
+    pub cranker: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), mint.key().as_ref()],
+        bump  = vault_state.bump,
+        has_one = mint @ VaultError::Unauthorised,
+        constraint = vault_state.strategy != Pubkey::default() @ VaultError::NoStrategy,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(address = vault_state.vault_ata @ VaultError::Unauthorised)]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: must be the vault's strategy program
+    #[account(executable, address = vault_state.strategy @ VaultError::StrategyMismatch)]
+    pub strategy_program: UncheckedAccount<'info>,
+
+    /// CHECK: the state account pinned by `set_strategy`, owned by the strategy program
+    #[account(
+        address = vault_state.strategy_state @ VaultError::StrategyMismatch,
+        owner = vault_state.strategy @ VaultError::StrategyMismatch,
+    )]
+    pub strategy_state: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the strategy program
+    pub strategy_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: the vault's strategy authority PDA, passed unsigned
+    #[account(seeds = [b"strategy_authority", vault_state.key().as_ref()], bump)]
+    pub strategy_authority: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Record the strategy's current valuation of the vault's position.
+pub fn report_handler(ctx: Context<ReportStrategy>) -> Result<()> {
+    let cpi = StrategyCpi {
+        program: ctx.accounts.strategy_program.to_account_info(),
+        vault: ctx.accounts.vault_state.to_account_info(),
+        strategy_state: ctx.accounts.strategy_state.to_account_info(),
+        strategy_ata: ctx.accounts.strategy_token_account.to_account_info(),
+        vault_ata: ctx.accounts.vault_ata.to_account_info(),
+        mint: ctx.accounts.mint.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        authority: ctx.accounts.strategy_authority.to_account_info(),
+        authority_bump: ctx.bumps.strategy_authority,
+    };
+    let value = cpi.report_assets()?;
+
+    let clock = Clock::get()?;
+    let vault = &mut ctx.accounts.vault_state;
+    vault.strategy_value = value;
+
+    emit!(StrategyReported {
+        vault: vault.key(),
+        strategy: vault.strategy,
+        value,
+        allocated: vault.allocated,
+        timestamp: clock.unix_timestamp,
+    });
+    msg!(
+        "[vault] strategy value={} allocated={}",
+        value,
+        vault.allocated
+    );
+    Ok(())
+}
@@ -0,0 +1,352 @@
+use crate::{
+    errors::VaultError,
+    events::{StreamCancelled, StreamClaimed, StreamCreated},
+    policy::{check_outflow, Outflow},
+    state::{Policy, Role, Stream, VaultState, PAUSE_DELEGATE_WITHDRAWALS, ROLE_WITHDRAWER},
+    utils::transfer_from_vault,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+// ─── CreateStream ────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+#[instruction(stream_id: u64)]
+pub struct CreateStream<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_WITHDRAWER) @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = !vault_state.pooled() @ VaultError::PooledVault,
+        constraint = vault_state.withdraw_tier_count == 0 @ VaultError::WithdrawQueueRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// `authority`'s role on this vault; not needed when it is the owner
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    #[account(address = vault_state.vault_ata @ VaultError::Unauthorised)]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: arbitrary pubkey being paid
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Stream::LEN,
+        seeds = [b"stream", vault_state.key().as_ref(), stream_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub stream: Account<'info, Stream>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Reserve `total` of the vault's free balance to vest linearly from `start`
+/// to `end`, claimable from `cliff` on. `start` may not be in the past, so
+/// nothing is claimable the moment the stream exists; in queued mode streams
+/// cannot be created at all, as they would pay out without a cooldown.
+pub fn create_handler(
+    ctx: Context<CreateStream>,
+    stream_id: u64,
+    total: u64,
+    start: i64,
+    cliff: i64,
+    end: i64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), now);
+
+    require!(total > 0, VaultError::ZeroAmount);
+    require!(
+        now <= start && start < end && start <= cliff && cliff <= end,
+        VaultError::InvalidStreamSchedule
+    );
+
+    let vault = &mut ctx.accounts.vault_state;
+    vault.require_available(ctx.accounts.vault_ata.amount, total)?;
+    vault.stream_reserved = vault
+        .stream_reserved
+        .checked_add(total)
+        .ok_or(VaultError::Overflow)?;
+    vault.stream_count = vault
+        .stream_count
+        .checked_add(1)
+        .ok_or(VaultError::Overflow)?;
+
+    let stream = &mut ctx.accounts.stream;
+    stream.vault = vault.key();
+    stream.recipient = ctx.accounts.recipient.key();
+    stream.stream_id = stream_id;
+    stream.total = total;
+    stream.start = start;
+    stream.cliff = cliff;
+    stream.end = end;
+    stream.withdrawn = 0;
+    stream.bump = ctx.bumps.stream;
+
+    emit!(StreamCreated {
+        vault: stream.vault,
+        stream: stream.key(),
+        recipient: stream.recipient,
+        total,
+        start,
+        cliff,
+        end,
+    });
+    msg!(
+        "[vault] stream created id={} recipient={} total={}",
+        stream_id,
+        stream.recipient,
+        total
+    );
+    Ok(())
+}
+
+// ─── ClaimStream ─────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct ClaimStream<'info> {
+    pub recipient: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), mint.key().as_ref()],
+        bump  = vault_state.bump,
+        has_one = mint @ VaultError::Unauthorised,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        address = vault_state.vault_ata @ VaultError::Unauthorised,
+    )]
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: gets the stream's rent back once it is fully claimed; validated against `vault_state.owner`
+    #[account(mut, address = vault_state.owner @ VaultError::Unauthorised)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"stream", vault_state.key().as_ref(), stream.stream_id.to_le_bytes().as_ref()],
+        bump  = stream.bump,
+        has_one = recipient @ VaultError::Unauthorised,
+        constraint = stream.vault == vault_state.key() @ VaultError::Unauthorised,
+    )]
+    pub stream: Account<'info, Stream>,
+
+    /// Any token account of the vault's mint chosen by the recipient
+    #[account(
+        mut,
+        constraint = destination.mint == mint.key() @ VaultError::Unauthorised,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    /// The vault's `Policy`; required while it has rules
+    #[account(
+        mut,
+        seeds = [b"policy", vault_state.key().as_ref()],
+        bump  = policy.bump,
+    )]
+    pub policy: Option<Account<'info, Policy>>,
+
+    /// The vault's co-signer; required above `cosign_threshold`
+    #[account(address = vault_state.cosigner @ VaultError::Unauthorised)]
+    pub cosigner: Option<Signer<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Pay out everything accrued so far, or `veto_threshold` of it while that
+/// is less; closes the stream once nothing is left.
+pub fn claim_handler(ctx: Context<ClaimStream>) -> Result<()> {
+    let clock = Clock::get()?;
+    let stream = &mut ctx.accounts.stream;
+    let vault = &mut ctx.accounts.vault_state;
+
+    let mut amount = stream
+        .vested(clock.unix_timestamp)?
+        .checked_sub(stream.withdrawn)
+        .ok_or(VaultError::Overflow)?;
+    // A claim cannot be queued, so a large accrual is paid out in pieces
+    // that need no veto window
+    if vault.requires_veto(amount) {
+        amount = vault.veto_threshold;
+    }
+    require!(amount > 0, VaultError::NothingToClaim);
+
+    require!(!vault.locked, VaultError::VaultLocked);
+    vault.require_not_paused(PAUSE_DELEGATE_WITHDRAWALS)?;
+    check_outflow(
+        vault,
+        ctx.accounts.policy.as_deref_mut(),
+        None,
+        &Outflow {
+            amount,
+            recipient: ctx.accounts.destination.owner,
+            now: clock.unix_timestamp,
+            cosigned: ctx.accounts.cosigner.is_some(),
+            vetted: false,
+        },
+    )?;
+    require!(
+        ctx.accounts.vault_ata.amount >= amount,
+        VaultError::InsufficientFunds
+    );
+
+    vault.locked = true;
+    stream.withdrawn = stream
+        .withdrawn
+        .checked_add(amount)
+        .ok_or(VaultError::Overflow)?;
+    vault.stream_reserved = vault
+        .stream_reserved
+        .checked_sub(amount)
+        .ok_or(VaultError::Overflow)?;
+
+    let received = transfer_from_vault(
+        vault,
+        &ctx.accounts.vault_ata,
+        &mut ctx.accounts.destination,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+
+    vault.total_withdrawn = vault
+        .total_withdrawn
+        .checked_add(received)
+        .ok_or(VaultError::Overflow)?;
+    vault.locked = false;
+
+    emit!(StreamClaimed {
+        vault: vault.key(),
+        stream: stream.key(),
+        recipient: stream.recipient,
+        amount: received,
+        fee: amount.saturating_sub(received),
+        withdrawn: stream.withdrawn,
+        timestamp: clock.unix_timestamp,
+    });
+    msg!(
+        "[vault] stream claim id={} amount={} withdrawn={}",
+        stream.stream_id,
+        amount,
+        stream.withdrawn
+    );
+
+    if stream.withdrawn == stream.total {
+        vault.stream_count = vault
+            .stream_count
+            .checked_sub(1)
+            .ok_or(VaultError::Overflow)?;
+        stream.close(ctx.accounts.owner.to_account_info())?;
+    }
+    Ok(())
+}
+
+// ─── CancelStream ────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct CancelStream<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_WITHDRAWER) @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: rent destination, validated against `vault_state.owner`
+    #[account(mut, address = vault_state.owner @ VaultError::Unauthorised)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// `authority`'s role on this vault; not needed when it is the owner
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+
+    #[account(
+        mut,
+        seeds = [b"stream", vault_state.key().as_ref(), stream.stream_id.to_le_bytes().as_ref()],
+        bump  = stream.bump,
+        constraint = stream.vault == vault_state.key() @ VaultError::Unauthorised,
+    )]
+    pub stream: Account<'info, Stream>,
+}
+
+/// Stop vesting now. What has vested stays claimable; the rest is released
+/// back to the vault's free balance.
+pub fn cancel_handler(ctx: Context<CancelStream>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
+
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    let stream = &mut ctx.accounts.stream;
+    let vault = &mut ctx.accounts.vault_state;
+
+    let vested = stream.vested(now)?;
+    let refunded = stream
+        .total
+        .checked_sub(vested)
+        .ok_or(VaultError::Overflow)?;
+    vault.stream_reserved = vault
+        .stream_reserved
+        .checked_sub(refunded)
+        .ok_or(VaultError::Overflow)?;
+
+    // Freeze the schedule so `vested()` returns exactly `vested` from now on
+    stream.total = vested;
+    stream.end = now.max(stream.start);
+    stream.cliff = stream.cliff.min(stream.end);
+
+    emit!(StreamCancelled {
+        vault: vault.key(),
+        stream: stream.key(),
+        recipient: stream.recipient,
+        vested,
+        refunded,
+        timestamp: now,
+    });
+    msg!(
+        "[vault] stream cancelled id={} vested={} refunded={}",
+        stream.stream_id,
+        vested,
+        refunded
+    );
+
+    if stream.withdrawn == stream.total {
+        vault.stream_count = vault
+            .stream_count
+            .checked_sub(1)
+            .ok_or(VaultError::Overflow)?;
+        stream.close(ctx.accounts.owner.to_account_info())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use crate::{
+    errors::VaultError,
+    events::TrancheReleased,
+    state::{Tranche, VaultState},
+};
+use anchor_lang::prelude::*;
+
+// ─── ReleaseTranche ──────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct ReleaseTranche<'info> {
+    /// Anyone may crank; the rent goes back to the depositor
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: rent destination, validated against `tranche.depositor`
+    #[account(mut, address = tranche.depositor @ VaultError::Unauthorised)]
+    pub depositor: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = depositor,
+        seeds = [b"tranche", vault_state.key().as_ref(), tranche.tranche_id.to_le_bytes().as_ref()],
+        bump  = tranche.bump,
+        constraint = tranche.vault == vault_state.key() @ VaultError::Unauthorised,
+    )]
+    pub tranche: Account<'info, Tranche>,
+}
+
+/// Retire a matured tranche, returning its tokens to the vault's free balance.
+pub fn release_handler(ctx: Context<ReleaseTranche>) -> Result<()> {
+    let clock = Clock::get()?;
+    let tranche = &ctx.accounts.tranche;
+    require!(
+        clock.unix_timestamp >= tranche.unlock_at,
+        VaultError::TrancheNotMatured
+    );
+
+    let vault = &mut ctx.accounts.vault_state;
+    vault.locked_amount = vault
+        .locked_amount
+        .checked_sub(tranche.amount)
+        .ok_or(VaultError::Overflow)?;
+    vault.tranche_count = vault
+        .tranche_count
+        .checked_sub(1)
+        .ok_or(VaultError::Overflow)?;
+
+    emit!(TrancheReleased {
+        vault: vault.key(),
+        tranche: tranche.key(),
+        depositor: tranche.depositor,
+        amount: tranche.amount,
+        timestamp: clock.unix_timestamp,
+    });
+    msg!(
+        "[vault] tranche released id={} amount={}",
+        tranche.tranche_id,
+        tranche.amount
+    );
+    Ok(())
+}
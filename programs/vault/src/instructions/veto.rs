@@ -0,0 +1,356 @@
+use crate::{
+    errors::VaultError,
+    events::{
+        VetoConfigChanged, VetoConfigQueued, VetoKeyAdded, VetoKeyRemovalQueued, VetoKeyRemoved,
+        WithdrawVetoed,
+    },
+    state::{PendingWithdrawal, VaultState, VetoKey, CONFIG_TIMELOCK_SECONDS},
+};
+use anchor_lang::prelude::*;
+
+// ─── SetVeto ─────────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct SetVeto<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+}
+
+fn apply(vault: &mut VaultState, key: Pubkey, threshold: u64, window: i64) {
+    let old_threshold = vault.veto_threshold;
+    let old_window = vault.veto_window;
+    vault.veto_threshold = threshold;
+    vault.veto_window = window;
+
+    emit!(VetoConfigChanged {
+        vault: key,
+        old_threshold,
+        new_threshold: threshold,
+        old_window,
+        new_window: window,
+    });
+    msg!(
+        "[vault] veto_threshold={} veto_window={}",
+        threshold,
+        window
+    );
+}
+
+/// Turning veto mode on, lengthening the window or lowering the threshold
+/// applies at once; anything weaker is queued for `CONFIG_TIMELOCK_SECONDS`.
+/// Any call replaces a previously queued change. A window needs a positive
+/// threshold: stream claims are capped to it and would otherwise stall.
+pub fn set_handler(ctx: Context<SetVeto>, threshold: u64, window: i64) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    require!(window >= 0, VaultError::InvalidVetoWindow);
+    require!(
+        window == 0 || threshold > 0,
+        VaultError::InvalidVetoThreshold
+    );
+
+    let key = ctx.accounts.vault_state.key();
+    let vault = &mut ctx.accounts.vault_state;
+
+    if !vault.loosens_veto(threshold, window) {
+        vault.pending_veto_threshold = 0;
+        vault.pending_veto_window = 0;
+        vault.veto_effective_at = 0;
+        apply(vault, key, threshold, window);
+        return Ok(());
+    }
+
+    let effective_at = Clock::get()?
+        .unix_timestamp
+        .checked_add(CONFIG_TIMELOCK_SECONDS)
+        .ok_or(VaultError::Overflow)?;
+    vault.pending_veto_threshold = threshold;
+    vault.pending_veto_window = window;
+    vault.veto_effective_at = effective_at;
+
+    emit!(VetoConfigQueued {
+        vault: key,
+        threshold,
+        window,
+        effective_at,
+    });
+    msg!("[vault] veto change queued effective_at={}", effective_at);
+    Ok(())
+}
+
+pub fn apply_handler(ctx: Context<SetVeto>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    let key = ctx.accounts.vault_state.key();
+    let vault = &mut ctx.accounts.vault_state;
+
+    require!(vault.veto_effective_at != 0, VaultError::NoPendingConfig);
+    require!(
+        Clock::get()?.unix_timestamp >= vault.veto_effective_at,
+        VaultError::ConfigTimelockActive
+    );
+
+    let (threshold, window) = (vault.pending_veto_threshold, vault.pending_veto_window);
+    vault.pending_veto_threshold = 0;
+    vault.pending_veto_window = 0;
+    vault.veto_effective_at = 0;
+    apply(vault, key, threshold, window);
+    Ok(())
+}
+
+// ─── AddVetoKey ──────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct AddVetoKey<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    /// CHECK: arbitrary pubkey allowed to veto
+    pub key: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = VetoKey::LEN,
+        seeds = [b"veto", vault_state.key().as_ref(), key.key().as_ref()],
+        bump,
+    )]
+    pub veto_key: Account<'info, VetoKey>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_key_handler(ctx: Context<AddVetoKey>) -> Result<()> {
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), Clock::get()?.unix_timestamp);
+
+    let vault = &mut ctx.accounts.vault_state;
+    vault.veto_key_count = vault
+        .veto_key_count
+        .checked_add(1)
+        .ok_or(VaultError::Overflow)?;
+
+    let veto_key = &mut ctx.accounts.veto_key;
+    veto_key.vault = vault.key();
+    veto_key.key = ctx.accounts.key.key();
+    veto_key.removable_at = 0;
+    veto_key.recovery_count = 0;
+    veto_key.bump = ctx.bumps.veto_key;
+
+    emit!(VetoKeyAdded {
+        vault: veto_key.vault,
+        key: veto_key.key,
+    });
+    msg!("[vault] veto key added key={}", veto_key.key);
+    Ok(())
+}
+
+// ─── RequestVetoKeyRemoval ───────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct RequestVetoKeyRemoval<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        seeds = [b"veto", vault_state.key().as_ref(), veto_key.key.as_ref()],
+        bump  = veto_key.bump,
+        constraint = veto_key.vault == vault_state.key() @ VaultError::Unauthorised,
+    )]
+    pub veto_key: Account<'info, VetoKey>,
+}
+
+/// Start the `CONFIG_TIMELOCK_SECONDS` countdown after which `remove_veto_key`
+/// may close `veto_key`. The key keeps its veto power until then.
+pub fn request_key_removal_handler(ctx: Context<RequestVetoKeyRemoval>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), now);
+
+    let effective_at = now
+        .checked_add(CONFIG_TIMELOCK_SECONDS)
+        .ok_or(VaultError::Overflow)?;
+
+    let veto_key = &mut ctx.accounts.veto_key;
+    veto_key.removable_at = effective_at;
+    veto_key.recovery_count = ctx.accounts.vault_state.recovery_count;
+
+    emit!(VetoKeyRemovalQueued {
+        vault: veto_key.vault,
+        key: veto_key.key,
+        effective_at,
+    });
+    msg!(
+        "[vault] veto key removal queued key={} effective_at={}",
+        veto_key.key,
+        effective_at
+    );
+    Ok(())
+}
+
+// ─── RemoveVetoKey ───────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct RemoveVetoKey<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        has_one = owner @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        // A veto key cannot be pulled while a withdrawal it might veto is pending
+        constraint = vault_state.pending_withdrawal_count == 0 @ VaultError::PendingWithdrawalsOutstanding,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"veto", vault_state.key().as_ref(), veto_key.key.as_ref()],
+        bump  = veto_key.bump,
+        constraint = veto_key.vault == vault_state.key() @ VaultError::Unauthorised,
+    )]
+    pub veto_key: Account<'info, VetoKey>,
+}
+
+/// Close `veto_key` once its queued removal has matured; rent returns to the
+/// owner. A removal requested before the last recovery no longer counts.
+pub fn remove_key_handler(ctx: Context<RemoveVetoKey>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.owner.key(), now);
+
+    let veto_key = &ctx.accounts.veto_key;
+    require!(
+        veto_key.removable_at != 0
+            && veto_key.recovery_count == ctx.accounts.vault_state.recovery_count,
+        VaultError::NoPendingConfig
+    );
+    let removable_at = veto_key.removable_at;
+    require!(now >= removable_at, VaultError::ConfigTimelockActive);
+
+    let vault = &mut ctx.accounts.vault_state;
+    vault.veto_key_count = vault
+        .veto_key_count
+        .checked_sub(1)
+        .ok_or(VaultError::Overflow)?;
+
+    let veto_key = &ctx.accounts.veto_key;
+    emit!(VetoKeyRemoved {
+        vault: veto_key.vault,
+        key: veto_key.key,
+    });
+    msg!("[vault] veto key removed key={}", veto_key.key);
+    Ok(())
+}
+
+// ─── VetoWithdraw ────────────────────────────────────────────────────────────
+
+#[derive(Accounts)]
+pub struct VetoWithdraw<'info> {
+    pub vetoer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", vault_state.creator.as_ref(), vault_state.mint.as_ref()],
+        bump  = vault_state.bump,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        seeds = [b"veto", vault_state.key().as_ref(), vetoer.key().as_ref()],
+        bump  = veto_key.bump,
+        constraint = veto_key.vault == vault_state.key() @ VaultError::Unauthorised,
+    )]
+    pub veto_key: Account<'info, VetoKey>,
+
+    #[account(
+        mut,
+        close = requester,
+        seeds = [b"pending_withdrawal", vault_state.key().as_ref(), pending_withdrawal.request_id.to_le_bytes().as_ref()],
+        bump  = pending_withdrawal.bump,
+        constraint = pending_withdrawal.vault == vault_state.key() @ VaultError::Unauthorised,
+    )]
+    pub pending_withdrawal: Account<'info, PendingWithdrawal>,
+
+    /// CHECK: rent destination; validated against `pending_withdrawal.requester`
+    #[account(mut, address = pending_withdrawal.requester @ VaultError::Unauthorised)]
+    pub requester: UncheckedAccount<'info>,
+}
+
+/// Cancel a pending withdrawal above the veto threshold before it becomes
+/// executable, recording `reason`. This is the only thing a veto key can do;
+/// withdrawals queued only by a delay tier are out of its reach.
+pub fn veto_handler(ctx: Context<VetoWithdraw>, reason: u8) -> Result<()> {
+    let pending = &ctx.accounts.pending_withdrawal;
+    require!(
+        ctx.accounts.vault_state.requires_veto(pending.amount),
+        VaultError::VetoNotRequired
+    );
+    require!(
+        Clock::get()?.unix_timestamp < pending.execute_after,
+        VaultError::VetoWindowClosed
+    );
+
+    let vault = &mut ctx.accounts.vault_state;
+    vault.pending_withdrawal_count = vault
+        .pending_withdrawal_count
+        .checked_sub(1)
+        .ok_or(VaultError::Overflow)?;
+
+    emit!(WithdrawVetoed {
+        vault: pending.vault,
+        request_id: pending.request_id,
+        amount: pending.amount,
+        veto_key: ctx.accounts.vetoer.key(),
+        reason,
+    });
+    msg!(
+        "[vault] withdraw vetoed id={} by={} reason={}",
+        pending.request_id,
+        ctx.accounts.vetoer.key(),
+        reason
+    );
+    Ok(())
+}
@@ -1,110 +1,125 @@
-use crate::{errors::VaultError, events::WithdrawMade, state::VaultState};
+use crate::{
+    errors::VaultError,
+    events::WithdrawMade,
+    policy::{check_outflow, Outflow},
+    state::{Policy, Role, VaultState, PAUSE_WITHDRAWALS, ROLE_WITHDRAWER},
+    utils::transfer_from_vault,
+};
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
-
-const DAY_SECONDS: i64 = 86_400;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[derive(Accounts)]
 pub struct Withdraw<'info> {
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub authority: Signer<'info>,
 
-    pub mint: Account<'info, Mint>,
+    pub mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
-        seeds = [b"vault", owner.key().as_ref(), mint.key().as_ref()],
+        seeds = [b"vault", vault_state.creator.as_ref(), mint.key().as_ref()],
         bump  = vault_state.bump,
-        has_one = owner @ VaultError::Unauthorised,
+        constraint = vault_state.authorized(&authority.key(), role.as_deref(), ROLE_WITHDRAWER) @ VaultError::Unauthorised,
         has_one = mint  @ VaultError::Unauthorised,
+        constraint = !vault_state.multisig @ VaultError::MultisigRequired,
+        constraint = !vault_state.pooled() @ VaultError::PooledVault,
+        constraint = vault_state.withdraw_tier_count == 0 @ VaultError::WithdrawQueueRequired,
+        constraint = vault_state.version == VaultState::VERSION @ VaultError::AccountNotMigrated,
     )]
     pub vault_state: Account<'info, VaultState>,
 
+    /// `authority`'s role on this vault; not needed when it is the owner
+    #[account(
+        seeds = [b"role", vault_state.key().as_ref(), authority.key().as_ref()],
+        bump  = role.bump,
+    )]
+    pub role: Option<Account<'info, Role>>,
+
     #[account(
         mut,
         address = vault_state.vault_ata @ VaultError::Unauthorised,
     )]
-    pub vault_ata: Account<'info, TokenAccount>,
+    pub vault_ata: InterfaceAccount<'info, TokenAccount>,
+
+    /// Any token account of the vault's mint — the owner's own or a third party's
+    #[account(
+        mut,
+        constraint = destination.mint == mint.key() @ VaultError::Unauthorised,
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
 
+    /// The vault's `Policy`; required while it has rules
     #[account(
         mut,
-        constraint = owner_ata.owner == owner.key()  @ VaultError::Unauthorised,
-        constraint = owner_ata.mint  == mint.key()   @ VaultError::Unauthorised,
+        seeds = [b"policy", vault_state.key().as_ref()],
+        bump  = policy.bump,
     )]
-    pub owner_ata: Account<'info, TokenAccount>,
+    pub policy: Option<Account<'info, Policy>>,
 
-    pub token_program: Program<'info, Token>,
+    /// The vault's co-signer; required above `cosign_threshold`
+    #[account(address = vault_state.cosigner @ VaultError::Unauthorised)]
+    pub cosigner: Option<Signer<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn handler(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
-    require!(amount > 0, VaultError::ZeroAmount);
+    ctx.accounts
+        .vault_state
+        .heartbeat(&ctx.accounts.authority.key(), Clock::get()?.unix_timestamp);
 
-    let vault_state_ai = ctx.accounts.vault_state.to_account_info();
     let vault = &mut ctx.accounts.vault_state;
     require!(!vault.locked, VaultError::VaultLocked);
+    vault.require_not_paused(PAUSE_WITHDRAWALS)?;
 
     let clock = Clock::get()?;
+    check_outflow(
+        vault,
+        ctx.accounts.policy.as_deref_mut(),
+        None,
+        &Outflow {
+            amount,
+            recipient: ctx.accounts.destination.owner,
+            now: clock.unix_timestamp,
+            cosigned: ctx.accounts.cosigner.is_some(),
+            vetted: false,
+        },
+    )?;
 
-    // Roll the 24-h window if necessary
-    if clock.unix_timestamp - vault.window_start >= DAY_SECONDS {
-        vault.window_start = clock.unix_timestamp;
-        vault.withdrawn_today = 0;
-    }
-
-    if vault.daily_withdraw_limit > 0 {
-        let new_today = vault
-            .withdrawn_today
-            .checked_add(amount)
-            .ok_or(VaultError::Overflow)?;
-        require!(
-            new_today <= vault.daily_withdraw_limit,
-            VaultError::DailyLimitExceeded
-        );
-        vault.withdrawn_today = new_today;
-    }
-
-    require!(
-        ctx.accounts.vault_ata.amount >= amount,
-        VaultError::InsufficientFunds
-    );
+    vault.require_available(ctx.accounts.vault_ata.amount, amount)?;
 
     vault.locked = true;
 
-    // PDA signer seeds
-    let owner_key = vault.owner;
-    let mint_key = vault.mint;
-    let bump = vault.bump;
-    let seeds = &[b"vault", owner_key.as_ref(), mint_key.as_ref(), &[bump]];
-    let signer = &[&seeds[..]];
-
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.key(),
-        Transfer {
-            from: ctx.accounts.vault_ata.to_account_info(),
-            to: ctx.accounts.owner_ata.to_account_info(),
-            authority: vault_state_ai,
-        },
-        signer,
-    );
-    token::transfer(cpi_ctx, amount)?;
+    let received = transfer_from_vault(
+        vault,
+        &ctx.accounts.vault_ata,
+        &mut ctx.accounts.destination,
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
 
     vault.total_withdrawn = vault
         .total_withdrawn
-        .checked_add(amount)
+        .checked_add(received)
         .ok_or(VaultError::Overflow)?;
     vault.locked = false;
 
     emit!(WithdrawMade {
         vault: vault.key(),
-        recipient: ctx.accounts.owner.key(),
-        amount,
+        mint: vault.mint,
+        recipient: ctx.accounts.destination.owner,
+        amount: received,
+        fee: amount.saturating_sub(received),
+        shares: 0,
         by_delegate: false,
         timestamp: clock.unix_timestamp,
     });
 
     msg!(
-        "[vault] withdraw amount={} total_withdrawn={}",
+        "[vault] withdraw amount={} received={} total_withdrawn={}",
         amount,
+        received,
         vault.total_withdrawn
     );
     Ok(())
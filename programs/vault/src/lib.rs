@@ -2,20 +2,34 @@
 
 //! # Vault — Solana Anchor Program
 //!
-//! Secure SPL-token vault with:
-//!  - owner deposit / withdraw
+//! Secure SPL Token / Token-2022 vault with:
+//!  - owner withdraw; owner-only, open or allowlisted deposits, optionally time-locked
+//!  - optional extra mints (`VaultAsset`) with per-mint limits and delegates
+//!  - optional pooled mode with share-token accounting
+//!  - idle funds can be allocated to a yield strategy through a CPI adapter
 //!  - time-limited, capped delegate withdrawals
+//!  - per-vault withdrawal policy rules checked on every owner and delegate withdrawal
+//!  - optional queued owner withdrawals with amount-tiered cooldowns
+//!  - optional co-signer for withdrawals above a threshold
+//!  - optional veto window on large withdrawals for registered veto keys
+//!  - guardian-based social recovery of a lost owner key
+//!  - dead-man switch: a beneficiary may claim after the owner's inactivity period
 //!  - checked arithmetic, verified CPIs, canonical PDAs
+//!  - fee-aware accounting for Token-2022 transfer-fee mints
 //!  - on-chain events for off-chain observability
 
 use anchor_lang::prelude::*;
 
+pub mod adapter;
 pub mod errors;
 pub mod events;
 pub mod instructions;
+pub mod policy;
 pub mod state;
+pub mod utils;
 
 use instructions::*;
+use state::{DelayTier, DelegateUpdate, DepositMode, ProposalAction, Rule};
 
 declare_id!("31mDBe7jLM8UVoqfBCUpC5yKsijh3uoKekKgRR1Z7VCJ");
 
@@ -32,19 +46,95 @@ pub mod vault {
         initialize::handler(ctx, max_deposit, daily_withdraw_limit)
     }
 
-    /// Deposit SPL tokens from owner into the vault PDA ATA.
-    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
-        deposit::handler(ctx, amount)
+    /// Deposit tokens into the vault PDA ATA (owner or permitted third party),
+    /// optionally locked in a tranche until `unlock_at`.
+    pub fn deposit(ctx: Context<Deposit>, amount: u64, unlock_at: Option<i64>) -> Result<()> {
+        deposit::handler(ctx, amount, unlock_at)
     }
 
-    /// Withdraw SPL tokens back to owner (full authority).
+    /// Owner withdraws to any token account of the vault's mint.
     pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
         withdraw::handler(ctx, amount)
     }
 
-    /// Grant a delegate capped, time-limited withdraw authority.
-    pub fn add_delegate(ctx: Context<AddDelegate>, allowance: u64, expires_at: i64) -> Result<()> {
-        delegate::add_handler(ctx, allowance, expires_at)
+    /// Set the cooldown tiers of queued mode (owner only); an empty list
+    /// leaves it. Shortening any delay is timelocked like `update_config`.
+    pub fn set_withdraw_tiers(ctx: Context<SetWithdrawTiers>, tiers: Vec<DelayTier>) -> Result<()> {
+        queue::set_tiers_handler(ctx, tiers)
+    }
+
+    /// Apply cooldown tiers queued by `set_withdraw_tiers` once the timelock passes.
+    pub fn apply_withdraw_tiers(ctx: Context<ApplyWithdrawTiers>) -> Result<()> {
+        queue::apply_tiers_handler(ctx)
+    }
+
+    /// Queued mode: open a pending withdrawal whose delay depends on `amount`.
+    pub fn request_withdraw(
+        ctx: Context<RequestWithdraw>,
+        request_id: u64,
+        amount: u64,
+    ) -> Result<()> {
+        queue::request_handler(ctx, request_id, amount)
+    }
+
+    /// Pay out a pending withdrawal after its cooldown (anyone may call).
+    pub fn execute_withdraw(ctx: Context<ExecuteWithdraw>) -> Result<()> {
+        queue::execute_handler(ctx)
+    }
+
+    /// Abort a pending withdrawal (owner or guardian).
+    pub fn cancel_withdraw(ctx: Context<CancelWithdraw>) -> Result<()> {
+        queue::cancel_handler(ctx)
+    }
+
+    /// Route withdrawals above `threshold` through a `window`-second veto
+    /// period (0 = off). Weakening it is timelocked.
+    pub fn set_veto(ctx: Context<SetVeto>, threshold: u64, window: i64) -> Result<()> {
+        veto::set_handler(ctx, threshold, window)
+    }
+
+    /// Apply a queued veto change once its timelock has passed.
+    pub fn apply_veto(ctx: Context<SetVeto>) -> Result<()> {
+        veto::apply_handler(ctx)
+    }
+
+    /// Register `key` as a veto key (owner only).
+    pub fn add_veto_key(ctx: Context<AddVetoKey>) -> Result<()> {
+        veto::add_key_handler(ctx)
+    }
+
+    /// Queue the removal of a veto key behind the config timelock.
+    pub fn request_veto_key_removal(ctx: Context<RequestVetoKeyRemoval>) -> Result<()> {
+        veto::request_key_removal_handler(ctx)
+    }
+
+    /// Remove a veto key once its queued removal has matured; refused while
+    /// withdrawals are pending.
+    pub fn remove_veto_key(ctx: Context<RemoveVetoKey>) -> Result<()> {
+        veto::remove_key_handler(ctx)
+    }
+
+    /// Veto a pending withdrawal within its window, with a reason code.
+    pub fn veto_withdraw(ctx: Context<VetoWithdraw>, reason: u8) -> Result<()> {
+        veto::veto_handler(ctx, reason)
+    }
+
+    /// Grant a delegate capped, time-limited withdraw authority; a non-zero
+    /// `period` refills the allowance every `period` seconds.
+    pub fn add_delegate(
+        ctx: Context<AddDelegate>,
+        allowance: u64,
+        expires_at: i64,
+        period: i64,
+        max_periods: u32,
+    ) -> Result<()> {
+        delegate::add_handler(ctx, allowance, expires_at, period, max_periods)
+    }
+
+    /// Change a delegate's allowance, expiry, activation time or pause flag,
+    /// keeping its `used` history.
+    pub fn update_delegate(ctx: Context<UpdateDelegate>, update: DelegateUpdate) -> Result<()> {
+        delegate::update_handler(ctx, update)
     }
 
     /// Revoke an existing delegate.
@@ -52,13 +142,349 @@ pub mod vault {
         delegate::remove_handler(ctx)
     }
 
+    /// Delegate gives up its own record; the rent returns to the owner.
+    pub fn renounce_delegate(ctx: Context<RenounceDelegate>) -> Result<()> {
+        delegate::renounce_handler(ctx)
+    }
+
+    /// Anyone closes an expired or exhausted delegate record; rent goes to the owner.
+    pub fn prune_delegate(ctx: Context<PruneDelegate>) -> Result<()> {
+        prune::prune_handler(ctx)
+    }
+
+    /// Owner closes many spent delegate records passed in `remaining_accounts`.
+    pub fn prune_delegates<'info>(ctx: Context<'info, PruneDelegates<'info>>) -> Result<()> {
+        prune::prune_many_handler(ctx)
+    }
+
     /// Delegate exercises partial withdrawal within allowance.
     pub fn delegate_withdraw(ctx: Context<DelegateWithdraw>, amount: u64) -> Result<()> {
         delegate::withdraw_handler(ctx, amount)
     }
 
-    /// Close vault, burn rent to owner.
-    pub fn close_vault(ctx: Context<CloseVault>) -> Result<()> {
+    /// Reserve part of the vault for a linear payment stream to `recipient`.
+    pub fn create_stream(
+        ctx: Context<CreateStream>,
+        stream_id: u64,
+        total: u64,
+        start: i64,
+        cliff: i64,
+        end: i64,
+    ) -> Result<()> {
+        stream::create_handler(ctx, stream_id, total, start, cliff, end)
+    }
+
+    /// Stream recipient claims everything accrued so far.
+    pub fn claim_stream(ctx: Context<ClaimStream>) -> Result<()> {
+        stream::claim_handler(ctx)
+    }
+
+    /// Stop a stream; the unvested part returns to the vault's free balance.
+    pub fn cancel_stream(ctx: Context<CancelStream>) -> Result<()> {
+        stream::cancel_handler(ctx)
+    }
+
+    /// Retire a matured lock-up tranche (permissionless crank).
+    pub fn release_tranche(ctx: Context<ReleaseTranche>) -> Result<()> {
+        tranche::release_handler(ctx)
+    }
+
+    /// Switch an empty vault to pooled mode with a vault-PDA share mint.
+    pub fn enable_pool(ctx: Context<EnablePool>) -> Result<()> {
+        pool::enable_handler(ctx)
+    }
+
+    /// Pooled mode: burn shares to redeem their part of the vault.
+    pub fn withdraw_shares(ctx: Context<WithdrawShares>, shares: u64) -> Result<()> {
+        pool::withdraw_handler(ctx, shares)
+    }
+
+    /// Queue a yield strategy program and its state account, or clear it at
+    /// once; nothing may be allocated.
+    pub fn set_strategy(
+        ctx: Context<SetStrategy>,
+        strategy: Pubkey,
+        strategy_state: Pubkey,
+    ) -> Result<()> {
+        strategy::set_handler(ctx, strategy, strategy_state)
+    }
+
+    /// Apply the strategy queued by `set_strategy` once the timelock has passed.
+    pub fn apply_strategy(ctx: Context<SetStrategy>) -> Result<()> {
+        strategy::apply_handler(ctx)
+    }
+
+    /// Move idle funds into the strategy (counts against the daily limit).
+    pub fn allocate(ctx: Context<MoveStrategyFunds>, amount: u64) -> Result<()> {
+        strategy::allocate_handler(ctx, amount)
+    }
+
+    /// Pull funds back from the strategy into the vault ATA.
+    pub fn deallocate(ctx: Context<MoveStrategyFunds>, amount: u64) -> Result<()> {
+        strategy::deallocate_handler(ctx, amount)
+    }
+
+    /// Refresh the strategy's reported value (permissionless crank).
+    pub fn report_strategy(ctx: Context<ReportStrategy>) -> Result<()> {
+        strategy::report_handler(ctx)
+    }
+
+    /// Register another mint on the vault, with its own limits.
+    pub fn add_asset(
+        ctx: Context<AddAsset>,
+        max_deposit: u64,
+        daily_withdraw_limit: u64,
+    ) -> Result<()> {
+        asset::add_handler(ctx, max_deposit, daily_withdraw_limit)
+    }
+
+    /// Unregister an empty asset and close its token account.
+    pub fn remove_asset(ctx: Context<RemoveAsset>) -> Result<()> {
+        asset::remove_handler(ctx)
+    }
+
+    /// Deposit a registered asset (same deposit-mode rules as `deposit`).
+    pub fn deposit_asset(ctx: Context<DepositAsset>, amount: u64) -> Result<()> {
+        asset::deposit_handler(ctx, amount)
+    }
+
+    /// Owner withdraws a registered asset, within that asset's daily limit.
+    pub fn withdraw_asset(ctx: Context<WithdrawAsset>, amount: u64) -> Result<()> {
+        asset::withdraw_handler(ctx, amount)
+    }
+
+    /// Grant a delegate an allowance in a registered asset's mint.
+    pub fn add_asset_delegate(
+        ctx: Context<AddAssetDelegate>,
+        allowance: u64,
+        expires_at: i64,
+        period: i64,
+        max_periods: u32,
+    ) -> Result<()> {
+        asset::add_delegate_handler(ctx, allowance, expires_at, period, max_periods)
+    }
+
+    /// Delegate withdraws a registered asset within its allowance.
+    pub fn delegate_withdraw_asset(ctx: Context<DelegateWithdrawAsset>, amount: u64) -> Result<()> {
+        asset::delegate_withdraw_handler(ctx, amount)
+    }
+
+    /// Realloc a vault written by an older layout and bump its version.
+    pub fn migrate_vault(ctx: Context<MigrateVault>) -> Result<()> {
+        migrate::vault_handler(ctx)
+    }
+
+    /// Realloc a delegate record written by an older layout and bump its version.
+    pub fn migrate_delegate(ctx: Context<MigrateDelegate>) -> Result<()> {
+        migrate::delegate_handler(ctx)
+    }
+
+    /// Close vault (and any delegate records passed along), rent to owner.
+    pub fn close_vault<'info>(ctx: Context<'info, CloseVault<'info>>) -> Result<()> {
         close::handler(ctx)
     }
+
+    /// Change limits: tightening is immediate, loosening is timelocked.
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        max_deposit: u64,
+        daily_withdraw_limit: u64,
+    ) -> Result<()> {
+        config::update_handler(ctx, max_deposit, daily_withdraw_limit)
+    }
+
+    /// Apply a queued limit change once its timelock has passed.
+    pub fn apply_config(ctx: Context<UpdateConfig>) -> Result<()> {
+        config::apply_handler(ctx)
+    }
+
+    /// Require `cosigner` to also sign withdrawals above `threshold`
+    /// (`Pubkey::default()` removes it). Weakening the requirement is timelocked.
+    pub fn set_cosigner(ctx: Context<SetCosigner>, cosigner: Pubkey, threshold: u64) -> Result<()> {
+        cosigner::set_handler(ctx, cosigner, threshold)
+    }
+
+    /// Apply a queued co-signer change once its timelock has passed.
+    pub fn apply_cosigner(ctx: Context<SetCosigner>) -> Result<()> {
+        cosigner::apply_handler(ctx)
+    }
+
+    /// Set, replace or remove (`Pubkey::default()`) the vault guardian.
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+        guardian::set_handler(ctx, guardian)
+    }
+
+    /// Owner or guardian pauses any of deposits / withdrawals / delegate withdrawals.
+    pub fn pause(ctx: Context<Pause>, flags: u8) -> Result<()> {
+        guardian::pause_handler(ctx, flags)
+    }
+
+    /// Guardian starts the delay after which it may lift `flags` itself.
+    pub fn request_unpause(ctx: Context<RequestUnpause>, flags: u8) -> Result<()> {
+        guardian::request_unpause_handler(ctx, flags)
+    }
+
+    /// Owner lifts a pause at once; guardian only after `request_unpause` + delay.
+    pub fn unpause(ctx: Context<Unpause>, flags: u8) -> Result<()> {
+        guardian::unpause_handler(ctx, flags)
+    }
+
+    /// Choose who may deposit: owner only, anyone, or allowlisted keys.
+    pub fn set_deposit_mode(ctx: Context<SetDepositMode>, mode: DepositMode) -> Result<()> {
+        depositor::set_mode_handler(ctx, mode)
+    }
+
+    /// Allowlist a depositor for `DepositMode::Allowlist`.
+    pub fn add_depositor(ctx: Context<AddDepositor>) -> Result<()> {
+        depositor::add_handler(ctx)
+    }
+
+    /// Remove a depositor from the allowlist.
+    pub fn remove_depositor(ctx: Context<RemoveDepositor>) -> Result<()> {
+        depositor::remove_handler(ctx)
+    }
+
+    /// Append a withdrawal rule to the vault's policy (owner only).
+    pub fn add_policy_rule(ctx: Context<AddPolicyRule>, rule: Rule) -> Result<()> {
+        rule::add_handler(ctx, rule)
+    }
+
+    /// Remove the policy rule at `index` (owner only).
+    pub fn remove_policy_rule(ctx: Context<RemovePolicyRule>, index: u8) -> Result<()> {
+        rule::remove_handler(ctx, index)
+    }
+
+    /// Grant `member` the `ROLE_*` bits in `permissions` (replacing any it held).
+    pub fn grant_role(ctx: Context<GrantRole>, permissions: u8) -> Result<()> {
+        role::grant_handler(ctx, permissions)
+    }
+
+    /// Revoke a role and reclaim its rent.
+    pub fn revoke_role(ctx: Context<RevokeRole>) -> Result<()> {
+        role::revoke_handler(ctx)
+    }
+
+    /// Nominate a new owner (step 1 of 2). `Pubkey::default()` cancels.
+    pub fn propose_owner_transfer(
+        ctx: Context<ProposeOwnerTransfer>,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        ownership::propose_handler(ctx, new_owner)
+    }
+
+    /// Nominated owner signs to take over the vault (step 2 of 2).
+    pub fn accept_owner_transfer(ctx: Context<AcceptOwnerTransfer>) -> Result<()> {
+        ownership::accept_handler(ctx)
+    }
+
+    /// Register or replace the recovery guardian set (owner only).
+    pub fn set_recovery(
+        ctx: Context<SetRecovery>,
+        guardians: Vec<Pubkey>,
+        threshold: u8,
+        delay: i64,
+    ) -> Result<()> {
+        recovery::set_handler(ctx, guardians, threshold, delay)
+    }
+
+    /// Remove the recovery guardian set and reclaim its rent.
+    pub fn remove_recovery(ctx: Context<RemoveRecovery>) -> Result<()> {
+        recovery::remove_handler(ctx)
+    }
+
+    /// Guardian starts a recovery naming `new_owner` (counts as its approval).
+    pub fn initiate_recovery(ctx: Context<GuardRecovery>, new_owner: Pubkey) -> Result<()> {
+        recovery::initiate_handler(ctx, new_owner)
+    }
+
+    /// Guardian approves the recovery in progress.
+    pub fn approve_recovery(ctx: Context<GuardRecovery>) -> Result<()> {
+        recovery::approve_handler(ctx)
+    }
+
+    /// Owner cancels the recovery in progress.
+    pub fn cancel_recovery(ctx: Context<CancelRecovery>) -> Result<()> {
+        recovery::cancel_handler(ctx)
+    }
+
+    /// Hand the vault to the recovery's new owner once its delay has passed.
+    pub fn complete_recovery(ctx: Context<CompleteRecovery>) -> Result<()> {
+        recovery::complete_handler(ctx)
+    }
+
+    /// Set the dead-man switch beneficiary, inactivity period and claim
+    /// limit; `Pubkey::default()` turns it off.
+    pub fn set_beneficiary(
+        ctx: Context<SetBeneficiary>,
+        beneficiary: Pubkey,
+        inactivity_period: i64,
+        claim_limit: u64,
+    ) -> Result<()> {
+        inheritance::set_handler(ctx, beneficiary, inactivity_period, claim_limit)
+    }
+
+    /// Owner refreshes its heartbeat without doing anything else.
+    pub fn ping(ctx: Context<Ping>) -> Result<()> {
+        inheritance::ping_handler(ctx)
+    }
+
+    /// Beneficiary claims from the vault after the owner's inactivity period.
+    pub fn claim_inheritance(ctx: Context<ClaimInheritance>, amount: u64) -> Result<()> {
+        inheritance::claim_handler(ctx, amount)
+    }
+
+    /// Hand withdrawals, delegate changes and closing to an N-of-M signer set.
+    pub fn enable_multisig(
+        ctx: Context<EnableMultisig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        multisig::enable_handler(ctx, signers, threshold)
+    }
+
+    /// Multisig signer proposes an action (counts as its first approval).
+    pub fn create_proposal(ctx: Context<CreateProposal>, action: ProposalAction) -> Result<()> {
+        multisig::create_handler(ctx, action)
+    }
+
+    /// Multisig signer approves a pending proposal.
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+        multisig::approve_handler(ctx)
+    }
+
+    /// Proposer withdraws its proposal and reclaims rent.
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        multisig::cancel_handler(ctx)
+    }
+
+    /// Execute an approved `Withdraw` proposal.
+    pub fn execute_withdraw_proposal(ctx: Context<ExecuteWithdrawProposal>) -> Result<()> {
+        multisig::execute_withdraw_handler(ctx)
+    }
+
+    /// Execute an approved `AddDelegate` proposal.
+    pub fn execute_add_delegate_proposal(ctx: Context<ExecuteAddDelegateProposal>) -> Result<()> {
+        multisig::execute_add_delegate_handler(ctx)
+    }
+
+    /// Execute an approved `RemoveDelegate` proposal.
+    pub fn execute_remove_delegate_proposal(
+        ctx: Context<ExecuteRemoveDelegateProposal>,
+    ) -> Result<()> {
+        multisig::execute_remove_delegate_handler(ctx)
+    }
+
+    /// Execute an approved `UpdateDelegate` proposal.
+    pub fn execute_update_delegate_proposal(
+        ctx: Context<ExecuteUpdateDelegateProposal>,
+    ) -> Result<()> {
+        multisig::execute_update_delegate_handler(ctx)
+    }
+
+    /// Execute an approved `CloseVault` proposal.
+    pub fn execute_close_vault_proposal<'info>(
+        ctx: Context<'info, ExecuteCloseVaultProposal<'info>>,
+    ) -> Result<()> {
+        multisig::execute_close_vault_handler(ctx)
+    }
 }
@@ -0,0 +1,151 @@
+//! Withdrawal policy engine.
+//!
+//! Every outflow of the vault's own mint — owner, delegate and multisig
+//! withdrawals, stream and inheritance claims, share redemptions and strategy
+//! allocations — clears through [`check_outflow`], which applies, in order:
+//!
+//! 1. a non-zero amount
+//! 2. the co-signer's signature, above `cosign_threshold`
+//! 3. a passed veto window, above `veto_threshold`
+//! 4. the delegate's allowance, for delegate withdrawals
+//! 5. every rule of the vault's `Policy`, if it has one
+//! 6. the vault-wide `daily_withdraw_limit`, unless the delegate is exempt
+//!
+//! A failing rule is reported with its own `Policy*` error and logged with
+//! its index in `Policy::rules`.
+
+use crate::{
+    errors::VaultError,
+    state::{DelegateRecord, Policy, PolicyRule, Rule, VaultState, DAY_SECONDS},
+};
+use anchor_lang::prelude::*;
+
+/// One withdrawal about to leave the vault.
+pub struct Outflow {
+    pub amount: u64,
+    /// Owner of the destination token account
+    pub recipient: Pubkey,
+    pub now: i64,
+    /// Whether the vault's co-signer signed the transaction
+    pub cosigned: bool,
+    /// Whether the outflow already sat out a full veto window as a
+    /// `PendingWithdrawal`
+    pub vetted: bool,
+}
+
+/// Run every check on `outflow`, charging the stateful ones (allowance,
+/// windowed rules, daily limit). `policy` must be passed whenever the vault
+/// has policy rules.
+pub fn check_outflow(
+    vault: &mut VaultState,
+    policy: Option<&mut Policy>,
+    mut delegate: Option<&mut DelegateRecord>,
+    outflow: &Outflow,
+) -> Result<()> {
+    require!(outflow.amount > 0, VaultError::ZeroAmount);
+    require!(
+        outflow.cosigned || !vault.requires_cosigner(outflow.amount),
+        VaultError::CosignatureRequired
+    );
+    require!(
+        outflow.vetted || !vault.requires_veto(outflow.amount),
+        VaultError::VetoWindowRequired
+    );
+
+    let mut exempt = false;
+    let mut drawn = None;
+    if let Some(rec) = delegate.as_deref_mut() {
+        exempt = rec.exempt_from_daily_limit;
+        drawn = Some(rec.check_draw(outflow.amount, outflow.now)?);
+    }
+
+    match policy {
+        Some(policy) => policy.apply(outflow)?,
+        None => require!(
+            vault.policy_rule_count == 0,
+            VaultError::PolicyAccountMismatch
+        ),
+    }
+
+    if !exempt {
+        vault.charge_daily_limit(outflow.amount, outflow.now)?;
+    }
+    if let (Some(rec), Some(used)) = (delegate, drawn) {
+        rec.used = used;
+    }
+    Ok(())
+}
+
+impl Rule {
+    /// Fail unless the parameters can ever let a withdrawal through.
+    pub fn validate(&self) -> Result<()> {
+        let valid = match *self {
+            Rule::MaxPerTransaction { max } => max > 0,
+            Rule::MinAmount { min } => min > 0,
+            Rule::RecipientCap { recipient, cap } => recipient != Pubkey::default() && cap > 0,
+            Rule::MaxWithdrawals { count, window } => count > 0 && window > 0,
+        };
+        require!(valid, VaultError::InvalidPolicyRule);
+        Ok(())
+    }
+}
+
+impl PolicyRule {
+    /// Check `outflow` against this rule, counting it if the rule is windowed.
+    fn apply(&mut self, outflow: &Outflow) -> Result<()> {
+        match self.rule {
+            Rule::MaxPerTransaction { max } => {
+                require!(outflow.amount <= max, VaultError::PolicyMaxPerTransaction);
+            }
+            Rule::MinAmount { min } => {
+                require!(outflow.amount >= min, VaultError::PolicyMinAmount);
+            }
+            Rule::RecipientCap { recipient, cap } => {
+                if outflow.recipient == recipient {
+                    self.roll(DAY_SECONDS, outflow.now);
+                    let used = self
+                        .used
+                        .checked_add(outflow.amount)
+                        .ok_or(VaultError::Overflow)?;
+                    require!(used <= cap, VaultError::PolicyRecipientCap);
+                    self.used = used;
+                }
+            }
+            Rule::MaxWithdrawals { count, window } => {
+                self.roll(window, outflow.now);
+                require!(
+                    self.used < u64::from(count),
+                    VaultError::PolicyMaxWithdrawals
+                );
+                self.used += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Start a new window at `now` once `window` seconds have passed.
+    fn roll(&mut self, window: i64, now: i64) {
+        if now - self.window_start >= window {
+            self.window_start = now;
+            self.used = 0;
+        }
+    }
+}
+
+impl Policy {
+    /// Apply every rule to `outflow`, stopping at the first that fails.
+    pub fn apply(&mut self, outflow: &Outflow) -> Result<()> {
+        for (i, rule) in self.rules.iter_mut().enumerate() {
+            if let Err(e) = rule.apply(outflow) {
+                msg!(
+                    "[vault] policy rule {} ({:?}) rejected amount={}",
+                    i,
+                    rule.rule,
+                    outflow.amount
+                );
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,18 +1,76 @@
+use crate::errors::VaultError;
 use anchor_lang::prelude::*;
 
-/// Central vault state account (PDA, seeds = [b"vault", owner])
+/// Length of the rolling withdrawal window
+pub const DAY_SECONDS: i64 = 86_400;
+
+/// Delay before a loosened `max_deposit` / `daily_withdraw_limit` takes effect
+pub const CONFIG_TIMELOCK_SECONDS: i64 = 2 * DAY_SECONDS;
+
+/// `VaultState::paused` bits
+pub const PAUSE_DEPOSITS: u8 = 1 << 0;
+pub const PAUSE_WITHDRAWALS: u8 = 1 << 1;
+pub const PAUSE_DELEGATE_WITHDRAWALS: u8 = 1 << 2;
+pub const PAUSE_ALL: u8 = PAUSE_DEPOSITS | PAUSE_WITHDRAWALS | PAUSE_DELEGATE_WITHDRAWALS;
+
+/// Delay before a guardian (as opposed to the owner) may lift a pause
+pub const GUARDIAN_UNPAUSE_DELAY: i64 = 3 * DAY_SECONDS;
+
+/// `Role::permissions` bits. The owner implicitly holds all of them.
+pub const ROLE_DEPOSITOR: u8 = 1 << 0;
+pub const ROLE_WITHDRAWER: u8 = 1 << 1;
+pub const ROLE_DELEGATE_MANAGER: u8 = 1 << 2;
+pub const ROLE_CONFIG_ADMIN: u8 = 1 << 3;
+pub const ROLE_PAUSER: u8 = 1 << 4;
+pub const ROLE_ALL: u8 =
+    ROLE_DEPOSITOR | ROLE_WITHDRAWER | ROLE_DELEGATE_MANAGER | ROLE_CONFIG_ADMIN | ROLE_PAUSER;
+
+/// Longest lock-up a deposit tranche may ask for, so no depositor can hold
+/// `close_vault` / `enable_pool` hostage with an open tranche forever
+pub const MAX_LOCKUP_SECONDS: i64 = 365 * DAY_SECONDS;
+
+/// Upper bound on the signer set of a vault multisig
+pub const MAX_MULTISIG_SIGNERS: usize = 10;
+
+/// Upper bound on the guardians of a vault's `RecoveryConfig`
+pub const MAX_RECOVERY_GUARDIANS: usize = 10;
+
+/// Upper bound on the rules in a vault's `Policy`
+pub const MAX_POLICY_RULES: usize = 8;
+
+/// Upper bound on the delay tiers of a vault's `WithdrawTiers`
+pub const MAX_WITHDRAW_TIERS: usize = 4;
+
+/// Virtual share supply and asset balance added when pricing pool shares, so
+/// a donation to an empty pool cannot inflate the share price for free
+pub const VIRTUAL_SHARES: u64 = 1;
+pub const VIRTUAL_ASSETS: u64 = 1;
+
+/// Who may call `deposit`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DepositMode {
+    /// Only the vault owner
+    #[default]
+    OwnerOnly,
+    /// Anyone, from their own token account
+    Open,
+    /// The owner plus keys holding a `DepositorRecord`
+    Allowlist,
+}
+
+/// Central vault state account (PDA, seeds = [b"vault", creator, mint])
 #[account]
 #[derive(Default)]
 pub struct VaultState {
-    /// Owner pubkey — the only signer allowed to deposit / withdraw freely
+    /// Owner pubkey — the only signer allowed to withdraw freely
     pub owner: Pubkey,
-    /// SPL mint this vault accepts
+    /// SPL Token or Token-2022 mint this vault accepts
     pub mint: Pubkey,
     /// The vault's Associated Token Account (PDA-owned)
     pub vault_ata: Pubkey,
-    /// Cumulative lifetime deposits (for analytics)
+    /// Cumulative lifetime deposits, net of transfer fees (for analytics)
     pub total_deposited: u64,
-    /// Cumulative lifetime withdrawals
+    /// Cumulative lifetime withdrawals, net of transfer fees
     pub total_withdrawn: u64,
     /// Maximum single deposit (0 = unlimited)
     pub max_deposit: u64,
@@ -26,6 +84,123 @@ pub struct VaultState {
     pub bump: u8,
     /// Whether the vault is locked (reentrancy guard)
     pub locked: bool,
+    /// Withdrawals, delegate changes and closing go through `Multisig` proposals
+    pub multisig: bool,
+    /// Owner at creation time. Fixed PDA seed, so `owner` can change without
+    /// moving funds or `DelegateRecord`s to a new address
+    pub creator: Pubkey,
+    /// Owner nominated by `propose_owner_transfer` (default = none pending)
+    pub pending_owner: Pubkey,
+    /// Queued `max_deposit`, applied by `apply_config` once `config_effective_at` passes
+    pub pending_max_deposit: u64,
+    /// Queued `daily_withdraw_limit`
+    pub pending_daily_withdraw_limit: u64,
+    /// When the queued limits may be applied (0 = nothing queued)
+    pub config_effective_at: i64,
+    /// May pause the vault; can only unpause after `GUARDIAN_UNPAUSE_DELAY` (default = none)
+    pub guardian: Pubkey,
+    /// `PAUSE_*` bits currently in force
+    pub paused: u8,
+    /// `PAUSE_*` bits the guardian asked to lift via `request_unpause`
+    pub pending_unpause: u8,
+    /// When the guardian may lift `pending_unpause`
+    pub unpause_available_at: i64,
+    /// Who may deposit; see `DepositMode`
+    pub deposit_mode: DepositMode,
+    /// Live `DelegateRecord`s; `close_vault` requires this to reach 0
+    pub delegate_count: u32,
+    /// Tokens promised to open `Stream`s and not yet claimed; other outflows
+    /// may only spend the balance above this
+    pub stream_reserved: u64,
+    /// Open `Stream`s; `close_vault` requires this to reach 0
+    pub stream_count: u32,
+    /// Tokens held by unreleased `Tranche`s; other outflows may only spend
+    /// the balance above this and `stream_reserved`
+    pub locked_amount: u64,
+    /// Unreleased `Tranche`s; `close_vault` requires this to reach 0
+    pub tranche_count: u32,
+    /// Id (PDA seed) of the next `Tranche`
+    pub next_tranche_id: u64,
+    /// Registered `VaultAsset`s; `close_vault` requires this to reach 0
+    pub asset_count: u32,
+    /// Share mint of a pooled vault (PDA, seeds = [b"shares", vault]; default = not pooled)
+    pub share_mint: Pubkey,
+    /// Strategy program `allocate` moves idle funds into (default = none)
+    pub strategy: Pubkey,
+    /// Principal currently allocated to `strategy`
+    pub allocated: u64,
+    /// Value of the position as last reported by `strategy` (principal plus yield)
+    pub strategy_value: u64,
+    /// Layout version; handlers require `VaultState::VERSION` (see `migrate_vault`)
+    pub version: u8,
+    /// Live `Role`s; `close_vault` requires this to reach 0
+    pub role_count: u32,
+    /// Rules in the vault's `Policy`; `withdraw` and `delegate_withdraw`
+    /// require the policy account while this is non-zero
+    pub policy_rule_count: u32,
+    /// Active `WithdrawTiers` tiers; while non-zero, owner withdrawals go
+    /// through `request_withdraw` / `execute_withdraw` instead of `withdraw`
+    pub withdraw_tier_count: u32,
+    /// Open `PendingWithdrawal`s; `close_vault` requires this to reach 0
+    pub pending_withdrawal_count: u32,
+    /// Must also sign withdrawals above `cosign_threshold` (default = none)
+    pub cosigner: Pubkey,
+    /// Largest withdrawal that needs no co-signature
+    pub cosign_threshold: u64,
+    /// Co-signer queued by `set_cosigner`, applied by `apply_cosigner`
+    pub pending_cosigner: Pubkey,
+    /// Queued `cosign_threshold`
+    pub pending_cosign_threshold: u64,
+    /// When the queued co-signer settings may be applied (0 = nothing queued)
+    pub cosigner_effective_at: i64,
+    /// `withdraw` above this amount must go through `request_withdraw` and
+    /// wait out `veto_window`
+    pub veto_threshold: u64,
+    /// Seconds a `VetoKey` has to veto a large withdrawal (0 = veto mode off)
+    pub veto_window: i64,
+    /// Live `VetoKey`s; `close_vault` requires this to reach 0
+    pub veto_key_count: u32,
+    /// `veto_threshold` queued by `set_veto`, applied by `apply_veto`
+    pub pending_veto_threshold: u64,
+    /// Queued `veto_window`
+    pub pending_veto_window: i64,
+    /// When the queued veto settings may be applied (0 = nothing queued)
+    pub veto_effective_at: i64,
+    /// Guardians in the vault's `RecoveryConfig`; `close_vault` requires this to reach 0
+    pub recovery_guardian_count: u32,
+    /// May claim the balance once the owner has been inactive for
+    /// `inactivity_period` (default = none)
+    pub beneficiary: Pubkey,
+    /// Seconds without a heartbeat after which `beneficiary` may claim
+    pub inactivity_period: i64,
+    /// Last time the owner signed an instruction or called `ping`
+    pub last_heartbeat: i64,
+    /// Max the beneficiary may claim per 24-h window (0 = only `daily_withdraw_limit`)
+    pub claim_limit: u64,
+    /// Amount claimed in the current claim window
+    pub claimed_today: u64,
+    /// Start of the current claim window
+    pub claim_window_start: i64,
+    /// Live `DepositorRecord`s; `close_vault` requires this to reach 0
+    pub depositor_count: u32,
+    /// Strategy queued by `set_strategy`, applied by `apply_strategy`
+    pub pending_strategy: Pubkey,
+    /// When the queued strategy may be applied (0 = nothing queued)
+    pub strategy_effective_at: i64,
+    /// The strategy's state account for this vault; `allocate`, `deallocate`
+    /// and `report_strategy` accept no other
+    pub strategy_state: Pubkey,
+    /// `strategy_state` queued with `pending_strategy`
+    pub pending_strategy_state: Pubkey,
+    /// Recoveries completed so far; a `PendingWithdrawal` stamped with an
+    /// older count cannot be executed
+    pub recovery_count: u32,
+    /// Set by `migrate_vault` on a pre-versioning vault, whose original-layout
+    /// `DelegateRecord`s are not yet counted; only then may `migrate_delegate`
+    /// take one, so a closed vault's leftovers cannot join a re-created one
+    pub legacy_delegates: bool,
+    /// Zeroed space for future fields, so adding one needs no realloc
+    pub reserved: [u32; 32],
 }
 
 impl VaultState {
@@ -33,20 +208,775 @@ impl VaultState {
         + 32 + 32 + 32          // owner, mint, vault_ata
         + 8 + 8                 // total_deposited, total_withdrawn
         + 8 + 8 + 8 + 8        // max_deposit, daily_withdraw_limit, withdrawn_today, window_start
-        + 1 + 1; // bump, locked
+        + 1 + 1                 // bump, locked
+        + 1                     // multisig
+        + 32 + 32               // creator, pending_owner
+        + 8 + 8 + 8             // pending_max_deposit, pending_daily_withdraw_limit, config_effective_at
+        + 32 + 1 + 1 + 8        // guardian, paused, pending_unpause, unpause_available_at
+        + 1 + 4                 // deposit_mode, delegate_count
+        + 8 + 4                 // stream_reserved, stream_count
+        + 8 + 4 + 8             // locked_amount, tranche_count, next_tranche_id
+        + 4                     // asset_count
+        + 32                    // share_mint
+        + 32 + 8 + 8            // strategy, allocated, strategy_value
+        + 1 + 4                 // version, role_count
+        + 4                     // policy_rule_count
+        + 4 + 4                 // withdraw_tier_count, pending_withdrawal_count
+        + 32 + 8                // cosigner, cosign_threshold
+        + 32 + 8 + 8            // pending_cosigner, pending_cosign_threshold, cosigner_effective_at
+        + 8 + 8 + 4             // veto_threshold, veto_window, veto_key_count
+        + 8 + 8 + 8             // pending_veto_threshold, pending_veto_window, veto_effective_at
+        + 4                     // recovery_guardian_count
+        + 32 + 8 + 8            // beneficiary, inactivity_period, last_heartbeat
+        + 8 + 8 + 8             // claim_limit, claimed_today, claim_window_start
+        + 4                     // depositor_count
+        + 32 + 8                // pending_strategy, strategy_effective_at
+        + 32 + 32               // strategy_state, pending_strategy_state
+        + 4                     // recovery_count
+        + 1                     // legacy_delegates
+        + 4 * 32; // reserved
+
+    /// Current layout version. Accounts written before versioning are shorter
+    /// than `LEN` and count as version 0. Version 1 ended with a 24-byte
+    /// reserved block after `cosigner_effective_at`; version 2 writes every
+    /// field from `veto_threshold` on from that offset, far past the old end
+    /// of the account, so `migrate_vault` must grow a version 1 vault to
+    /// `LEN`. The zeroes it reads and fills in leave each of those features
+    /// off. Version 2 ends with a 128-byte reserved block for future fields.
+    pub const VERSION: u8 = 2;
+
+    /// Fail with the matching `*Paused` error if any of `flag` is paused.
+    pub fn require_not_paused(&self, flag: u8) -> Result<()> {
+        if self.paused & flag & PAUSE_DEPOSITS != 0 {
+            return err!(VaultError::DepositsPaused);
+        }
+        if self.paused & flag & PAUSE_WITHDRAWALS != 0 {
+            return err!(VaultError::WithdrawalsPaused);
+        }
+        if self.paused & flag & PAUSE_DELEGATE_WITHDRAWALS != 0 {
+            return err!(VaultError::DelegateWithdrawalsPaused);
+        }
+        Ok(())
+    }
+
+    /// Fail unless `amount` can be paid out of the available `balance` —
+    /// with `InsufficientLiquidity` when the shortfall is allocated to the
+    /// strategy (and `deallocate` would cover it), else `InsufficientFunds`.
+    pub fn require_available(&self, balance: u64, amount: u64) -> Result<()> {
+        let available = self.available(balance);
+        if available >= amount {
+            return Ok(());
+        }
+        if available.saturating_add(self.strategy_value) >= amount {
+            return err!(VaultError::InsufficientLiquidity);
+        }
+        err!(VaultError::InsufficientFunds)
+    }
+
+    /// Whether `enable_pool` has turned the vault into a share-accounted pool.
+    pub fn pooled(&self) -> bool {
+        self.share_mint != Pubkey::default()
+    }
+
+    /// Shares worth `amount` given the pool's `assets` and share `supply`
+    /// before the deposit (rounded down, in the pool's favour).
+    pub fn shares_for_deposit(amount: u64, assets: u64, supply: u64) -> Result<u64> {
+        mul_div(
+            amount,
+            supply.saturating_add(VIRTUAL_SHARES),
+            assets.saturating_add(VIRTUAL_ASSETS),
+        )
+    }
+
+    /// Underlying paid out for burning `shares` (rounded down, in the pool's favour).
+    pub fn assets_for_shares(shares: u64, assets: u64, supply: u64) -> Result<u64> {
+        mul_div(
+            shares,
+            assets.saturating_add(VIRTUAL_ASSETS),
+            supply.saturating_add(VIRTUAL_SHARES),
+        )
+    }
+
+    /// Whether `depositor` may deposit under the current `deposit_mode`;
+    /// `allowlisted` is whether it presented a `DepositorRecord`.
+    pub fn may_deposit(&self, depositor: Pubkey, allowlisted: bool) -> bool {
+        match self.deposit_mode {
+            DepositMode::Open => true,
+            DepositMode::OwnerOnly => depositor == self.owner,
+            DepositMode::Allowlist => depositor == self.owner || allowlisted,
+        }
+    }
+
+    /// Whether `authority` may act with `permission`: the owner always may,
+    /// anyone else needs a `Role` holding that bit.
+    pub fn authorized(&self, authority: &Pubkey, role: Option<&Role>, permission: u8) -> bool {
+        *authority == self.owner
+            || role.is_some_and(|r| r.member == *authority && r.permissions & permission != 0)
+    }
+
+    /// Part of the vault ATA `balance` neither reserved for streams nor
+    /// held by a lock-up tranche.
+    pub fn available(&self, balance: u64) -> u64 {
+        balance
+            .saturating_sub(self.stream_reserved)
+            .saturating_sub(self.locked_amount)
+    }
+
+    /// Assets backing pool shares: the vault ATA `balance` plus whatever the
+    /// strategy last reported — always nothing, as pooled vaults cannot
+    /// allocate to a strategy.
+    pub fn pool_assets(&self, balance: u64) -> Result<u64> {
+        balance
+            .checked_add(self.strategy_value)
+            .ok_or(VaultError::Overflow.into())
+    }
+
+    /// Whether a withdrawal of `amount` needs the co-signer's signature.
+    pub fn requires_cosigner(&self, amount: u64) -> bool {
+        self.cosigner != Pubkey::default() && amount > self.cosign_threshold
+    }
+
+    /// Whether switching to `cosigner` / `threshold` weakens the co-sign
+    /// requirement: removing or replacing the co-signer, or raising the threshold.
+    pub fn loosens_cosigner(&self, cosigner: Pubkey, threshold: u64) -> bool {
+        self.cosigner != Pubkey::default()
+            && (cosigner != self.cosigner || threshold > self.cosign_threshold)
+    }
+
+    /// Whether a withdrawal of `amount` must wait out the veto window.
+    pub fn requires_veto(&self, amount: u64) -> bool {
+        self.veto_window > 0 && amount > self.veto_threshold
+    }
+
+    /// Whether registered assets may leave the vault. Asset withdrawals cannot
+    /// be co-signed, queued or vetoed, so they stop while a co-signer, delay
+    /// tiers or a veto window guard the vault's own mint.
+    pub fn asset_outflows_allowed(&self) -> bool {
+        self.cosigner == Pubkey::default() && self.withdraw_tier_count == 0 && self.veto_window == 0
+    }
+
+    /// Whether switching to `threshold` / `window` weakens veto mode:
+    /// turning it off, shortening the window or raising the threshold.
+    pub fn loosens_veto(&self, threshold: u64, window: i64) -> bool {
+        self.veto_window > 0 && (window < self.veto_window || threshold > self.veto_threshold)
+    }
+
+    /// Refresh `last_heartbeat` if `signer` is the owner. Called by every
+    /// instruction the owner can sign.
+    pub fn heartbeat(&mut self, signer: &Pubkey, now: i64) {
+        if *signer == self.owner {
+            self.last_heartbeat = now;
+        }
+    }
+
+    /// Whether the beneficiary may claim at `now`: one is set and the owner
+    /// has been silent for `inactivity_period`.
+    pub fn inheritance_claimable(&self, now: i64) -> bool {
+        self.beneficiary != Pubkey::default()
+            && now.saturating_sub(self.last_heartbeat) >= self.inactivity_period
+    }
+
+    /// Charge `amount` against `claim_limit` (0 = unlimited), in its own
+    /// 24-h window.
+    pub fn charge_claim_limit(&mut self, amount: u64, now: i64) -> Result<()> {
+        charge_window(
+            self.claim_limit,
+            &mut self.claimed_today,
+            &mut self.claim_window_start,
+            amount,
+            now,
+        )
+    }
+
+    /// Whether replacing limit `old` with `new` loosens it (0 = unlimited).
+    pub fn loosens_limit(old: u64, new: u64) -> bool {
+        old != 0 && (new == 0 || new > old)
+    }
+
+    /// Roll the 24-h window if necessary, then charge `amount` against
+    /// `daily_withdraw_limit` (0 = unlimited). Every outflow path of the
+    /// vault's own mint shares this one budget: owner, multisig and delegate
+    /// withdrawals alike.
+    pub fn charge_daily_limit(&mut self, amount: u64, now: i64) -> Result<()> {
+        charge_window(
+            self.daily_withdraw_limit,
+            &mut self.withdrawn_today,
+            &mut self.window_start,
+            amount,
+            now,
+        )
+    }
 }
 
-/// Per-delegate record (PDA, seeds = [b"delegate", vault, delegate_pubkey])
+/// `a * b / c` in u128, rounded down.
+fn mul_div(a: u64, b: u64, c: u64) -> Result<u64> {
+    let out = u128::from(a)
+        .checked_mul(u128::from(b))
+        .and_then(|p| p.checked_div(u128::from(c)))
+        .ok_or(VaultError::Overflow)?;
+    u64::try_from(out).map_err(|_| error!(VaultError::Overflow))
+}
+
+/// Roll the 24-h window starting at `window_start` if it has passed, then add
+/// `amount` to `withdrawn_today` unless that would exceed `limit` (0 = unlimited).
+fn charge_window(
+    limit: u64,
+    withdrawn_today: &mut u64,
+    window_start: &mut i64,
+    amount: u64,
+    now: i64,
+) -> Result<()> {
+    if now - *window_start >= DAY_SECONDS {
+        *window_start = now;
+        *withdrawn_today = 0;
+    }
+
+    if limit > 0 {
+        let new_today = withdrawn_today
+            .checked_add(amount)
+            .ok_or(VaultError::Overflow)?;
+        require!(new_today <= limit, VaultError::DailyLimitExceeded);
+        *withdrawn_today = new_today;
+    }
+    Ok(())
+}
+
+/// Per-delegate record (PDA, seeds = [b"delegate", vault, delegate_pubkey], with
+/// the asset's mint appended when the allowance is in a `VaultAsset`'s mint)
 #[account]
 pub struct DelegateRecord {
     pub vault: Pubkey,
     pub delegate: Pubkey,
+    /// Total grant, or the amount per period when `period` is set
     pub allowance: u64,
+    /// Amount drawn from `allowance` (in the current period, if recurring)
     pub used: u64,
     pub expires_at: i64,
     pub bump: u8,
+    /// Seconds after which `used` resets (0 = one-shot allowance)
+    pub period: i64,
+    /// Number of periods the delegate may draw in (0 = until `expires_at`)
+    pub max_periods: u32,
+    /// Zero-based index of the current period
+    pub period_index: u32,
+    /// Start of the current period; advances in whole `period` steps
+    pub period_start: i64,
+    /// Withdrawals are refused before this time (0 = active at once)
+    pub not_before: i64,
+    /// Set by `update_delegate` to suspend the delegate without losing `used`
+    pub paused: bool,
+    /// Withdrawals skip the vault's `daily_withdraw_limit` (still bound by `allowance`)
+    pub exempt_from_daily_limit: bool,
+    /// Mint the allowance is denominated in — the vault's own or a `VaultAsset`'s
+    pub mint: Pubkey,
+    /// Layout version; handlers require `DelegateRecord::VERSION` (see `migrate_delegate`)
+    pub version: u8,
+    /// Zeroed space for future fields
+    pub reserved: [u64; 8],
 }
 
 impl DelegateRecord {
-    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1;
+    pub const LEN: usize = 8  // discriminator
+        + 32 + 32 + 8 + 8 + 8 + 1   // vault, delegate, allowance, used, expires_at, bump
+        + 8 + 4 + 4 + 8             // period, max_periods, period_index, period_start
+        + 8 + 1 + 1             // not_before, paused, exempt_from_daily_limit
+        + 32                        // mint
+        + 1 + 8 * 8; // version, reserved
+
+    /// Current layout version; see `VaultState::VERSION`.
+    pub const VERSION: u8 = 1;
+
+    /// Trailing PDA seed for this record: empty for the vault's own mint
+    /// (`vault_mint`), so primary records keep their original address.
+    pub fn mint_seed(&self, vault_mint: &Pubkey) -> &[u8] {
+        if self.mint == *vault_mint {
+            &[]
+        } else {
+            self.mint.as_ref()
+        }
+    }
+
+    /// Check that the delegate may draw `amount` at `now`, rolling into the
+    /// current period first. Returns the new `used`, to be stored once the
+    /// vault-side checks pass.
+    pub fn check_draw(&mut self, amount: u64, now: i64) -> Result<u64> {
+        require!(now < self.expires_at, VaultError::DelegateExpired);
+        require!(now >= self.not_before, VaultError::DelegateNotActive);
+        require!(!self.paused, VaultError::DelegatePaused);
+        self.roll_period(now)?;
+
+        let new_used = self.used.checked_add(amount).ok_or(VaultError::Overflow)?;
+        require!(new_used <= self.allowance, VaultError::AllowanceExceeded);
+        Ok(new_used)
+    }
+
+    /// Move into the period containing `now`, resetting `used` if it changed,
+    /// then fail if the delegate has drawn in all `max_periods` periods.
+    pub fn roll_period(&mut self, now: i64) -> Result<()> {
+        if self.period > 0 && now - self.period_start >= self.period {
+            let elapsed = (now - self.period_start) / self.period;
+            self.period_start = elapsed
+                .checked_mul(self.period)
+                .and_then(|d| self.period_start.checked_add(d))
+                .ok_or(VaultError::Overflow)?;
+            self.period_index = u32::try_from(elapsed)
+                .ok()
+                .and_then(|e| self.period_index.checked_add(e))
+                .ok_or(VaultError::Overflow)?;
+            self.used = 0;
+        }
+
+        if self.max_periods > 0 {
+            require!(
+                self.period_index < self.max_periods,
+                VaultError::DelegatePeriodsExhausted
+            );
+        }
+        Ok(())
+    }
+
+    /// Whether the record can never be drawn on again: expired, a one-shot
+    /// allowance used up, or a recurring one past its last period.
+    pub fn is_spent(&self, now: i64) -> bool {
+        if now >= self.expires_at {
+            return true;
+        }
+        if self.period == 0 {
+            return self.used >= self.allowance;
+        }
+        if self.max_periods == 0 {
+            return false;
+        }
+
+        let elapsed = now.saturating_sub(self.period_start).max(0) / self.period;
+        let index = i64::from(self.period_index).saturating_add(elapsed);
+        let last = i64::from(self.max_periods) - 1;
+        index > last || (index == last && elapsed == 0 && self.used >= self.allowance)
+    }
+}
+
+/// Linear payment stream out of a vault
+/// (PDA, seeds = [b"stream", vault, stream_id (le bytes)])
+#[account]
+pub struct Stream {
+    pub vault: Pubkey,
+    pub recipient: Pubkey,
+    /// Chosen by the owner at creation; only needs to be unique per vault
+    pub stream_id: u64,
+    /// Amount that vests by `end` (cut to the vested amount on cancel)
+    pub total: u64,
+    pub start: i64,
+    /// Nothing can be claimed before this, after which the linear amount since `start` unlocks
+    pub cliff: i64,
+    pub end: i64,
+    /// Amount claimed so far
+    pub withdrawn: u64,
+    pub bump: u8,
+}
+
+impl Stream {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    /// Amount vested at `now`.
+    pub fn vested(&self, now: i64) -> Result<u64> {
+        if now < self.cliff {
+            return Ok(0);
+        }
+        if now >= self.end {
+            return Ok(self.total);
+        }
+
+        let elapsed = (now - self.start) as u128;
+        let duration = (self.end - self.start) as u128;
+        let vested = (self.total as u128)
+            .checked_mul(elapsed)
+            .ok_or(VaultError::Overflow)?
+            / duration;
+        Ok(vested as u64)
+    }
+}
+
+/// Lock-up tranche created by `deposit` with an `unlock_at`
+/// (PDA, seeds = [b"tranche", vault, tranche_id (le bytes)])
+#[account]
+pub struct Tranche {
+    pub vault: Pubkey,
+    /// Signer of the deposit; gets the rent back on release
+    pub depositor: Pubkey,
+    /// `VaultState::next_tranche_id` at creation
+    pub tranche_id: u64,
+    /// Tokens locked, net of transfer fees
+    pub amount: u64,
+    /// `release_tranche` is refused before this time
+    pub unlock_at: i64,
+    pub bump: u8,
+}
+
+impl Tranche {
+    pub const LEN: usize = 8  // discriminator
+        + 32 + 32               // vault, depositor
+        + 8 + 8 + 8             // tranche_id, amount, unlock_at
+        + 1; // bump
+}
+
+/// Additional token held by a multi-mint vault, with its own limits
+/// (PDA, seeds = [b"asset", vault, mint])
+#[account]
+pub struct VaultAsset {
+    pub vault: Pubkey,
+    pub mint: Pubkey,
+    /// ATA of `mint` owned by the vault PDA
+    pub vault_ata: Pubkey,
+    /// Cumulative deposits of this mint, net of transfer fees
+    pub total_deposited: u64,
+    /// Cumulative withdrawals of this mint, net of transfer fees
+    pub total_withdrawn: u64,
+    /// Maximum single deposit (0 = unlimited)
+    pub max_deposit: u64,
+    /// Max tokens of this mint that can leave in a 24-h window (0 = unlimited)
+    pub daily_withdraw_limit: u64,
+    /// Amount of this mint already withdrawn in the current window
+    pub withdrawn_today: u64,
+    /// Unix timestamp of the start of the current 24-h window
+    pub window_start: i64,
+    pub bump: u8,
+    /// Open `DelegateRecord`s with an allowance in this mint
+    pub delegate_count: u32,
+}
+
+impl VaultAsset {
+    pub const LEN: usize = 8  // discriminator
+        + 32 + 32 + 32          // vault, mint, vault_ata
+        + 8 + 8                 // total_deposited, total_withdrawn
+        + 8 + 8 + 8 + 8         // max_deposit, daily_withdraw_limit, withdrawn_today, window_start
+        + 1                     // bump
+        + 4; // delegate_count
+
+    /// Same as `VaultState::charge_daily_limit`, against this asset's own budget.
+    pub fn charge_daily_limit(&mut self, amount: u64, now: i64) -> Result<()> {
+        charge_window(
+            self.daily_withdraw_limit,
+            &mut self.withdrawn_today,
+            &mut self.window_start,
+            amount,
+            now,
+        )
+    }
+}
+
+/// Allowlisted depositor (PDA, seeds = [b"depositor", vault, depositor_pubkey])
+#[account]
+pub struct DepositorRecord {
+    pub vault: Pubkey,
+    pub depositor: Pubkey,
+    pub bump: u8,
+}
+
+impl DepositorRecord {
+    pub const LEN: usize = 8 + 32 + 32 + 1;
+}
+
+/// Permissions granted to one key on a vault (PDA, seeds = [b"role", vault, member])
+#[account]
+pub struct Role {
+    pub vault: Pubkey,
+    pub member: Pubkey,
+    /// `ROLE_*` bits
+    pub permissions: u8,
+    pub bump: u8,
+}
+
+impl Role {
+    pub const LEN: usize = 8 + 32 + 32 + 1 + 1;
+}
+
+/// One withdrawal rule of a vault `Policy`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rule {
+    /// No single withdrawal may exceed `max`
+    MaxPerTransaction { max: u64 },
+    /// No single withdrawal may be below `min`
+    MinAmount { min: u64 },
+    /// At most `cap` may go to `recipient` (the destination's owner) per 24-h window
+    RecipientCap { recipient: Pubkey, cap: u64 },
+    /// At most `count` withdrawals per `window` seconds
+    MaxWithdrawals { count: u32, window: i64 },
+}
+
+impl Rule {
+    /// Largest variant: tag + recipient + cap
+    pub const MAX_LEN: usize = 1 + 32 + 8;
+}
+
+/// A `Rule` plus the window it has been counting in
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PolicyRule {
+    pub rule: Rule,
+    /// Amount (`RecipientCap`) or number of withdrawals (`MaxWithdrawals`)
+    /// counted in the current window; unused by the stateless rules
+    pub used: u64,
+    /// Start of the current window
+    pub window_start: i64,
+}
+
+impl PolicyRule {
+    pub const LEN: usize = Rule::MAX_LEN + 8 + 8;
+}
+
+/// Withdrawal rules checked by `policy::check_outflow`
+/// (PDA, seeds = [b"policy", vault])
+#[account]
+pub struct Policy {
+    pub vault: Pubkey,
+    /// Up to `MAX_POLICY_RULES` rules, all of which must pass
+    pub rules: Vec<PolicyRule>,
+    pub bump: u8,
+}
+
+impl Policy {
+    pub const LEN: usize = 8 + 32 + (4 + PolicyRule::LEN * MAX_POLICY_RULES) + 1;
+}
+
+/// Withdrawals of at least `min_amount` wait `delay` seconds between
+/// `request_withdraw` and `execute_withdraw`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DelayTier {
+    pub min_amount: u64,
+    pub delay: i64,
+}
+
+impl DelayTier {
+    pub const LEN: usize = 8 + 8;
+
+    /// Cooldown for `amount` under `tiers` (sorted by `min_amount`): the delay
+    /// of the highest tier it reaches, or 0 below the first.
+    pub fn delay_for(tiers: &[DelayTier], amount: u64) -> i64 {
+        tiers
+            .iter()
+            .rev()
+            .find(|t| amount >= t.min_amount)
+            .map_or(0, |t| t.delay)
+    }
+
+    /// Whether replacing `old` with `new` shortens the cooldown of any amount.
+    /// Both delay curves are step functions, so comparing them at every
+    /// threshold is enough.
+    pub fn loosens(old: &[DelayTier], new: &[DelayTier]) -> bool {
+        old.iter()
+            .chain(new)
+            .any(|t| Self::delay_for(new, t.min_amount) < Self::delay_for(old, t.min_amount))
+    }
+}
+
+/// Cooldown schedule for queued withdrawals
+/// (PDA, seeds = [b"withdraw_tiers", vault])
+#[account]
+pub struct WithdrawTiers {
+    pub vault: Pubkey,
+    /// Up to `MAX_WITHDRAW_TIERS` tiers with increasing `min_amount`
+    pub tiers: Vec<DelayTier>,
+    /// Shorter schedule queued by `set_withdraw_tiers`
+    pub pending_tiers: Vec<DelayTier>,
+    /// When `pending_tiers` may be applied (0 = nothing queued)
+    pub tiers_effective_at: i64,
+    pub bump: u8,
+}
+
+impl WithdrawTiers {
+    pub const LEN: usize = 8 + 32
+        + 2 * (4 + DelayTier::LEN * MAX_WITHDRAW_TIERS) // tiers, pending_tiers
+        + 8 + 1;
+}
+
+/// Owner withdrawal waiting out its cooldown
+/// (PDA, seeds = [b"pending_withdrawal", vault, request_id (le bytes)])
+#[account]
+pub struct PendingWithdrawal {
+    pub vault: Pubkey,
+    /// Signer of `request_withdraw`; gets the rent back
+    pub requester: Pubkey,
+    /// Chosen by the requester; only needs to be unique per vault
+    pub request_id: u64,
+    /// Token account of the vault's mint that will be paid
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub requested_at: i64,
+    /// `execute_withdraw` is refused before this time
+    pub execute_after: i64,
+    /// Requested by the beneficiary under the dead-man switch; executing it
+    /// re-checks the owner's inactivity and charges `claim_limit`
+    pub inheritance: bool,
+    /// `VaultState::recovery_count` at request time
+    pub recovery_count: u32,
+    pub bump: u8,
+}
+
+impl PendingWithdrawal {
+    pub const LEN: usize = 8  // discriminator
+        + 32 + 32 + 8           // vault, requester, request_id
+        + 32 + 8                // destination, amount
+        + 8 + 8                 // requested_at, execute_after
+        + 1                     // inheritance
+        + 4                     // recovery_count
+        + 1; // bump
+}
+
+/// Key allowed to veto queued withdrawals during the veto window — and
+/// nothing else (PDA, seeds = [b"veto", vault, key])
+#[account]
+pub struct VetoKey {
+    pub vault: Pubkey,
+    pub key: Pubkey,
+    /// When `remove_veto_key` may close this key (0 = removal not requested)
+    pub removable_at: i64,
+    /// `VaultState::recovery_count` when removal was requested; a recovery
+    /// since then voids the request
+    pub recovery_count: u32,
+    pub bump: u8,
+}
+
+impl VetoKey {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 4 + 1;
+}
+
+/// Guardians that can hand the vault to a new owner if the owner key is lost,
+/// plus the recovery in progress (PDA, seeds = [b"recovery", vault])
+#[account]
+pub struct RecoveryConfig {
+    pub vault: Pubkey,
+    /// Up to `MAX_RECOVERY_GUARDIANS` distinct guardians
+    pub guardians: Vec<Pubkey>,
+    /// Guardian approvals a recovery needs
+    pub threshold: u8,
+    /// Seconds the owner has to cancel once a recovery reaches `threshold`
+    pub delay: i64,
+    /// Owner named by the recovery in progress (default = none in progress)
+    pub new_owner: Pubkey,
+    /// Bit i set = `guardians[i]` approved the recovery in progress
+    pub approvals: u16,
+    /// When `complete_recovery` may run (0 = threshold not reached yet)
+    pub ready_at: i64,
+    pub bump: u8,
+}
+
+impl RecoveryConfig {
+    pub const LEN: usize = 8 + 32
+        + (4 + 32 * MAX_RECOVERY_GUARDIANS) // guardians
+        + 1 + 8                             // threshold, delay
+        + 32 + 2 + 8                        // new_owner, approvals, ready_at
+        + 1; // bump
+
+    pub fn guardian_index(&self, key: &Pubkey) -> Option<usize> {
+        self.guardians.iter().position(|g| g == key)
+    }
+
+    pub fn in_progress(&self) -> bool {
+        self.new_owner != Pubkey::default()
+    }
+
+    pub fn approval_count(&self) -> u32 {
+        self.approvals.count_ones()
+    }
+
+    /// Forget the recovery in progress.
+    pub fn reset(&mut self) {
+        self.new_owner = Pubkey::default();
+        self.approvals = 0;
+        self.ready_at = 0;
+    }
+}
+
+/// N-of-M signer set that owns a vault's sensitive actions
+/// (PDA, seeds = [b"multisig", vault])
+#[account]
+pub struct Multisig {
+    pub vault: Pubkey,
+    /// Up to `MAX_MULTISIG_SIGNERS` distinct signers
+    pub signers: Vec<Pubkey>,
+    /// Approvals needed before a proposal can execute
+    pub threshold: u8,
+    /// Index handed to the next proposal (part of its PDA seeds)
+    pub proposal_count: u64,
+    /// Proposals created and not yet executed or cancelled; the close
+    /// proposal must be the last one open
+    pub open_proposals: u32,
+    /// Slot the multisig was enabled in; proposals carry it, so none can
+    /// outlive the vault into a re-created one
+    pub generation: u64,
+    pub bump: u8,
+}
+
+impl Multisig {
+    pub const LEN: usize = 8 + 32 + (4 + 32 * MAX_MULTISIG_SIGNERS) + 1 + 8 + 4 + 8 + 1;
+
+    pub fn signer_index(&self, key: &Pubkey) -> Option<usize> {
+        self.signers.iter().position(|s| s == key)
+    }
+}
+
+/// Fields `update_delegate` changes; `None` leaves a field as it is
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DelegateUpdate {
+    pub allowance: Option<u64>,
+    pub expires_at: Option<i64>,
+    pub not_before: Option<i64>,
+    pub paused: Option<bool>,
+    pub exempt_from_daily_limit: Option<bool>,
+}
+
+impl DelegateUpdate {
+    pub const LEN: usize = 9 + 9 + 9 + 2 + 2;
+}
+
+/// Action a multisig proposal performs once it reaches the threshold
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ProposalAction {
+    /// Send `amount` from the vault ATA to the `destination` token account
+    Withdraw {
+        amount: u64,
+        destination: Pubkey,
+    },
+    AddDelegate {
+        delegate: Pubkey,
+        allowance: u64,
+        expires_at: i64,
+        period: i64,
+        max_periods: u32,
+    },
+    RemoveDelegate {
+        delegate: Pubkey,
+    },
+    CloseVault,
+    UpdateDelegate {
+        delegate: Pubkey,
+        update: DelegateUpdate,
+    },
+}
+
+impl ProposalAction {
+    /// Largest variant: tag + delegate + `DelegateUpdate`
+    pub const MAX_LEN: usize = 1 + 32 + DelegateUpdate::LEN;
+}
+
+/// Pending multisig action (PDA, seeds = [b"proposal", vault, index (le bytes)])
+#[account]
+pub struct Proposal {
+    pub vault: Pubkey,
+    /// Signer that created the proposal; receives the rent back
+    pub proposer: Pubkey,
+    pub index: u64,
+    /// `Multisig::generation` at creation
+    pub generation: u64,
+    pub action: ProposalAction,
+    /// Bit `i` is set once `multisig.signers[i]` has approved
+    pub approvals: u16,
+    pub bump: u8,
+}
+
+impl Proposal {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + ProposalAction::MAX_LEN + 2 + 1;
+
+    pub fn approval_count(&self) -> u32 {
+        self.approvals.count_ones()
+    }
 }
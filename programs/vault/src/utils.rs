@@ -0,0 +1,138 @@
+use crate::{
+    errors::VaultError,
+    events::DelegateRemoved,
+    state::{DelegateRecord, VaultState},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+};
+
+/// Move `amount` out of the vault ATA into `destination`, signed by the vault PDA.
+///
+/// Returns what `destination` actually received, which is less than `amount`
+/// when a Token-2022 transfer fee is withheld on the way out.
+pub fn transfer_from_vault<'info>(
+    vault: &Account<'info, VaultState>,
+    vault_ata: &InterfaceAccount<'info, TokenAccount>,
+    destination: &mut InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<u64> {
+    // PDA signer seeds
+    let creator_key = vault.creator;
+    let mint_key = vault.mint;
+    let bump = vault.bump;
+    let seeds = &[b"vault", creator_key.as_ref(), mint_key.as_ref(), &[bump]];
+    let signer = &[&seeds[..]];
+
+    let balance_before = destination.amount;
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.key(),
+        TransferChecked {
+            from: vault_ata.to_account_info(),
+            mint: mint.to_account_info(),
+            to: destination.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)?;
+
+    destination.reload()?;
+    let received = destination
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(VaultError::Overflow)?;
+    Ok(received)
+}
+
+/// Mint `shares` of the pool's share mint to `destination`, signed by the vault PDA.
+pub fn mint_shares<'info>(
+    vault: &Account<'info, VaultState>,
+    share_mint: &InterfaceAccount<'info, Mint>,
+    destination: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    shares: u64,
+) -> Result<()> {
+    let creator_key = vault.creator;
+    let mint_key = vault.mint;
+    let bump = vault.bump;
+    let seeds = &[b"vault", creator_key.as_ref(), mint_key.as_ref(), &[bump]];
+    let signer = &[&seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.key(),
+        MintTo {
+            mint: share_mint.to_account_info(),
+            to: destination.to_account_info(),
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token_interface::mint_to(cpi_ctx, shares)
+}
+
+/// Close the (empty) vault ATA, signed by the vault PDA, sending its rent to `destination`.
+pub fn close_vault_ata<'info>(
+    vault: &Account<'info, VaultState>,
+    vault_ata: &InterfaceAccount<'info, TokenAccount>,
+    destination: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let creator_key = vault.creator;
+    let mint_key = vault.mint;
+    let bump = vault.bump;
+    let seeds = &[b"vault", creator_key.as_ref(), mint_key.as_ref(), &[bump]];
+    let signer = &[&seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.key(),
+        CloseAccount {
+            account: vault_ata.to_account_info(),
+            destination,
+            authority: vault.to_account_info(),
+        },
+        signer,
+    );
+    token_interface::close_account(cpi_ctx)
+}
+
+/// Close `vault`'s `DelegateRecord`s passed (writable) in `records`, sending their
+/// rent to `destination` and keeping `delegate_count` current. With `spent_at`,
+/// only records that are spent at that time are accepted. Records in an asset's
+/// mint are refused, as they are also counted on their `VaultAsset`.
+pub fn close_delegate_records<'info>(
+    vault: &mut Account<'info, VaultState>,
+    records: &'info [AccountInfo<'info>],
+    destination: &AccountInfo<'info>,
+    spent_at: Option<i64>,
+) -> Result<()> {
+    let vault_key = vault.key();
+    for info in records {
+        // Checks the program owner and discriminator
+        let rec = Account::<DelegateRecord>::try_from(info)?;
+        require_keys_eq!(rec.vault, vault_key, VaultError::Unauthorised);
+        require_keys_eq!(rec.mint, vault.mint, VaultError::AssetMismatch);
+        require!(
+            rec.version == DelegateRecord::VERSION,
+            VaultError::AccountNotMigrated
+        );
+        if let Some(now) = spent_at {
+            require!(rec.is_spent(now), VaultError::DelegateStillActive);
+        }
+
+        vault.delegate_count = vault
+            .delegate_count
+            .checked_sub(1)
+            .ok_or(VaultError::Overflow)?;
+        emit!(DelegateRemoved {
+            vault: vault_key,
+            delegate: rec.delegate,
+        });
+        rec.close(destination.clone())?;
+    }
+    Ok(())
+}
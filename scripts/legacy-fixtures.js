@@ -0,0 +1,149 @@
+#!/usr/bin/env node
+// Writes the baseline-layout accounts under tests/fixtures/, preloaded into the
+// test validator by Anchor.toml and exercised by tests/migration.ts:
+//
+//   legacy-owner.json / legacy-delegate.json   keypairs
+//   legacy-stale-delegate.json                 keypair
+//   legacy-mint.json                           mint (authority = owner, 6 decimals)
+//   legacy-vault-ata.json                      vault ATA holding 5 000
+//   legacy-vault-state.json                    154-byte VaultState, seeds [b"vault", owner, mint]
+//   legacy-delegate-record.json                97-byte DelegateRecord, allowance 1 000
+//   legacy-stale-delegate-record.json          another, left behind when the vault closes
+//
+// Re-run after changing it: node scripts/legacy-fixtures.js
+const fs = require("fs");
+const path = require("path");
+const crypto = require("crypto");
+const { Keypair, PublicKey } = require("@solana/web3.js");
+const {
+  AccountLayout,
+  MintLayout,
+  TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} = require("@solana/spl-token");
+
+const PROGRAM_ID = new PublicKey("31mDBe7jLM8UVoqfBCUpC5yKsijh3uoKekKgRR1Z7VCJ");
+const DIR = path.join(__dirname, "..", "tests", "fixtures");
+
+const VAULT_BALANCE = 5_000n;
+const DAILY_WITHDRAW_LIMIT = 10_000n;
+const DELEGATE_ALLOWANCE = 1_000n;
+const DELEGATE_EXPIRES_AT = 4_102_444_800n; // 2100-01-01
+
+const discriminator = (name) =>
+  crypto.createHash("sha256").update(`account:${name}`).digest().subarray(0, 8);
+
+const u64 = (n) => {
+  const b = Buffer.alloc(8);
+  b.writeBigUInt64LE(BigInt(n));
+  return b;
+};
+
+const rent = (len) => (128 + len) * 6_960;
+
+const writeAccount = (name, pubkey, owner, data) => {
+  const account = {
+    pubkey: pubkey.toBase58(),
+    account: {
+      lamports: rent(data.length),
+      data: [data.toString("base64"), "base64"],
+      owner: owner.toBase58(),
+      executable: false,
+      rentEpoch: 0,
+      space: data.length,
+    },
+  };
+  fs.writeFileSync(path.join(DIR, name), JSON.stringify(account, null, 2) + "\n");
+};
+
+const writeKeypair = (name, kp) =>
+  fs.writeFileSync(path.join(DIR, name), JSON.stringify(Array.from(kp.secretKey)) + "\n");
+
+fs.mkdirSync(DIR, { recursive: true });
+
+const owner = Keypair.generate();
+const delegate = Keypair.generate();
+const staleDelegate = Keypair.generate();
+const mint = Keypair.generate().publicKey;
+writeKeypair("legacy-owner.json", owner);
+writeKeypair("legacy-delegate.json", delegate);
+writeKeypair("legacy-stale-delegate.json", staleDelegate);
+
+const [vault, vaultBump] = PublicKey.findProgramAddressSync(
+  [Buffer.from("vault"), owner.publicKey.toBuffer(), mint.toBuffer()],
+  PROGRAM_ID,
+);
+const vaultAta = getAssociatedTokenAddressSync(mint, vault, true);
+
+const mintData = Buffer.alloc(MintLayout.span);
+MintLayout.encode(
+  {
+    mintAuthorityOption: 1,
+    mintAuthority: owner.publicKey,
+    supply: VAULT_BALANCE,
+    decimals: 6,
+    isInitialized: true,
+    freezeAuthorityOption: 0,
+    freezeAuthority: PublicKey.default,
+  },
+  mintData,
+);
+writeAccount("legacy-mint.json", mint, TOKEN_PROGRAM_ID, mintData);
+
+const ataData = Buffer.alloc(AccountLayout.span);
+AccountLayout.encode(
+  {
+    mint,
+    owner: vault,
+    amount: VAULT_BALANCE,
+    delegateOption: 0,
+    delegate: PublicKey.default,
+    state: 1,
+    isNativeOption: 0,
+    isNative: 0n,
+    delegatedAmount: 0n,
+    closeAuthorityOption: 0,
+    closeAuthority: PublicKey.default,
+  },
+  ataData,
+);
+writeAccount("legacy-vault-ata.json", vaultAta, TOKEN_PROGRAM_ID, ataData);
+
+// owner, mint, vault_ata, total_deposited, total_withdrawn, max_deposit,
+// daily_withdraw_limit, withdrawn_today, window_start, bump, locked
+const vaultData = Buffer.concat([
+  discriminator("VaultState"),
+  owner.publicKey.toBuffer(),
+  mint.toBuffer(),
+  vaultAta.toBuffer(),
+  u64(VAULT_BALANCE),
+  u64(0),
+  u64(0),
+  u64(DAILY_WITHDRAW_LIMIT),
+  u64(0),
+  u64(0),
+  Buffer.from([vaultBump, 0]),
+]);
+writeAccount("legacy-vault-state.json", vault, PROGRAM_ID, vaultData);
+
+// vault, delegate, allowance, used, expires_at, bump
+const writeDelegateRecord = (name, key) => {
+  const [record, bump] = PublicKey.findProgramAddressSync(
+    [Buffer.from("delegate"), vault.toBuffer(), key.toBuffer()],
+    PROGRAM_ID,
+  );
+  const data = Buffer.concat([
+    discriminator("DelegateRecord"),
+    vault.toBuffer(),
+    key.toBuffer(),
+    u64(DELEGATE_ALLOWANCE),
+    u64(0),
+    u64(DELEGATE_EXPIRES_AT),
+    Buffer.from([bump]),
+  ]);
+  writeAccount(name, record, PROGRAM_ID, data);
+};
+writeDelegateRecord("legacy-delegate-record.json", delegate.publicKey);
+writeDelegateRecord("legacy-stale-delegate-record.json", staleDelegate.publicKey);
+
+console.log(`vault ${vault.toBase58()} mint ${mint.toBase58()}`);
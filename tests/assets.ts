@@ -0,0 +1,309 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveDelegatePDA,
+  deriveAssetPDA,
+  deriveWithdrawTiersPDA,
+  getTokenBalance,
+} from "./helpers";
+
+describe("vault multi-mint assets", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const delegate = Keypair.generate();
+  const ASSET_DAILY_LIMIT = 5_000;
+
+  let mint:        PublicKey;
+  let assetMint:   PublicKey;
+  let ownerAssetAta: PublicKey;
+  let delegateAta: PublicKey;
+  let vaultState:  PublicKey;
+  let vaultAta:    PublicKey;
+  let asset:       PublicKey;
+  let assetAta:    PublicKey;
+  let delegateRec: PublicKey;
+
+  const withdrawAsset = (amount: number) =>
+    program.methods
+      .withdrawAsset(new BN(amount))
+      .accounts({
+        authority: payer.publicKey, mint: assetMint, vaultState, asset, assetAta,
+        destination: ownerAssetAta, tokenProgram,
+        role: null,
+      } as any)
+      .rpc();
+
+  const closeVault = () =>
+    program.methods
+      .closeVault()
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+
+  const removeAsset = (assetMint: PublicKey, asset: PublicKey, assetAta: PublicKey) =>
+    program.methods
+      .removeAsset()
+      .accounts({ authority: payer.publicKey, owner: payer.publicKey, mint: assetMint, vaultState, asset, assetAta, tokenProgram, role: null } as any)
+      .rpc();
+
+  // A fresh vault holding 1k of `assetMint`, for tests that leave it guarded
+  const assetVault = async () => {
+    const primary = await createTestMint(conn, payer);
+    const [vs]    = deriveVaultPDA(payer.publicKey, primary);
+    const [a]     = deriveAssetPDA(vs, assetMint);
+    const aAta    = await getAssociatedTokenAddress(assetMint, vs, true);
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint: primary, vaultState: vs, vaultAta: await getAssociatedTokenAddress(primary, vs, true), tokenProgram } as any)
+      .rpc();
+    await program.methods
+      .addAsset(new BN(0), new BN(0))
+      .accounts({ authority: payer.publicKey, mint: assetMint, vaultState: vs, asset: a, assetAta: aAta, tokenProgram, role: null } as any)
+      .rpc();
+    await program.methods
+      .depositAsset(new BN(1_000))
+      .accounts({ depositor: payer.publicKey, mint: assetMint, vaultState: vs, asset: a, depositorAta: ownerAssetAta, assetAta: aAta, depositorRecord: null, tokenProgram, role: null } as any)
+      .rpc();
+    return { vs, a, aAta };
+  };
+
+  const expectAssetOutflowsDisabled = async (vs: PublicKey, a: PublicKey, aAta: PublicKey) => {
+    try {
+      await program.methods
+        .withdrawAsset(new BN(1))
+        .accounts({ authority: payer.publicKey, mint: assetMint, vaultState: vs, asset: a, assetAta: aAta, destination: ownerAssetAta, tokenProgram, role: null } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("AssetOutflowsDisabled");
+    }
+    expect(Number(await getTokenBalance(conn, aAta))).to.equal(1_000);
+  };
+
+  before(async () => {
+    mint          = await createTestMint(conn, payer);
+    assetMint     = await createTestMint(conn, payer);
+    ownerAssetAta = await fundAta(conn, payer, assetMint, payer.publicKey, 100_000);
+    delegateAta   = await fundAta(conn, payer, assetMint, delegate.publicKey, 0);
+
+    [vaultState] = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta     = await getAssociatedTokenAddress(mint, vaultState, true);
+    [asset]      = deriveAssetPDA(vaultState, assetMint);
+    assetAta     = await getAssociatedTokenAddress(assetMint, vaultState, true);
+    [delegateRec] = deriveDelegatePDA(vaultState, delegate.publicKey, assetMint);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+  });
+
+  it("refuses to register the vault's own mint", async () => {
+    try {
+      await program.methods
+        .addAsset(new BN(0), new BN(0))
+        .accounts({
+          authority: payer.publicKey, mint, vaultState,
+          asset: deriveAssetPDA(vaultState, mint)[0], assetAta: vaultAta, tokenProgram,
+          role: null,
+        } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("DuplicateAsset");
+    }
+  });
+
+  it("registers a second mint and accepts deposits of it", async () => {
+    await program.methods
+      .addAsset(new BN(0), new BN(ASSET_DAILY_LIMIT))
+      .accounts({ authority: payer.publicKey, mint: assetMint, vaultState, asset, assetAta, tokenProgram, role: null } as any)
+      .rpc();
+
+    await program.methods
+      .depositAsset(new BN(5_000))
+      .accounts({
+        depositor: payer.publicKey, mint: assetMint, vaultState, asset,
+        depositorAta: ownerAssetAta, assetAta, depositorRecord: null, tokenProgram,
+        role: null,
+      } as any)
+      .rpc();
+
+    expect(Number(await getTokenBalance(conn, assetAta))).to.equal(5_000);
+    const a = await program.account.vaultAsset.fetch(asset);
+    expect(a.totalDeposited.toNumber()).to.equal(5_000);
+    expect((await program.account.vaultState.fetch(vaultState)).assetCount).to.equal(1);
+  });
+
+  it("an asset delegate draws only in that asset's mint", async () => {
+    await program.methods
+      .addAssetDelegate(new BN(1_000), new BN(Math.floor(Date.now() / 1000) + 3600), new BN(0), 0)
+      .accounts({
+        authority: payer.publicKey, mint: assetMint, vaultState, asset,
+        delegate: delegate.publicKey, delegateRecord: delegateRec,
+        role: null,
+      } as any)
+      .rpc();
+
+    await program.methods
+      .delegateWithdrawAsset(new BN(1_000))
+      .accounts({
+        delegateSigner: delegate.publicKey, mint: assetMint, vaultState, asset, assetAta,
+        delegateAta, delegateRecord: delegateRec, tokenProgram,
+      } as any)
+      .signers([delegate])
+      .rpc();
+    expect(Number(await getTokenBalance(conn, delegateAta))).to.equal(1_000);
+
+    const primaryAta = await fundAta(conn, payer, mint, delegate.publicKey, 0);
+    try {
+      await program.methods
+        .delegateWithdraw(new BN(1))
+        .accounts({
+          delegateSigner: delegate.publicKey, mint, vaultState, vaultAta,
+          delegateAta: primaryAta, delegateRecord: delegateRec, tokenProgram,
+          policy: null,
+          cosigner: null,
+        } as any)
+        .signers([delegate])
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("AssetMismatch");
+    }
+  });
+
+  it("enforces the asset's own daily limit", async () => {
+    // The delegate already used 1k of the 5k budget
+    try {
+      await withdrawAsset(4_001);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("DailyLimitExceeded");
+    }
+    await withdrawAsset(4_000);
+    expect(Number(await getTokenBalance(conn, assetAta))).to.equal(0);
+  });
+
+  it("one delegate holds an allowance in each asset", async () => {
+    const otherMint = await createTestMint(conn, payer);
+    const [other]   = deriveAssetPDA(vaultState, otherMint);
+    const otherAta  = await getAssociatedTokenAddress(otherMint, vaultState, true);
+    const [otherRec] = deriveDelegatePDA(vaultState, delegate.publicKey, otherMint);
+    const ownerOtherAta    = await fundAta(conn, payer, otherMint, payer.publicKey, 500);
+    const delegateOtherAta = await fundAta(conn, payer, otherMint, delegate.publicKey, 0);
+    expect(otherRec.equals(delegateRec)).to.be.false;
+
+    await program.methods
+      .addAsset(new BN(0), new BN(0))
+      .accounts({ authority: payer.publicKey, mint: otherMint, vaultState, asset: other, assetAta: otherAta, tokenProgram, role: null } as any)
+      .rpc();
+    await program.methods
+      .depositAsset(new BN(500))
+      .accounts({ depositor: payer.publicKey, mint: otherMint, vaultState, asset: other, depositorAta: ownerOtherAta, assetAta: otherAta, depositorRecord: null, tokenProgram, role: null } as any)
+      .rpc();
+    await program.methods
+      .addAssetDelegate(new BN(500), new BN(Math.floor(Date.now() / 1000) + 3600), new BN(0), 0)
+      .accounts({ authority: payer.publicKey, mint: otherMint, vaultState, asset: other, delegate: delegate.publicKey, delegateRecord: otherRec, role: null } as any)
+      .rpc();
+    expect((await program.account.vaultAsset.fetch(asset)).delegateCount).to.equal(1);
+    expect((await program.account.vaultAsset.fetch(other)).delegateCount).to.equal(1);
+
+    await program.methods
+      .delegateWithdrawAsset(new BN(500))
+      .accounts({ delegateSigner: delegate.publicKey, mint: otherMint, vaultState, asset: other, assetAta: otherAta, delegateAta: delegateOtherAta, delegateRecord: otherRec, tokenProgram } as any)
+      .signers([delegate])
+      .rpc();
+    expect(Number(await getTokenBalance(conn, delegateOtherAta))).to.equal(500);
+
+    // The asset stays registered until its delegate is gone
+    try {
+      await removeAsset(otherMint, other, otherAta);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("DelegatesOutstanding");
+    }
+    await program.methods
+      .renounceDelegate()
+      .accounts({ delegateSigner: delegate.publicKey, vaultState, owner: payer.publicKey, delegateRecord: otherRec, asset: other } as any)
+      .signers([delegate])
+      .rpc();
+    await removeAsset(otherMint, other, otherAta);
+    expect(await conn.getAccountInfo(other)).to.be.null;
+    expect(await conn.getAccountInfo(delegateRec)).to.not.be.null;
+  });
+
+  it("asset withdrawals stop while a co-signer is set", async () => {
+    const { vs, a, aAta } = await assetVault();
+    await program.methods
+      .setCosigner(Keypair.generate().publicKey, new BN(0))
+      .accounts({ owner: payer.publicKey, vaultState: vs } as any)
+      .rpc();
+    await expectAssetOutflowsDisabled(vs, a, aAta);
+  });
+
+  it("asset withdrawals stop in queued mode", async () => {
+    const { vs, a, aAta } = await assetVault();
+    await program.methods
+      .setWithdrawTiers([{ minAmount: new BN(0), delay: new BN(60) }] as any)
+      .accounts({ owner: payer.publicKey, vaultState: vs, withdrawTiers: deriveWithdrawTiersPDA(vs)[0] } as any)
+      .rpc();
+    await expectAssetOutflowsDisabled(vs, a, aAta);
+  });
+
+  it("asset withdrawals stop in veto mode", async () => {
+    const { vs, a, aAta } = await assetVault();
+    await program.methods
+      .setVeto(new BN(10_000), new BN(60))
+      .accounts({ owner: payer.publicKey, vaultState: vs } as any)
+      .rpc();
+    await expectAssetOutflowsDisabled(vs, a, aAta);
+  });
+
+  it("close_vault requires every asset to be removed", async () => {
+    try {
+      await closeVault();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("DelegatesOutstanding");
+    }
+
+    // The asset's delegate is uncounted on the asset as well as the vault
+    const removeDelegate = (asset: PublicKey | null) =>
+      program.methods
+        .removeDelegate()
+        .accounts({ authority: payer.publicKey, owner: payer.publicKey, mint, vaultState, delegate: delegate.publicKey, delegateRecord: delegateRec, asset, role: null } as any)
+        .rpc();
+    try {
+      await removeDelegate(null);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("AssetMismatch");
+    }
+    await removeDelegate(asset);
+    try {
+      await closeVault();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("AssetsOutstanding");
+    }
+
+    await removeAsset(assetMint, asset, assetAta);
+    expect(await conn.getAccountInfo(asset)).to.be.null;
+    expect(await conn.getAccountInfo(assetAta)).to.be.null;
+
+    await closeVault();
+    expect(await conn.getAccountInfo(vaultState)).to.be.null;
+  });
+});
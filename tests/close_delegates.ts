@@ -0,0 +1,88 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { createTestMint, deriveVaultPDA, deriveDelegatePDA } from "./helpers";
+
+describe("vault close with outstanding delegates", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  let mint:       PublicKey;
+  let vaultState: PublicKey;
+  let vaultAta:   PublicKey;
+  const records:  PublicKey[] = [];
+
+  const delegateCount = async () =>
+    (await program.account.vaultState.fetch(vaultState)).delegateCount;
+
+  const closeVault = (remaining: PublicKey[]) =>
+    program.methods
+      .closeVault()
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .remainingAccounts(remaining.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+      .rpc();
+
+  before(async () => {
+    mint = await createTestMint(conn, payer);
+    [vaultState] = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta     = await getAssociatedTokenAddress(mint, vaultState, true);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+
+    for (let i = 0; i < 3; i++) {
+      const delegate = Keypair.generate();
+      const [rec] = deriveDelegatePDA(vaultState, delegate.publicKey);
+      await program.methods
+        .addDelegate(new BN(1_000), new BN(Math.floor(Date.now() / 1000) + 3600), new BN(0), 0)
+        .accounts({
+          authority: payer.publicKey, mint, vaultState,
+          delegate: delegate.publicKey, delegateRecord: rec,
+          role: null,
+        } as any)
+        .rpc();
+      records.push(rec);
+    }
+  });
+
+  it("counts live delegate records", async () => {
+    expect(await delegateCount()).to.equal(3);
+  });
+
+  it("refuses to close while delegate records are outstanding", async () => {
+    try {
+      await closeVault(records.slice(0, 2));
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("DelegatesOutstanding");
+    }
+    expect(await delegateCount()).to.equal(3);
+  });
+
+  it("rejects an account that is not a delegate record", async () => {
+    try {
+      await closeVault([vaultState]);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("AccountDiscriminatorMismatch");
+    }
+  });
+
+  it("closes every delegate record together with the vault", async () => {
+    await closeVault(records);
+
+    for (const rec of records) {
+      expect(await conn.getAccountInfo(rec)).to.be.null;
+    }
+    expect(await conn.getAccountInfo(vaultState)).to.be.null;
+  });
+});
@@ -0,0 +1,75 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { createTestMint, deriveVaultPDA } from "./helpers";
+
+describe("vault config timelock", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const MAX_DEPOSIT = 1_000_000;
+  const DAILY_LIMIT = 5_000_000;
+
+  let mint:       PublicKey;
+  let vaultState: PublicKey;
+
+  const update = (maxDeposit: number, dailyLimit: number) =>
+    program.methods
+      .updateConfig(new BN(maxDeposit), new BN(dailyLimit))
+      .accounts({ authority: payer.publicKey, vaultState, role: null } as any)
+      .rpc();
+
+  before(async () => {
+    mint = await createTestMint(conn, payer);
+    [vaultState] = deriveVaultPDA(payer.publicKey, mint);
+    const vaultAta = await getAssociatedTokenAddress(mint, vaultState, true);
+
+    await program.methods
+      .initialize(new BN(MAX_DEPOSIT), new BN(DAILY_LIMIT))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+  });
+
+  it("applies a tightened limit immediately", async () => {
+    await update(MAX_DEPOSIT, DAILY_LIMIT / 2);
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.dailyWithdrawLimit.toNumber()).to.equal(DAILY_LIMIT / 2);
+    expect(state.configEffectiveAt.toNumber()).to.equal(0);
+  });
+
+  it("queues a loosened limit behind the timelock", async () => {
+    await update(MAX_DEPOSIT, 0); // 0 = unlimited, the loosest setting
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.dailyWithdrawLimit.toNumber()).to.equal(DAILY_LIMIT / 2);
+    expect(state.pendingDailyWithdrawLimit.toNumber()).to.equal(0);
+    expect(state.configEffectiveAt.toNumber()).to.be.greaterThan(
+      Math.floor(Date.now() / 1000)
+    );
+
+    try {
+      await program.methods
+        .applyConfig()
+        .accounts({ authority: payer.publicKey, vaultState, role: null } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("ConfigTimelockActive");
+    }
+  });
+
+  it("re-submitting the current limits cancels the queued change", async () => {
+    await update(MAX_DEPOSIT, DAILY_LIMIT / 2);
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.configEffectiveAt.toNumber()).to.equal(0);
+  });
+});
@@ -0,0 +1,132 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveDelegatePDA,
+  getTokenBalance,
+} from "./helpers";
+
+describe("vault co-signer", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const cosigner = Keypair.generate();
+  const other    = Keypair.generate();
+  const delegate = Keypair.generate();
+
+  let mint:        PublicKey;
+  let ownerAta:    PublicKey;
+  let delegateAta: PublicKey;
+  let vaultState:  PublicKey;
+  let vaultAta:    PublicKey;
+  let delegateRec: PublicKey;
+
+  const setCosigner = (key: PublicKey, threshold: number) =>
+    program.methods
+      .setCosigner(key, new BN(threshold))
+      .accounts({ owner: payer.publicKey, vaultState } as any)
+      .rpc();
+
+  const withdraw = (amount: number, signer: Keypair | null) => {
+    const call = program.methods
+      .withdraw(new BN(amount))
+      .accounts({ authority: payer.publicKey, mint, vaultState, role: null, vaultAta, destination: ownerAta, tokenProgram, policy: null, cosigner: signer ? signer.publicKey : null } as any);
+    return signer ? call.signers([signer]).rpc() : call.rpc();
+  };
+
+  const expectRejected = async (p: Promise<unknown>, error: string) => {
+    try {
+      await p;
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include(error);
+    }
+  };
+
+  before(async () => {
+    mint        = await createTestMint(conn, payer);
+    ownerAta    = await fundAta(conn, payer, mint, payer.publicKey, 100_000);
+    delegateAta = await fundAta(conn, payer, mint, delegate.publicKey, 0);
+
+    [vaultState]  = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta      = await getAssociatedTokenAddress(mint, vaultState, true);
+    [delegateRec] = deriveDelegatePDA(vaultState, delegate.publicKey);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await program.methods
+      .deposit(new BN(50_000), null)
+      .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
+      .rpc();
+    await program.methods
+      .addDelegate(new BN(10_000), new BN(Math.floor(Date.now() / 1000) + 3600), new BN(0), 0)
+      .accounts({ authority: payer.publicKey, mint, vaultState, delegate: delegate.publicKey, delegateRecord: delegateRec, role: null } as any)
+      .rpc();
+  });
+
+  it("adding a co-signer applies at once", async () => {
+    await setCosigner(cosigner.publicKey, 1_000);
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.cosigner.toBase58()).to.equal(cosigner.publicKey.toBase58());
+    expect(state.cosignThreshold.toNumber()).to.equal(1_000);
+  });
+
+  it("withdrawals above the threshold need the co-signer", async () => {
+    await withdraw(1_000, null);
+    await expectRejected(withdraw(1_001, null), "CosignatureRequired");
+    await expectRejected(withdraw(1_001, other), "Unauthorised");
+
+    const before = await getTokenBalance(conn, ownerAta);
+    await withdraw(5_000, cosigner);
+    expect(Number((await getTokenBalance(conn, ownerAta)) - before)).to.equal(5_000);
+  });
+
+  it("applies to delegate withdrawals too", async () => {
+    await expectRejected(
+      program.methods
+        .delegateWithdraw(new BN(2_000))
+        .accounts({ delegateSigner: delegate.publicKey, mint, vaultState, vaultAta, delegateAta, delegateRecord: delegateRec, tokenProgram, policy: null, cosigner: null } as any)
+        .signers([delegate])
+        .rpc(),
+      "CosignatureRequired",
+    );
+    await program.methods
+      .delegateWithdraw(new BN(2_000))
+      .accounts({ delegateSigner: delegate.publicKey, mint, vaultState, vaultAta, delegateAta, delegateRecord: delegateRec, tokenProgram, policy: null, cosigner: cosigner.publicKey } as any)
+      .signers([delegate, cosigner])
+      .rpc();
+    expect(Number(await getTokenBalance(conn, delegateAta))).to.equal(2_000);
+  });
+
+  it("removing or raising is timelocked, lowering is not", async () => {
+    await setCosigner(PublicKey.default, 0);
+    let state = await program.account.vaultState.fetch(vaultState);
+    expect(state.cosigner.toBase58()).to.equal(cosigner.publicKey.toBase58());
+    expect(state.cosignerEffectiveAt.toNumber()).to.be.greaterThan(0);
+
+    await expectRejected(
+      program.methods
+        .applyCosigner()
+        .accounts({ owner: payer.publicKey, vaultState } as any)
+        .rpc(),
+      "ConfigTimelockActive",
+    );
+
+    await setCosigner(cosigner.publicKey, 500);
+    state = await program.account.vaultState.fetch(vaultState);
+    expect(state.cosignThreshold.toNumber()).to.equal(500);
+    expect(state.cosignerEffectiveAt.toNumber()).to.equal(0);
+  });
+});
@@ -0,0 +1,123 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveDelegatePDA,
+} from "./helpers";
+
+describe("vault shared daily limit", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const DAILY_LIMIT = 10_000;
+
+  let mint:       PublicKey;
+  let ownerAta:   PublicKey;
+  let vaultState: PublicKey;
+  let vaultAta:   PublicKey;
+
+  type Delegate = { kp: Keypair; ata: PublicKey; rec: PublicKey };
+  const delegates: Delegate[] = [];
+
+  const pull = (d: Delegate, amount: number) =>
+    program.methods
+      .delegateWithdraw(new BN(amount))
+      .accounts({
+        delegateSigner: d.kp.publicKey,
+        mint,
+        vaultState,
+        vaultAta,
+        delegateAta: d.ata,
+        delegateRecord: d.rec,
+        tokenProgram,
+        policy: null,
+        cosigner: null,
+      } as any)
+      .signers([d.kp])
+      .rpc();
+
+  before(async () => {
+    mint     = await createTestMint(conn, payer);
+    ownerAta = await fundAta(conn, payer, mint, payer.publicKey, 100_000);
+
+    [vaultState] = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta     = await getAssociatedTokenAddress(mint, vaultState, true);
+
+    await program.methods
+      .initialize(new BN(0), new BN(DAILY_LIMIT))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await program.methods
+      .deposit(new BN(100_000), null)
+      .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
+      .rpc();
+
+    // Each delegate alone could take the whole daily limit
+    for (let i = 0; i < 3; i++) {
+      const kp = Keypair.generate();
+      const ata = await fundAta(conn, payer, mint, kp.publicKey, 0);
+      const [rec] = deriveDelegatePDA(vaultState, kp.publicKey);
+      await program.methods
+        .addDelegate(new BN(DAILY_LIMIT), new BN(Math.floor(Date.now() / 1000) + 3600), new BN(0), 0)
+        .accounts({
+          authority: payer.publicKey, mint, vaultState,
+          delegate: kp.publicKey, delegateRecord: rec,
+          role: null,
+        } as any)
+        .rpc();
+      delegates.push({ kp, ata, rec });
+    }
+  });
+
+  it("rejects two delegates once they jointly exceed the cap", async () => {
+    await pull(delegates[0], 6_000);
+    await pull(delegates[1], 4_000);
+
+    try {
+      await pull(delegates[1], 1);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("DailyLimitExceeded");
+    }
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.withdrawnToday.toNumber()).to.equal(DAILY_LIMIT);
+  });
+
+  it("owner withdrawals share the same budget", async () => {
+    try {
+      await program.methods
+        .withdraw(new BN(1))
+        .accounts({ authority: payer.publicKey, mint, vaultState, vaultAta, destination: ownerAta, tokenProgram, role: null, policy: null, cosigner: null } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("DailyLimitExceeded");
+    }
+  });
+
+  it("an exempt delegate is bound only by its allowance", async () => {
+    const exempt = delegates[2];
+    await program.methods
+      .updateDelegate({
+        allowance: null, expiresAt: null, notBefore: null, paused: null, exemptFromDailyLimit: true,
+      } as any)
+      .accounts({ authority: payer.publicKey, vaultState, delegateRecord: exempt.rec, role: null } as any)
+      .rpc();
+
+    await pull(exempt, 5_000);
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.withdrawnToday.toNumber()).to.equal(DAILY_LIMIT);
+  });
+});
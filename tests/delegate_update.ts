@@ -0,0 +1,151 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveDelegatePDA,
+  getTokenBalance,
+} from "./helpers";
+
+describe("vault delegate update", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const delegate = Keypair.generate();
+  const now = () => Math.floor(Date.now() / 1000);
+
+  let mint:        PublicKey;
+  let vaultState:  PublicKey;
+  let vaultAta:    PublicKey;
+  let delegateAta: PublicKey;
+  let delegateRec: PublicKey;
+
+  const NONE = {
+    allowance: null, expiresAt: null, notBefore: null, paused: null, exemptFromDailyLimit: null,
+  };
+
+  const update = (fields: object) =>
+    program.methods
+      .updateDelegate({ ...NONE, ...fields } as any)
+      .accounts({ authority: payer.publicKey, vaultState, delegateRecord: delegateRec, role: null } as any)
+      .rpc();
+
+  const pull = (amount: number) =>
+    program.methods
+      .delegateWithdraw(new BN(amount))
+      .accounts({
+        delegateSigner: delegate.publicKey,
+        mint,
+        vaultState,
+        vaultAta,
+        delegateAta,
+        delegateRecord: delegateRec,
+        tokenProgram,
+        policy: null,
+        cosigner: null,
+      } as any)
+      .signers([delegate])
+      .rpc();
+
+  before(async () => {
+    mint = await createTestMint(conn, payer);
+    const ownerAta = await fundAta(conn, payer, mint, payer.publicKey, 100_000);
+    delegateAta    = await fundAta(conn, payer, mint, delegate.publicKey, 0);
+
+    [vaultState]  = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta      = await getAssociatedTokenAddress(mint, vaultState, true);
+    [delegateRec] = deriveDelegatePDA(vaultState, delegate.publicKey);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await program.methods
+      .deposit(new BN(100_000), null)
+      .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
+      .rpc();
+    await program.methods
+      .addDelegate(new BN(10_000), new BN(now() + 3600), new BN(0), 0)
+      .accounts({
+        authority: payer.publicKey, mint, vaultState,
+        delegate: delegate.publicKey, delegateRecord: delegateRec,
+        role: null,
+      } as any)
+      .rpc();
+    await pull(6_000);
+  });
+
+  it("raises the allowance and extends expiry without losing `used`", async () => {
+    const expiresAt = now() + 7200;
+    await update({ allowance: new BN(20_000), expiresAt: new BN(expiresAt) });
+
+    const rec = await program.account.delegateRecord.fetch(delegateRec);
+    expect(rec.allowance.toNumber()).to.equal(20_000);
+    expect(rec.expiresAt.toNumber()).to.equal(expiresAt);
+    expect(rec.used.toNumber()).to.equal(6_000);
+
+    await pull(10_000);
+    expect(Number(await getTokenBalance(conn, delegateAta))).to.equal(16_000);
+  });
+
+  it("refuses to lower the allowance below `used`", async () => {
+    try {
+      await update({ allowance: new BN(15_000) });
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("AllowanceBelowUsed");
+    }
+  });
+
+  it("pauses and resumes the delegate", async () => {
+    await update({ paused: true });
+    try {
+      await pull(1_000);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("DelegatePaused");
+    }
+
+    await update({ paused: false });
+    await pull(1_000);
+  });
+
+  it("holds withdrawals until not_before", async () => {
+    await update({ notBefore: new BN(now() + 600) });
+    try {
+      await pull(1_000);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("DelegateNotActive");
+    }
+
+    try {
+      await update({ notBefore: new BN(now() + 10_000) });
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("InvalidDelegateWindow");
+    }
+  });
+
+  it("rejects update_delegate from a non-owner", async () => {
+    try {
+      await program.methods
+        .updateDelegate({ ...NONE, paused: false } as any)
+        .accounts({ authority: delegate.publicKey, vaultState, delegateRecord: delegateRec, role: null } as any)
+        .signers([delegate])
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("Unauthorised");
+    }
+  });
+});
@@ -0,0 +1,166 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveDepositorPDA,
+  getTokenBalance,
+} from "./helpers";
+
+describe("vault third-party deposits", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const customer = Keypair.generate();
+  const stranger = Keypair.generate();
+
+  let mint:        PublicKey;
+  let vaultState:  PublicKey;
+  let vaultAta:    PublicKey;
+  let customerAta: PublicKey;
+  let strangerAta: PublicKey;
+  let customerRec: PublicKey;
+
+  const depositFrom = (
+    who: Keypair,
+    ata: PublicKey,
+    amount: number,
+    depositorRecord: PublicKey | null = null
+  ) =>
+    program.methods
+      .deposit(new BN(amount), null)
+      .accounts({
+        depositor: who.publicKey,
+        mint,
+        vaultState,
+        depositorAta: ata,
+        vaultAta,
+        depositorRecord,
+        tranche: null,
+        shareMint: null,
+        depositorShares: null,
+        tokenProgram,
+        role: null,
+      } as any)
+      .signers([who])
+      .rpc();
+
+  const setMode = (mode: object) =>
+    program.methods
+      .setDepositMode(mode as any)
+      .accounts({ authority: payer.publicKey, vaultState, role: null } as any)
+      .rpc();
+
+  before(async () => {
+    mint        = await createTestMint(conn, payer);
+    customerAta = await fundAta(conn, payer, mint, customer.publicKey, 100_000);
+    strangerAta = await fundAta(conn, payer, mint, stranger.publicKey, 100_000);
+
+    [vaultState]  = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta      = await getAssociatedTokenAddress(mint, vaultState, true);
+    [customerRec] = deriveDepositorPDA(vaultState, customer.publicKey);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+  });
+
+  it("defaults to owner-only deposits", async () => {
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.depositMode).to.deep.equal({ ownerOnly: {} });
+
+    try {
+      await depositFrom(customer, customerAta, 1_000);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("DepositorNotAllowed");
+    }
+  });
+
+  it("open mode accepts deposits from anyone", async () => {
+    await setMode({ open: {} });
+    await depositFrom(stranger, strangerAta, 5_000);
+
+    expect(Number(await getTokenBalance(conn, vaultAta))).to.equal(5_000);
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.totalDeposited.toNumber()).to.equal(5_000);
+  });
+
+  it("rejects a depositor spending someone else's token account", async () => {
+    try {
+      await depositFrom(stranger, customerAta, 1_000);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("Unauthorised");
+    }
+  });
+
+  it("allowlist mode only accepts allowlisted depositors", async () => {
+    await setMode({ allowlist: {} });
+    await program.methods
+      .addDepositor()
+      .accounts({
+        authority: payer.publicKey,
+        vaultState,
+        depositor: customer.publicKey,
+        depositorRecord: customerRec,
+        role: null,
+      } as any)
+      .rpc();
+
+    await depositFrom(customer, customerAta, 2_000, customerRec);
+    expect(Number(await getTokenBalance(conn, vaultAta))).to.equal(7_000);
+    expect((await program.account.vaultState.fetch(vaultState)).depositorCount).to.equal(1);
+
+    try {
+      await depositFrom(stranger, strangerAta, 1_000);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("DepositorNotAllowed");
+    }
+  });
+
+  it("a removed depositor can no longer deposit", async () => {
+    await program.methods
+      .removeDepositor()
+      .accounts({
+        authority: payer.publicKey, owner: payer.publicKey,
+        vaultState,
+        depositor: customer.publicKey,
+        depositorRecord: customerRec,
+        role: null,
+      } as any)
+      .rpc();
+    expect((await program.account.vaultState.fetch(vaultState)).depositorCount).to.equal(0);
+
+    try {
+      await depositFrom(customer, customerAta, 1_000);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("DepositorNotAllowed");
+    }
+  });
+
+  it("rejects set_deposit_mode from a non-owner", async () => {
+    try {
+      await program.methods
+        .setDepositMode({ open: {} } as any)
+        .accounts({ authority: stranger.publicKey, vaultState, role: null } as any)
+        .signers([stranger])
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("Unauthorised");
+    }
+  });
+});
@@ -0,0 +1,129 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import { createTestMint, fundAta, deriveVaultPDA } from "./helpers";
+
+// Mirrors PAUSE_* in state.rs
+const PAUSE_DEPOSITS = 1;
+const PAUSE_WITHDRAWALS = 2;
+
+describe("vault guardian", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const guardian = Keypair.generate();
+
+  let mint:       PublicKey;
+  let ownerAta:   PublicKey;
+  let vaultState: PublicKey;
+  let vaultAta:   PublicKey;
+
+  const deposit = (amount: number) =>
+    program.methods
+      .deposit(new BN(amount), null)
+      .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
+      .rpc();
+
+  before(async () => {
+    mint     = await createTestMint(conn, payer);
+    ownerAta = await fundAta(conn, payer, mint, payer.publicKey, 1_000_000);
+    [vaultState] = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta     = await getAssociatedTokenAddress(mint, vaultState, true);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await deposit(100_000);
+    await program.methods
+      .setGuardian(guardian.publicKey)
+      .accounts({ owner: payer.publicKey, vaultState } as any)
+      .rpc();
+  });
+
+  it("guardian pauses deposits without touching withdrawals", async () => {
+    await program.methods
+      .pause(PAUSE_DEPOSITS)
+      .accounts({ authority: guardian.publicKey, vaultState, role: null } as any)
+      .signers([guardian])
+      .rpc();
+
+    try {
+      await deposit(1_000);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("DepositsPaused");
+    }
+
+    await program.methods
+      .withdraw(new BN(1_000))
+      .accounts({ authority: payer.publicKey, mint, vaultState, vaultAta, destination: ownerAta, tokenProgram, role: null, policy: null, cosigner: null } as any)
+      .rpc();
+  });
+
+  it("guardian cannot lift a pause before the delay", async () => {
+    const unpause = () =>
+      program.methods
+        .unpause(PAUSE_DEPOSITS)
+        .accounts({ authority: guardian.publicKey, vaultState } as any)
+        .signers([guardian])
+        .rpc();
+
+    try {
+      await unpause();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("NoPendingUnpause");
+    }
+
+    await program.methods
+      .requestUnpause(PAUSE_DEPOSITS)
+      .accounts({ guardian: guardian.publicKey, vaultState } as any)
+      .signers([guardian])
+      .rpc();
+
+    try {
+      await unpause();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("UnpauseDelayActive");
+    }
+  });
+
+  it("owner lifts a pause immediately", async () => {
+    await program.methods
+      .pause(PAUSE_WITHDRAWALS)
+      .accounts({ authority: guardian.publicKey, vaultState, role: null } as any)
+      .signers([guardian])
+      .rpc();
+    await program.methods
+      .unpause(PAUSE_DEPOSITS | PAUSE_WITHDRAWALS)
+      .accounts({ authority: payer.publicKey, vaultState } as any)
+      .rpc();
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.paused).to.equal(0);
+    await deposit(1_000);
+  });
+
+  it("rejects pause from a random key", async () => {
+    const rando = Keypair.generate();
+    try {
+      await program.methods
+        .pause(PAUSE_DEPOSITS)
+        .accounts({ authority: rando.publicKey, vaultState, role: null } as any)
+        .signers([rando])
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("Unauthorised");
+    }
+  });
+});
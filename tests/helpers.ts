@@ -3,18 +3,84 @@ import {
   createAssociatedTokenAccount,
   mintTo,
   getAccount,
+  getMintLen,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  ExtensionType,
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
 } from "@solana/spl-token";
-import { Keypair, PublicKey, Connection } from "@solana/web3.js";
+import {
+  Keypair,
+  PublicKey,
+  Connection,
+  SystemProgram,
+  Transaction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
 
 export const PROGRAM_ID = new PublicKey(
   "31mDBe7jLM8UVoqfBCUpC5yKsijh3uoKekKgRR1Z7VCJ"
 );
 
+export const MOCK_STRATEGY_ID = new PublicKey(
+  "ESWWuqRZL9X4F97dVUPZV54Lh32p98q2g3wAhbge4WDa"
+);
+
 export async function createTestMint(
   connection: Connection,
-  payer: Keypair
+  payer: Keypair,
+  programId: PublicKey = TOKEN_PROGRAM_ID
 ): Promise<PublicKey> {
-  return createMint(connection, payer, payer.publicKey, null, 6);
+  return createMint(
+    connection,
+    payer,
+    payer.publicKey,
+    null,
+    6,
+    undefined,
+    undefined,
+    programId
+  );
+}
+
+/** Token-2022 mint with a transfer-fee extension (`feeBps` capped at `maxFee`). */
+export async function createTransferFeeMint(
+  connection: Connection,
+  payer: Keypair,
+  feeBps: number,
+  maxFee: bigint
+): Promise<PublicKey> {
+  const mintKp = Keypair.generate();
+  const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+  const lamports = await connection.getMinimumBalanceForRentExemption(mintLen);
+
+  const tx = new Transaction().add(
+    SystemProgram.createAccount({
+      fromPubkey: payer.publicKey,
+      newAccountPubkey: mintKp.publicKey,
+      space: mintLen,
+      lamports,
+      programId: TOKEN_2022_PROGRAM_ID,
+    }),
+    createInitializeTransferFeeConfigInstruction(
+      mintKp.publicKey,
+      payer.publicKey,
+      payer.publicKey,
+      feeBps,
+      maxFee,
+      TOKEN_2022_PROGRAM_ID
+    ),
+    createInitializeMintInstruction(
+      mintKp.publicKey,
+      6,
+      payer.publicKey,
+      null,
+      TOKEN_2022_PROGRAM_ID
+    )
+  );
+  await sendAndConfirmTransaction(connection, tx, [payer, mintKp]);
+  return mintKp.publicKey;
 }
 
 export async function fundAta(
@@ -22,37 +88,185 @@ export async function fundAta(
   payer: Keypair,
   mint: PublicKey,
   owner: PublicKey,
-  amount: number
+  amount: number,
+  programId: PublicKey = TOKEN_PROGRAM_ID
 ): Promise<PublicKey> {
-  const ata = await createAssociatedTokenAccount(connection, payer, mint, owner);
-  await mintTo(connection, payer, mint, ata, payer, amount);
+  const ata = await createAssociatedTokenAccount(
+    connection,
+    payer,
+    mint,
+    owner,
+    undefined,
+    programId
+  );
+  await mintTo(connection, payer, mint, ata, payer, amount, [], undefined, programId);
   return ata;
 }
 
+/** Vault PDA — keyed by the vault's creator, which stays fixed across owner transfers. */
 export function deriveVaultPDA(
-  owner: PublicKey,
+  creator: PublicKey,
   mint: PublicKey
 ): [PublicKey, number] {
   return PublicKey.findProgramAddressSync(
-    [Buffer.from("vault"), owner.toBuffer(), mint.toBuffer()],
+    [Buffer.from("vault"), creator.toBuffer(), mint.toBuffer()],
     PROGRAM_ID
   );
 }
 
+/** Pass `assetMint` for a delegate whose allowance is in one of the vault's assets. */
 export function deriveDelegatePDA(
   vault: PublicKey,
-  delegate: PublicKey
+  delegate: PublicKey,
+  assetMint?: PublicKey
+): [PublicKey, number] {
+  const seeds = [Buffer.from("delegate"), vault.toBuffer(), delegate.toBuffer()];
+  if (assetMint) seeds.push(assetMint.toBuffer());
+  return PublicKey.findProgramAddressSync(seeds, PROGRAM_ID);
+}
+
+export function deriveDepositorPDA(
+  vault: PublicKey,
+  depositor: PublicKey
 ): [PublicKey, number] {
   return PublicKey.findProgramAddressSync(
-    [Buffer.from("delegate"), vault.toBuffer(), delegate.toBuffer()],
+    [Buffer.from("depositor"), vault.toBuffer(), depositor.toBuffer()],
     PROGRAM_ID
   );
 }
 
 export async function getTokenBalance(
   connection: Connection,
-  ata: PublicKey
+  ata: PublicKey,
+  programId: PublicKey = TOKEN_PROGRAM_ID
 ): Promise<bigint> {
-  const account = await getAccount(connection, ata);
+  const account = await getAccount(connection, ata, undefined, programId);
   return account.amount;
 }
+
+export function deriveStreamPDA(
+  vault: PublicKey,
+  streamId: number
+): [PublicKey, number] {
+  const le = Buffer.alloc(8);
+  le.writeBigUInt64LE(BigInt(streamId));
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("stream"), vault.toBuffer(), le],
+    PROGRAM_ID
+  );
+}
+
+export function deriveTranchePDA(
+  vault: PublicKey,
+  trancheId: number
+): [PublicKey, number] {
+  const le = Buffer.alloc(8);
+  le.writeBigUInt64LE(BigInt(trancheId));
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("tranche"), vault.toBuffer(), le],
+    PROGRAM_ID
+  );
+}
+
+export function deriveAssetPDA(
+  vault: PublicKey,
+  mint: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("asset"), vault.toBuffer(), mint.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+export function deriveShareMintPDA(vault: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("shares"), vault.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+export function deriveStrategyStatePDA(vault: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("strategy"), vault.toBuffer()],
+    MOCK_STRATEGY_ID
+  );
+}
+
+export function deriveStrategyAuthorityPDA(vault: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("strategy_authority"), vault.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+export function deriveRolePDA(
+  vault: PublicKey,
+  member: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("role"), vault.toBuffer(), member.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+export function derivePolicyPDA(vault: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("policy"), vault.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+export function deriveWithdrawTiersPDA(vault: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("withdraw_tiers"), vault.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+export function derivePendingWithdrawalPDA(
+  vault: PublicKey,
+  requestId: number
+): [PublicKey, number] {
+  const le = Buffer.alloc(8);
+  le.writeBigUInt64LE(BigInt(requestId));
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("pending_withdrawal"), vault.toBuffer(), le],
+    PROGRAM_ID
+  );
+}
+
+export function deriveVetoKeyPDA(
+  vault: PublicKey,
+  key: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("veto"), vault.toBuffer(), key.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+export function deriveRecoveryPDA(vault: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("recovery"), vault.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+export function deriveMultisigPDA(vault: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("multisig"), vault.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+export function deriveProposalPDA(
+  vault: PublicKey,
+  index: number
+): [PublicKey, number] {
+  const le = Buffer.alloc(8);
+  le.writeBigUInt64LE(BigInt(index));
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("proposal"), vault.toBuffer(), le],
+    PROGRAM_ID
+  );
+}
@@ -0,0 +1,155 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  derivePendingWithdrawalPDA,
+  getTokenBalance,
+} from "./helpers";
+
+const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
+
+describe("vault dead-man switch", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const heir     = Keypair.generate();
+  const outsider = Keypair.generate();
+
+  let mint:       PublicKey;
+  let ownerAta:   PublicKey;
+  let heirAta:    PublicKey;
+  let vaultState: PublicKey;
+  let vaultAta:   PublicKey;
+
+  const setBeneficiary = (beneficiary: PublicKey, period: number, claimLimit: number) =>
+    program.methods
+      .setBeneficiary(beneficiary, new BN(period), new BN(claimLimit))
+      .accounts({ owner: payer.publicKey, vaultState } as any)
+      .rpc();
+
+  const claim = (signer: Keypair, amount: number) =>
+    program.methods
+      .claimInheritance(new BN(amount))
+      .accounts({ beneficiary: signer.publicKey, mint, vaultState, vaultAta, destination: heirAta, tokenProgram, policy: null, cosigner: null } as any)
+      .signers([signer])
+      .rpc();
+
+  const lastHeartbeat = async () =>
+    (await program.account.vaultState.fetch(vaultState)).lastHeartbeat.toNumber();
+
+  const expectRejected = async (p: Promise<unknown>, error: string) => {
+    try {
+      await p;
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include(error);
+    }
+  };
+
+  before(async () => {
+    mint     = await createTestMint(conn, payer);
+    ownerAta = await fundAta(conn, payer, mint, payer.publicKey, 100_000);
+    heirAta  = await fundAta(conn, payer, mint, heir.publicKey, 0);
+
+    [vaultState] = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta     = await getAssociatedTokenAddress(mint, vaultState, true);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await program.methods
+      .deposit(new BN(50_000), null)
+      .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
+      .rpc();
+    await setBeneficiary(heir.publicKey, 2, 3_000);
+  });
+
+  it("nothing can be claimed while the owner is active", async () => {
+    await expectRejected(claim(heir, 1_000), "OwnerStillActive");
+  });
+
+  it("after the inactivity period only the beneficiary claims, within the claim limit", async () => {
+    await sleep(3_000);
+    await expectRejected(claim(outsider, 1_000), "Unauthorised");
+
+    await claim(heir, 2_000);
+    expect(Number(await getTokenBalance(conn, heirAta))).to.equal(2_000);
+    await expectRejected(claim(heir, 2_000), "DailyLimitExceeded");
+  });
+
+  it("any owner-signed instruction, or a ping, refreshes the heartbeat", async () => {
+    const before = await lastHeartbeat();
+    await program.methods
+      .deposit(new BN(1_000), null)
+      .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
+      .rpc();
+    expect(await lastHeartbeat()).to.be.greaterThan(before);
+    await expectRejected(claim(heir, 500), "OwnerStillActive");
+
+    await sleep(3_000);
+    const stamps: number[] = [];
+    const listener = program.addEventListener("ownerHeartbeat", (e: any) => stamps.push(e.timestamp.toNumber()));
+    await program.methods
+      .ping()
+      .accounts({ owner: payer.publicKey, vaultState } as any)
+      .rpc();
+    await sleep(1_000);
+    await program.removeEventListener(listener);
+
+    expect(stamps).to.have.length(1);
+    await expectRejected(claim(heir, 500), "OwnerStillActive");
+  });
+
+  it("the owner can turn the feature off", async () => {
+    await setBeneficiary(PublicKey.default, 0, 0);
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.beneficiary.equals(PublicKey.default)).to.be.true;
+    expect(state.inactivityPeriod.toNumber()).to.equal(0);
+
+    await sleep(3_000);
+    await expectRejected(claim(heir, 500), "Unauthorised");
+  });
+
+  it("claims above the veto threshold are queued and wait out the veto window", async () => {
+    const sig = await conn.requestAirdrop(heir.publicKey, LAMPORTS_PER_SOL);
+    await conn.confirmTransaction(sig);
+    const [pending] = derivePendingWithdrawalPDA(vaultState, 1);
+    const request = () =>
+      program.methods
+        .requestWithdraw(new BN(1), new BN(5_000))
+        .accounts({ authority: heir.publicKey, vaultState, role: null, withdrawTiers: null, destination: heirAta, cosigner: null, pendingWithdrawal: pending } as any)
+        .signers([heir])
+        .rpc();
+
+    await program.methods
+      .setVeto(new BN(1_000), new BN(1))
+      .accounts({ owner: payer.publicKey, vaultState } as any)
+      .rpc();
+    await setBeneficiary(heir.publicKey, 2, 0);
+    await expectRejected(request(), "OwnerStillActive");
+
+    await sleep(3_000);
+    await expectRejected(claim(heir, 5_000), "VetoWindowRequired");
+    await request();
+    await sleep(2_000);
+
+    const before = await getTokenBalance(conn, heirAta);
+    await program.methods
+      .executeWithdraw()
+      .accounts({ executor: heir.publicKey, mint, vaultState, vaultAta, pendingWithdrawal: pending, requester: heir.publicKey, destination: heirAta, policy: null, tokenProgram } as any)
+      .signers([heir])
+      .rpc();
+    expect(Number((await getTokenBalance(conn, heirAta)) - before)).to.equal(5_000);
+  });
+});
@@ -0,0 +1,204 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveMultisigPDA,
+  deriveProposalPDA,
+  getTokenBalance,
+} from "./helpers";
+
+describe("vault multisig", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  // 2-of-3: the wallet plus two fresh keys
+  const alice = Keypair.generate();
+  const bob   = Keypair.generate();
+
+  let mint:       PublicKey;
+  let ownerAta:   PublicKey;
+  let vaultState: PublicKey;
+  let vaultAta:   PublicKey;
+  let multisig:   PublicKey;
+  let vendorAta:  PublicKey;
+
+  before(async () => {
+    for (const kp of [alice, bob]) {
+      const sig = await conn.requestAirdrop(kp.publicKey, LAMPORTS_PER_SOL);
+      await conn.confirmTransaction(sig);
+    }
+
+    mint      = await createTestMint(conn, payer);
+    ownerAta  = await fundAta(conn, payer, mint, payer.publicKey, 1_000_000);
+    vendorAta = await fundAta(conn, payer, mint, Keypair.generate().publicKey, 0);
+
+    [vaultState] = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta     = await getAssociatedTokenAddress(mint, vaultState, true);
+    [multisig]   = deriveMultisigPDA(vaultState);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await program.methods
+      .deposit(new BN(500_000), null)
+      .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
+      .rpc();
+  });
+
+  it("enables a 2-of-3 signer set", async () => {
+    await program.methods
+      .enableMultisig([payer.publicKey, alice.publicKey, bob.publicKey], 2)
+      .accounts({ owner: payer.publicKey, mint, vaultState, multisig } as any)
+      .rpc();
+
+    const ms    = await program.account.multisig.fetch(multisig);
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(ms.threshold).to.equal(2);
+    expect(ms.signers.length).to.equal(3);
+    expect(state.multisig).to.be.true;
+  });
+
+  it("blocks the owner's direct withdraw", async () => {
+    try {
+      await program.methods
+        .withdraw(new BN(1_000))
+        .accounts({ authority: payer.publicKey, mint, vaultState, vaultAta, destination: ownerAta, tokenProgram, role: null, policy: null, cosigner: null } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("MultisigRequired");
+    }
+  });
+
+  it("executes a withdraw proposal only after the threshold", async () => {
+    const [proposal] = deriveProposalPDA(vaultState, 0);
+    const execute = () =>
+      program.methods
+        .executeWithdrawProposal()
+        .accounts({
+          executor:    alice.publicKey,
+          proposer:    alice.publicKey,
+          mint,
+          vaultState,
+          multisig,
+          proposal,
+          vaultAta,
+          destination: vendorAta,
+          tokenProgram,
+          policy:      null,
+          cosigner:    null,
+        } as any)
+        .signers([alice])
+        .rpc();
+
+    await program.methods
+      .createProposal({ withdraw: { amount: new BN(100_000), destination: vendorAta } } as any)
+      .accounts({ proposer: alice.publicKey, vaultState, multisig, proposal } as any)
+      .signers([alice])
+      .rpc();
+
+    try {
+      await execute();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("ThresholdNotMet");
+    }
+
+    await program.methods
+      .approveProposal()
+      .accounts({ signer: bob.publicKey, vaultState, multisig, proposal } as any)
+      .signers([bob])
+      .rpc();
+
+    await execute();
+    expect(Number(await getTokenBalance(conn, vendorAta))).to.equal(100_000);
+  });
+
+  it("rejects approvals from outside the signer set", async () => {
+    const [proposal] = deriveProposalPDA(vaultState, 1);
+    await program.methods
+      .createProposal({ closeVault: {} } as any)
+      .accounts({ proposer: payer.publicKey, vaultState, multisig, proposal } as any)
+      .rpc();
+
+    const outsider = Keypair.generate();
+    try {
+      await program.methods
+        .approveProposal()
+        .accounts({ signer: outsider.publicKey, vaultState, multisig, proposal } as any)
+        .signers([outsider])
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("NotMultisigSigner");
+    }
+  });
+
+  it("closes only once every other proposal is executed or cancelled", async () => {
+    const [closeProposal] = deriveProposalPDA(vaultState, 1);
+    const [drain]         = deriveProposalPDA(vaultState, 2);
+    const [straggler]     = deriveProposalPDA(vaultState, 3);
+    const create = (proposal: PublicKey, action: object) =>
+      program.methods
+        .createProposal(action as any)
+        .accounts({ proposer: alice.publicKey, vaultState, multisig, proposal } as any)
+        .signers([alice])
+        .rpc();
+    const approve = (proposal: PublicKey, signer: Keypair) =>
+      program.methods
+        .approveProposal()
+        .accounts({ signer: signer.publicKey, vaultState, multisig, proposal } as any)
+        .signers([signer])
+        .rpc();
+    const close = () =>
+      program.methods
+        .executeCloseVaultProposal()
+        .accounts({ executor: alice.publicKey, proposer: payer.publicKey, owner: payer.publicKey, vaultState, multisig, proposal: closeProposal, vaultAta, tokenProgram } as any)
+        .signers([alice])
+        .rpc();
+
+    // Empty the vault so only the open proposals stand in the way
+    await create(drain, { withdraw: { amount: new BN(400_000), destination: vendorAta } });
+    await approve(drain, bob);
+    await program.methods
+      .executeWithdrawProposal()
+      .accounts({ executor: alice.publicKey, proposer: alice.publicKey, mint, vaultState, multisig, proposal: drain, vaultAta, destination: vendorAta, tokenProgram, policy: null, cosigner: null } as any)
+      .signers([alice])
+      .rpc();
+
+    await create(straggler, { withdraw: { amount: new BN(1), destination: vendorAta } });
+    const ms = await program.account.multisig.fetch(multisig);
+    expect(ms.openProposals).to.equal(2);
+    expect((await program.account.proposal.fetch(straggler)).generation.eq(ms.generation)).to.be.true;
+
+    await approve(closeProposal, alice);
+    try {
+      await close();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("ProposalsOutstanding");
+    }
+
+    await program.methods
+      .cancelProposal()
+      .accounts({ proposer: alice.publicKey, vaultState, multisig, proposal: straggler } as any)
+      .signers([alice])
+      .rpc();
+    expect((await program.account.multisig.fetch(multisig)).openProposals).to.equal(1);
+
+    await close();
+    expect(await conn.getAccountInfo(vaultState)).to.be.null;
+    expect(await conn.getAccountInfo(multisig)).to.be.null;
+  });
+});
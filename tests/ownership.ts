@@ -0,0 +1,122 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveDelegatePDA,
+  getTokenBalance,
+} from "./helpers";
+
+describe("vault owner transfer", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const newOwner = Keypair.generate();
+  const delegate = Keypair.generate();
+
+  let mint:        PublicKey;
+  let ownerAta:    PublicKey;
+  let newOwnerAta: PublicKey;
+  let vaultState:  PublicKey;
+  let vaultAta:    PublicKey;
+  let delegateRec: PublicKey;
+
+  before(async () => {
+    const sig = await conn.requestAirdrop(newOwner.publicKey, LAMPORTS_PER_SOL);
+    await conn.confirmTransaction(sig);
+
+    mint        = await createTestMint(conn, payer);
+    ownerAta    = await fundAta(conn, payer, mint, payer.publicKey, 1_000_000);
+    newOwnerAta = await fundAta(conn, payer, mint, newOwner.publicKey, 0);
+
+    [vaultState]  = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta      = await getAssociatedTokenAddress(mint, vaultState, true);
+    [delegateRec] = deriveDelegatePDA(vaultState, delegate.publicKey);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await program.methods
+      .deposit(new BN(300_000), null)
+      .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
+      .rpc();
+    await program.methods
+      .addDelegate(new BN(10_000), new BN(Math.floor(Date.now() / 1000) + 3600), new BN(0), 0)
+      .accounts({
+        authority: payer.publicKey, mint, vaultState,
+        delegate: delegate.publicKey, delegateRecord: delegateRec,
+        role: null,
+      } as any)
+      .rpc();
+  });
+
+  it("only the nominated key can accept", async () => {
+    await program.methods
+      .proposeOwnerTransfer(newOwner.publicKey)
+      .accounts({ owner: payer.publicKey, vaultState } as any)
+      .rpc();
+
+    const impostor = Keypair.generate();
+    try {
+      await program.methods
+        .acceptOwnerTransfer()
+        .accounts({ newOwner: impostor.publicKey, vaultState } as any)
+        .signers([impostor])
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("Unauthorised");
+    }
+  });
+
+  it("hands authority over without moving the vault", async () => {
+    await program.methods
+      .acceptOwnerTransfer()
+      .accounts({ newOwner: newOwner.publicKey, vaultState } as any)
+      .signers([newOwner])
+      .rpc();
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.owner.toString()).to.equal(newOwner.publicKey.toString());
+    expect(state.creator.toString()).to.equal(payer.publicKey.toString());
+
+    // Delegate records are keyed by the vault PDA, so they carry over
+    const rec = await program.account.delegateRecord.fetch(delegateRec);
+    expect(rec.vault.toString()).to.equal(vaultState.toString());
+  });
+
+  it("old owner loses access, new owner can withdraw", async () => {
+    try {
+      await program.methods
+        .withdraw(new BN(1_000))
+        .accounts({ authority: payer.publicKey, mint, vaultState, vaultAta, destination: ownerAta, tokenProgram, role: null, policy: null, cosigner: null } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("Unauthorised");
+    }
+
+    await program.methods
+      .withdraw(new BN(100_000))
+      .accounts({
+        authority: newOwner.publicKey, mint, vaultState, vaultAta,
+        destination: newOwnerAta, tokenProgram,
+        role: null,
+        policy: null,
+        cosigner: null,
+      } as any)
+      .signers([newOwner])
+      .rpc();
+    expect(Number(await getTokenBalance(conn, newOwnerAta))).to.equal(100_000);
+  });
+});
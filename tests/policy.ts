@@ -0,0 +1,161 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveDelegatePDA,
+  derivePolicyPDA,
+  deriveStreamPDA,
+  getTokenBalance,
+} from "./helpers";
+
+describe("vault withdrawal policy", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const delegate = Keypair.generate();
+  const vendor   = Keypair.generate();
+
+  let mint:        PublicKey;
+  let ownerAta:    PublicKey;
+  let vendorAta:   PublicKey;
+  let delegateAta: PublicKey;
+  let vaultState:  PublicKey;
+  let vaultAta:    PublicKey;
+  let delegateRec: PublicKey;
+  let policy:      PublicKey;
+
+  const addRule = (rule: object) =>
+    program.methods
+      .addPolicyRule(rule as any)
+      .accounts({ owner: payer.publicKey, vaultState, policy } as any)
+      .rpc();
+
+  const removeRule = (index: number) =>
+    program.methods
+      .removePolicyRule(index)
+      .accounts({ owner: payer.publicKey, vaultState, policy } as any)
+      .rpc();
+
+  const withdraw = (amount: number, destination: PublicKey, withPolicy = true) =>
+    program.methods
+      .withdraw(new BN(amount))
+      .accounts({ authority: payer.publicKey, mint, vaultState, role: null, vaultAta, destination, tokenProgram, policy: withPolicy ? policy : null, cosigner: null } as any)
+      .rpc();
+
+  const pull = (amount: number) =>
+    program.methods
+      .delegateWithdraw(new BN(amount))
+      .accounts({ delegateSigner: delegate.publicKey, mint, vaultState, vaultAta, delegateAta, delegateRecord: delegateRec, tokenProgram, policy, cosigner: null } as any)
+      .signers([delegate])
+      .rpc();
+
+  const expectRejected = async (p: Promise<unknown>, error: string) => {
+    try {
+      await p;
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include(error);
+    }
+  };
+
+  before(async () => {
+    mint        = await createTestMint(conn, payer);
+    ownerAta    = await fundAta(conn, payer, mint, payer.publicKey, 100_000);
+    vendorAta   = await fundAta(conn, payer, mint, vendor.publicKey, 0);
+    delegateAta = await fundAta(conn, payer, mint, delegate.publicKey, 0);
+
+    [vaultState]  = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta      = await getAssociatedTokenAddress(mint, vaultState, true);
+    [delegateRec] = deriveDelegatePDA(vaultState, delegate.publicKey);
+    [policy]      = derivePolicyPDA(vaultState);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await program.methods
+      .deposit(new BN(50_000), null)
+      .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
+      .rpc();
+    await program.methods
+      .addDelegate(new BN(10_000), new BN(Math.floor(Date.now() / 1000) + 3600), new BN(0), 0)
+      .accounts({ authority: payer.publicKey, mint, vaultState, delegate: delegate.publicKey, delegateRecord: delegateRec, role: null } as any)
+      .rpc();
+  });
+
+  it("rejects rules that could never pass", async () => {
+    await expectRejected(addRule({ maxPerTransaction: { max: new BN(0) } }), "InvalidPolicyRule");
+    await expectRejected(addRule({ maxWithdrawals: { count: 3, window: new BN(0) } }), "InvalidPolicyRule");
+  });
+
+  it("adds rules and then requires the policy account", async () => {
+    await addRule({ maxPerTransaction: { max: new BN(5_000) } });
+    await addRule({ minAmount: { min: new BN(100) } });
+    await addRule({ recipientCap: { recipient: vendor.publicKey, cap: new BN(3_000) } });
+
+    const state = await program.account.policy.fetch(policy);
+    expect(state.rules.length).to.equal(3);
+    expect((await program.account.vaultState.fetch(vaultState)).policyRuleCount).to.equal(3);
+
+    await expectRejected(withdraw(1_000, ownerAta, false), "PolicyAccountMismatch");
+  });
+
+  it("names the rule that rejected the withdrawal", async () => {
+    await expectRejected(withdraw(6_000, ownerAta), "PolicyMaxPerTransaction");
+    await expectRejected(withdraw(50, ownerAta), "PolicyMinAmount");
+    await expectRejected(pull(6_000), "PolicyMaxPerTransaction");
+
+    await withdraw(2_000, vendorAta);
+    await expectRejected(withdraw(2_000, vendorAta), "PolicyRecipientCap");
+    await withdraw(2_000, ownerAta);
+    expect(Number(await getTokenBalance(conn, vendorAta))).to.equal(2_000);
+  });
+
+  it("stream claims are held to the same rules", async () => {
+    const [stream] = deriveStreamPDA(vaultState, 0);
+    const start = Math.floor(Date.now() / 1000) + 2;
+    await program.methods
+      .createStream(new BN(0), new BN(6_000), new BN(start), new BN(start), new BN(start + 1))
+      .accounts({ authority: payer.publicKey, vaultState, vaultAta, recipient: vendor.publicKey, stream, role: null } as any)
+      .rpc();
+    await new Promise((r) => setTimeout(r, 5_000));
+
+    const claim = (withPolicy: boolean) =>
+      program.methods
+        .claimStream()
+        .accounts({ recipient: vendor.publicKey, mint, vaultState, vaultAta, owner: payer.publicKey, stream, destination: vendorAta, tokenProgram, policy: withPolicy ? policy : null, cosigner: null } as any)
+        .signers([vendor])
+        .rpc();
+    await expectRejected(claim(false), "PolicyAccountMismatch");
+    await expectRejected(claim(true), "PolicyMaxPerTransaction");
+  });
+
+  it("caps the number of withdrawals per window, delegates included", async () => {
+    await addRule({ maxWithdrawals: { count: 2, window: new BN(3600) } });
+    await pull(1_000);
+    await withdraw(1_000, ownerAta);
+    await expectRejected(pull(1_000), "PolicyMaxWithdrawals");
+
+    const rec = await program.account.delegateRecord.fetch(delegateRec);
+    expect(rec.used.toNumber()).to.equal(1_000);
+  });
+
+  it("removing every rule closes the policy", async () => {
+    await expectRejected(removeRule(4), "PolicyRuleNotFound");
+    for (let i = 3; i >= 0; i--) await removeRule(i);
+
+    expect(await conn.getAccountInfo(policy)).to.be.null;
+    expect((await program.account.vaultState.fetch(vaultState)).policyRuleCount).to.equal(0);
+    await withdraw(6_000, ownerAta, false);
+  });
+});
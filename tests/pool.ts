@@ -0,0 +1,200 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import {
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddress,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveShareMintPDA,
+  deriveTranchePDA,
+  deriveStrategyStatePDA,
+  getTokenBalance,
+  MOCK_STRATEGY_ID,
+} from "./helpers";
+
+describe("vault pooled share accounting", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const bob = Keypair.generate();
+
+  let mint:        PublicKey;
+  let ownerAta:    PublicKey;
+  let bobAta:      PublicKey;
+  let vaultState:  PublicKey;
+  let vaultAta:    PublicKey;
+  let shareMint:   PublicKey;
+  let ownerShares: PublicKey;
+  let bobShares:   PublicKey;
+
+  const deposit = (who: Keypair, ata: PublicKey, shares: PublicKey, amount: number) =>
+    program.methods
+      .deposit(new BN(amount), null)
+      .accounts({
+        depositor: who.publicKey, mint, vaultState, depositorAta: ata, vaultAta,
+        depositorRecord: null, tranche: null, shareMint, depositorShares: shares, tokenProgram,
+        role: null,
+      } as any)
+      .signers(who === payer ? [] : [who])
+      .rpc();
+
+  const setStrategy = (key: PublicKey, state: PublicKey) =>
+    program.methods
+      .setStrategy(key, state)
+      .accounts({ owner: payer.publicKey, vaultState, vaultAta } as any)
+      .rpc();
+
+  const shareSupply = async () =>
+    Number((await conn.getTokenSupply(shareMint)).value.amount);
+
+  before(async () => {
+    mint     = await createTestMint(conn, payer);
+    ownerAta = await fundAta(conn, payer, mint, payer.publicKey, 100_000);
+    bobAta   = await fundAta(conn, payer, mint, bob.publicKey, 100_000);
+
+    [vaultState] = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta     = await getAssociatedTokenAddress(mint, vaultState, true);
+    [shareMint]  = deriveShareMintPDA(vaultState);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await program.methods
+      .setDepositMode({ open: {} } as any)
+      .accounts({ authority: payer.publicKey, vaultState, role: null } as any)
+      .rpc();
+  });
+
+  it("refuses to pool a vault with a strategy set", async () => {
+    await setStrategy(MOCK_STRATEGY_ID, deriveStrategyStatePDA(vaultState)[0]);
+    try {
+      await program.methods
+        .enablePool()
+        .accounts({ authority: payer.publicKey, mint, vaultState, vaultAta, shareMint, tokenProgram, role: null } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("PooledStrategy");
+    }
+    await setStrategy(PublicKey.default, PublicKey.default);
+  });
+
+  it("enables pooled mode with a vault-owned share mint", async () => {
+    await program.methods
+      .enablePool()
+      .accounts({ authority: payer.publicKey, mint, vaultState, vaultAta, shareMint, tokenProgram, role: null } as any)
+      .rpc();
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.shareMint.toBase58()).to.equal(shareMint.toBase58());
+
+    ownerShares = await createAssociatedTokenAccount(conn, payer, shareMint, payer.publicKey);
+    bobShares   = await createAssociatedTokenAccount(conn, payer, shareMint, bob.publicKey);
+  });
+
+  it("a pooled vault cannot take a strategy", async () => {
+    try {
+      await setStrategy(MOCK_STRATEGY_ID, deriveStrategyStatePDA(vaultState)[0]);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("PooledStrategy");
+    }
+  });
+
+  it("requires the share accounts once pooled", async () => {
+    try {
+      await program.methods
+        .deposit(new BN(1_000), null)
+        .accounts({
+          depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta,
+          depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram,
+          role: null,
+        } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("ShareAccountMismatch");
+    }
+  });
+
+  it("refuses lock-up tranches once pooled", async () => {
+    try {
+      await program.methods
+        .deposit(new BN(1_000), new BN(Math.floor(Date.now() / 1000) + 3600))
+        .accounts({
+          depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta,
+          depositorRecord: null, tranche: deriveTranchePDA(vaultState, 0)[0], shareMint,
+          depositorShares: ownerShares, tokenProgram, role: null,
+        } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("PooledVault");
+    }
+  });
+
+  it("mints shares in proportion to each deposit", async () => {
+    await deposit(payer, ownerAta, ownerShares, 10_000);
+    await deposit(bob, bobAta, bobShares, 5_000);
+
+    expect(Number(await getTokenBalance(conn, ownerShares))).to.equal(10_000);
+    expect(Number(await getTokenBalance(conn, bobShares))).to.equal(5_000);
+  });
+
+  it("blocks the owner's direct withdraw", async () => {
+    try {
+      await program.methods
+        .withdraw(new BN(1_000))
+        .accounts({ authority: payer.publicKey, mint, vaultState, vaultAta, destination: ownerAta, tokenProgram, role: null, policy: null, cosigner: null } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("PooledVault");
+    }
+  });
+
+  it("rejects a deposit that would round down to zero shares", async () => {
+    // A direct donation inflates the price of each share
+    await mintTo(conn, payer, mint, vaultAta, payer, 1_000_000);
+    try {
+      await deposit(bob, bobAta, bobShares, 10);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("ZeroShares");
+    }
+  });
+
+  it("burns shares to redeem a pro-rata part of the pool", async () => {
+    const assets = Number(await getTokenBalance(conn, vaultAta));
+    const supply = await shareSupply();
+    const expected = Math.floor((5_000 * (assets + 1)) / (supply + 1));
+
+    const before = Number(await getTokenBalance(conn, bobAta));
+    await program.methods
+      .withdrawShares(new BN(5_000))
+      .accounts({
+        holder: bob.publicKey, mint, vaultState, vaultAta, shareMint,
+        holderShares: bobShares, destination: bobAta, tokenProgram,
+        policy: null, cosigner: null,
+      } as any)
+      .signers([bob])
+      .rpc();
+
+    expect(Number(await getTokenBalance(conn, bobAta)) - before).to.equal(expected);
+    expect(Number(await getTokenBalance(conn, bobShares))).to.equal(0);
+    expect(await shareSupply()).to.equal(supply - 5_000);
+  });
+});
@@ -0,0 +1,130 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveDelegatePDA,
+} from "./helpers";
+
+const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
+
+describe("vault delegate pruning", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const cranker = Keypair.generate();
+  const now = () => Math.floor(Date.now() / 1000);
+
+  let mint:       PublicKey;
+  let vaultState: PublicKey;
+  let vaultAta:   PublicKey;
+
+  const addDelegate = async (allowance: number, expiresAt: number) => {
+    const kp = Keypair.generate();
+    const [rec] = deriveDelegatePDA(vaultState, kp.publicKey);
+    await program.methods
+      .addDelegate(new BN(allowance), new BN(expiresAt), new BN(0), 0)
+      .accounts({
+        authority: payer.publicKey, mint, vaultState,
+        delegate: kp.publicKey, delegateRecord: rec,
+        role: null,
+      } as any)
+      .rpc();
+    return { kp, rec };
+  };
+
+  const prune = (delegateRecord: PublicKey) =>
+    program.methods
+      .pruneDelegate()
+      .accounts({ cranker: cranker.publicKey, vaultState, owner: payer.publicKey, delegateRecord, asset: null } as any)
+      .signers([cranker])
+      .rpc();
+
+  const isClosed = async (rec: PublicKey) => (await conn.getAccountInfo(rec)) === null;
+
+  before(async () => {
+    mint = await createTestMint(conn, payer);
+    const ownerAta = await fundAta(conn, payer, mint, payer.publicKey, 100_000);
+
+    [vaultState] = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta     = await getAssociatedTokenAddress(mint, vaultState, true);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await program.methods
+      .deposit(new BN(100_000), null)
+      .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
+      .rpc();
+  });
+
+  it("refuses to prune a live delegate", async () => {
+    const { rec } = await addDelegate(1_000, now() + 3600);
+    try {
+      await prune(rec);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("DelegateStillActive");
+    }
+  });
+
+  it("anyone prunes an exhausted delegate; rent goes to the owner", async () => {
+    const { kp, rec } = await addDelegate(1_000, now() + 3600);
+    const delegateAta = await fundAta(conn, payer, mint, kp.publicKey, 0);
+    await program.methods
+      .delegateWithdraw(new BN(1_000))
+      .accounts({
+        delegateSigner: kp.publicKey, mint, vaultState, vaultAta,
+        delegateAta, delegateRecord: rec, tokenProgram,
+        policy: null,
+        cosigner: null,
+      } as any)
+      .signers([kp])
+      .rpc();
+
+    const rent = (await conn.getAccountInfo(rec))!.lamports;
+    const before = await conn.getBalance(payer.publicKey);
+    await prune(rec);
+    const after = await conn.getBalance(payer.publicKey);
+
+    expect(await isClosed(rec)).to.be.true;
+    // The owner also pays the transaction fee here, so allow for it
+    expect(after - before).to.be.greaterThan(rent - 10_000);
+  });
+
+  it("owner prunes many expired delegates in one transaction", async () => {
+    const a = await addDelegate(1_000, now() + 2);
+    const b = await addDelegate(1_000, now() + 2);
+    await sleep(4_000);
+
+    await program.methods
+      .pruneDelegates()
+      .accounts({ authority: payer.publicKey, owner: payer.publicKey, vaultState, role: null } as any)
+      .remainingAccounts([a.rec, b.rec].map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+      .rpc();
+
+    expect(await isClosed(a.rec)).to.be.true;
+    expect(await isClosed(b.rec)).to.be.true;
+  });
+
+  it("a delegate renounces its own record", async () => {
+    const { kp, rec } = await addDelegate(1_000, now() + 3600);
+    await program.methods
+      .renounceDelegate()
+      .accounts({ delegateSigner: kp.publicKey, vaultState, owner: payer.publicKey, delegateRecord: rec, asset: null } as any)
+      .signers([kp])
+      .rpc();
+
+    expect(await isClosed(rec)).to.be.true;
+  });
+});
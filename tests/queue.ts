@@ -0,0 +1,197 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveWithdrawTiersPDA,
+  derivePendingWithdrawalPDA,
+  deriveStreamPDA,
+  deriveDelegatePDA,
+  getTokenBalance,
+} from "./helpers";
+
+const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
+
+describe("vault queued withdrawals", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const guardian = Keypair.generate();
+  const cranker  = Keypair.generate();
+  const delegate = Keypair.generate();
+
+  let mint:          PublicKey;
+  let ownerAta:      PublicKey;
+  let vaultState:    PublicKey;
+  let vaultAta:      PublicKey;
+  let withdrawTiers: PublicKey;
+
+  const tier = (minAmount: number, delay: number) => ({ minAmount: new BN(minAmount), delay: new BN(delay) });
+
+  const setTiers = (tiers: object[]) =>
+    program.methods
+      .setWithdrawTiers(tiers as any)
+      .accounts({ owner: payer.publicKey, vaultState, withdrawTiers } as any)
+      .rpc();
+
+  const request = (id: number, amount: number) =>
+    program.methods
+      .requestWithdraw(new BN(id), new BN(amount))
+      .accounts({ authority: payer.publicKey, vaultState, role: null, withdrawTiers, destination: ownerAta, pendingWithdrawal: derivePendingWithdrawalPDA(vaultState, id)[0], cosigner: null } as any)
+      .rpc();
+
+  const execute = (id: number) =>
+    program.methods
+      .executeWithdraw()
+      .accounts({ executor: cranker.publicKey, mint, vaultState, vaultAta, pendingWithdrawal: derivePendingWithdrawalPDA(vaultState, id)[0], requester: payer.publicKey, destination: ownerAta, policy: null, tokenProgram } as any)
+      .signers([cranker])
+      .rpc();
+
+  const expectRejected = async (p: Promise<unknown>, error: string) => {
+    try {
+      await p;
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include(error);
+    }
+  };
+
+  before(async () => {
+    mint     = await createTestMint(conn, payer);
+    ownerAta = await fundAta(conn, payer, mint, payer.publicKey, 100_000);
+
+    [vaultState]    = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta        = await getAssociatedTokenAddress(mint, vaultState, true);
+    [withdrawTiers] = deriveWithdrawTiersPDA(vaultState);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await program.methods
+      .deposit(new BN(50_000), null)
+      .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
+      .rpc();
+    await program.methods
+      .setGuardian(guardian.publicKey)
+      .accounts({ owner: payer.publicKey, vaultState } as any)
+      .rpc();
+    await program.methods
+      .addDelegate(new BN(1_000), new BN(Math.floor(Date.now() / 1000) + 3600), new BN(0), 0)
+      .accounts({ authority: payer.publicKey, mint, vaultState, delegate: delegate.publicKey, delegateRecord: deriveDelegatePDA(vaultState, delegate.publicKey)[0], role: null } as any)
+      .rpc();
+  });
+
+  it("rejects unordered tiers", async () => {
+    await expectRejected(setTiers([tier(10_000, 2), tier(1_000, 1)]), "InvalidWithdrawTiers");
+    await expectRejected(setTiers([tier(1_000, 5), tier(10_000, 2)]), "InvalidWithdrawTiers");
+  });
+
+  it("enabling tiers applies at once and blocks direct withdraw", async () => {
+    await setTiers([tier(0, 2), tier(10_000, 3_600)]);
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.withdrawTierCount).to.equal(2);
+
+    await expectRejected(
+      program.methods
+        .withdraw(new BN(1_000))
+        .accounts({ authority: payer.publicKey, mint, vaultState, role: null, vaultAta, destination: ownerAta, tokenProgram, policy: null, cosigner: null } as any)
+        .rpc(),
+      "WithdrawQueueRequired",
+    );
+  });
+
+  it("streams cannot be opened to route around the queue", async () => {
+    const start = Math.floor(Date.now() / 1000) + 5;
+    await expectRejected(
+      program.methods
+        .createStream(new BN(0), new BN(1_000), new BN(start), new BN(start), new BN(start + 1))
+        .accounts({ authority: payer.publicKey, vaultState, vaultAta, recipient: cranker.publicKey, stream: deriveStreamPDA(vaultState, 0)[0], role: null } as any)
+        .rpc(),
+      "WithdrawQueueRequired",
+    );
+  });
+
+  it("delegates cannot be added or widened to route around the queue", async () => {
+    const other = Keypair.generate().publicKey;
+    await expectRejected(
+      program.methods
+        .addDelegate(new BN(50_000), new BN(Math.floor(Date.now() / 1000) + 3600), new BN(0), 0)
+        .accounts({ authority: payer.publicKey, mint, vaultState, delegate: other, delegateRecord: deriveDelegatePDA(vaultState, other)[0], role: null } as any)
+        .rpc(),
+      "WithdrawQueueRequired",
+    );
+
+    const [delegateRecord] = deriveDelegatePDA(vaultState, delegate.publicKey);
+    const update = (fields: object) =>
+      program.methods
+        .updateDelegate({ allowance: null, expiresAt: null, notBefore: null, paused: null, exemptFromDailyLimit: null, ...fields } as any)
+        .accounts({ authority: payer.publicKey, vaultState, delegateRecord, role: null } as any)
+        .rpc();
+    await expectRejected(update({ allowance: new BN(50_000) }), "WithdrawQueueRequired");
+    await expectRejected(update({ exemptFromDailyLimit: true }), "WithdrawQueueRequired");
+    await update({ allowance: new BN(500) });
+    expect((await program.account.delegateRecord.fetch(delegateRecord)).allowance.toNumber()).to.equal(500);
+  });
+
+  it("executes only after the tier's delay", async () => {
+    await request(1, 2_000);
+    const pending = await program.account.pendingWithdrawal.fetch(derivePendingWithdrawalPDA(vaultState, 1)[0]);
+    expect(pending.executeAfter.toNumber() - pending.requestedAt.toNumber()).to.equal(2);
+
+    await expectRejected(execute(1), "WithdrawCooldownActive");
+    await sleep(3_000);
+
+    const before = await getTokenBalance(conn, ownerAta);
+    await execute(1);
+    expect(Number((await getTokenBalance(conn, ownerAta)) - before)).to.equal(2_000);
+    expect((await program.account.vaultState.fetch(vaultState)).pendingWithdrawalCount).to.equal(0);
+  });
+
+  it("large amounts get the long delay and the guardian can cancel", async () => {
+    await request(2, 20_000);
+    const [pda] = derivePendingWithdrawalPDA(vaultState, 2);
+    const pending = await program.account.pendingWithdrawal.fetch(pda);
+    expect(pending.executeAfter.toNumber() - pending.requestedAt.toNumber()).to.equal(3_600);
+
+    await expectRejected(
+      program.methods
+        .cancelWithdraw()
+        .accounts({ authority: cranker.publicKey, vaultState, pendingWithdrawal: pda, requester: payer.publicKey } as any)
+        .signers([cranker])
+        .rpc(),
+      "Unauthorised",
+    );
+    await program.methods
+      .cancelWithdraw()
+      .accounts({ authority: guardian.publicKey, vaultState, pendingWithdrawal: pda, requester: payer.publicKey } as any)
+      .signers([guardian])
+      .rpc();
+    expect(await conn.getAccountInfo(pda)).to.be.null;
+  });
+
+  it("shortening the tiers is timelocked", async () => {
+    await setTiers([]);
+    const schedule = await program.account.withdrawTiers.fetch(withdrawTiers);
+    expect(schedule.tiers.length).to.equal(2);
+    expect(schedule.tiersEffectiveAt.toNumber()).to.be.greaterThan(0);
+
+    await expectRejected(
+      program.methods
+        .applyWithdrawTiers()
+        .accounts({ owner: payer.publicKey, vaultState, withdrawTiers } as any)
+        .rpc(),
+      "ConfigTimelockActive",
+    );
+    expect((await program.account.vaultState.fetch(vaultState)).withdrawTierCount).to.equal(2);
+  });
+});
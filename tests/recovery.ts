@@ -0,0 +1,272 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveDelegatePDA,
+  deriveRecoveryPDA,
+  deriveRolePDA,
+  derivePendingWithdrawalPDA,
+  deriveVetoKeyPDA,
+  deriveWithdrawTiersPDA,
+  getTokenBalance,
+} from "./helpers";
+
+const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
+
+const ROLE_WITHDRAWER = 1 << 1;
+
+describe("vault social recovery", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const guardians = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+  const newOwner  = Keypair.generate();
+  const delegate  = Keypair.generate();
+  const outsider  = Keypair.generate();
+  const operator  = Keypair.generate();
+
+  let mint:        PublicKey;
+  let ownerAta:    PublicKey;
+  let vaultState:  PublicKey;
+  let vaultAta:    PublicKey;
+  let recovery:    PublicKey;
+  let delegateRec: PublicKey;
+  let operatorRole: PublicKey;
+  let pending:     PublicKey;
+
+  const initiate = (signer: Keypair, target: PublicKey = newOwner.publicKey) =>
+    program.methods
+      .initiateRecovery(target)
+      .accounts({ guardian: signer.publicKey, vaultState, recovery } as any)
+      .signers([signer])
+      .rpc();
+
+  const approve = (signer: Keypair) =>
+    program.methods
+      .approveRecovery()
+      .accounts({ guardian: signer.publicKey, vaultState, recovery } as any)
+      .signers([signer])
+      .rpc();
+
+  const complete = (withdrawTiers: PublicKey | null = null) =>
+    program.methods
+      .completeRecovery()
+      .accounts({ executor: outsider.publicKey, vaultState, recovery, withdrawTiers } as any)
+      .signers([outsider])
+      .rpc();
+
+  const withdraw = (authority: Keypair, amount: number) =>
+    program.methods
+      .withdraw(new BN(amount))
+      .accounts({ authority: authority.publicKey, mint, vaultState, role: null, vaultAta, destination: ownerAta, tokenProgram, policy: null, cosigner: null } as any)
+      .signers([authority])
+      .rpc();
+
+  const expectRejected = async (p: Promise<unknown>, error: string) => {
+    try {
+      await p;
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include(error);
+    }
+  };
+
+  before(async () => {
+    mint     = await createTestMint(conn, payer);
+    ownerAta = await fundAta(conn, payer, mint, payer.publicKey, 100_000);
+
+    [vaultState]  = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta      = await getAssociatedTokenAddress(mint, vaultState, true);
+    [recovery]    = deriveRecoveryPDA(vaultState);
+    [delegateRec] = deriveDelegatePDA(vaultState, delegate.publicKey);
+    [operatorRole] = deriveRolePDA(vaultState, operator.publicKey);
+    [pending]     = derivePendingWithdrawalPDA(vaultState, 1);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await program.methods
+      .deposit(new BN(50_000), null)
+      .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
+      .rpc();
+    await program.methods
+      .addDelegate(new BN(1_000), new BN(Math.floor(Date.now() / 1000) + 3600), new BN(0), 0)
+      .accounts({ authority: payer.publicKey, mint, vaultState, delegate: delegate.publicKey, delegateRecord: delegateRec, role: null } as any)
+      .rpc();
+    await program.methods
+      .setRecovery(guardians.map((g) => g.publicKey), 2, new BN(2))
+      .accounts({ owner: payer.publicKey, vaultState, recovery } as any)
+      .rpc();
+
+    // Left behind by the old key: a role and a large withdrawal in flight
+    await program.methods
+      .grantRole(ROLE_WITHDRAWER)
+      .accounts({ owner: payer.publicKey, vaultState, member: operator.publicKey, role: operatorRole } as any)
+      .rpc();
+    await program.methods
+      .setVeto(new BN(10_000), new BN(1))
+      .accounts({ owner: payer.publicKey, vaultState } as any)
+      .rpc();
+    await program.methods
+      .requestWithdraw(new BN(1), new BN(20_000))
+      .accounts({ authority: payer.publicKey, vaultState, role: null, withdrawTiers: null, destination: ownerAta, cosigner: null, pendingWithdrawal: pending } as any)
+      .rpc();
+  });
+
+  it("only guardians can start a recovery", async () => {
+    await expectRejected(initiate(outsider), "NotRecoveryGuardian");
+  });
+
+  it("the owner cancels a recovery it did not ask for", async () => {
+    const cancelled: PublicKey[] = [];
+    const listener = program.addEventListener("recoveryCancelled", (e: any) => cancelled.push(e.newOwner));
+
+    await initiate(guardians[0]);
+    await program.methods
+      .cancelRecovery()
+      .accounts({ owner: payer.publicKey, vaultState, recovery } as any)
+      .rpc();
+    await sleep(1_000);
+    await program.removeEventListener(listener);
+
+    expect(cancelled.map((k) => k.toBase58())).to.deep.equal([newOwner.publicKey.toBase58()]);
+    const state = await program.account.recoveryConfig.fetch(recovery);
+    expect(state.newOwner.equals(PublicKey.default)).to.be.true;
+    expect(state.approvals).to.equal(0);
+  });
+
+  it("completes only after the threshold and the delay", async () => {
+    await initiate(guardians[0]);
+    await expectRejected(approve(guardians[0]), "AlreadyApproved");
+    await expectRejected(complete(), "RecoveryNotReady");
+
+    await approve(guardians[1]);
+    await expectRejected(complete(), "RecoveryNotReady");
+
+    await sleep(3_000);
+    await complete();
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.owner.equals(newOwner.publicKey)).to.be.true;
+    expect(state.creator.equals(payer.publicKey)).to.be.true;
+    const config = await program.account.recoveryConfig.fetch(recovery);
+    expect(config.newOwner.equals(PublicKey.default)).to.be.true;
+  });
+
+  it("the vault, its funds and its delegates answer to the new owner", async () => {
+    await expectRejected(withdraw(payer, 1_000), "Unauthorised");
+
+    const before = await getTokenBalance(conn, ownerAta);
+    await withdraw(newOwner, 1_000);
+    expect(Number((await getTokenBalance(conn, ownerAta)) - before)).to.equal(1_000);
+
+    await program.methods
+      .removeDelegate()
+      .accounts({ authority: newOwner.publicKey, owner: newOwner.publicKey, mint, vaultState, delegate: delegate.publicKey, delegateRecord: delegateRec, asset: null, role: null } as any)
+      .signers([newOwner])
+      .rpc();
+    expect(await conn.getAccountInfo(delegateRec)).to.be.null;
+    expect(await conn.getBalance(newOwner.publicKey)).to.be.greaterThan(0);
+  });
+
+  it("withdrawals requested by the old key can only be cancelled", async () => {
+    await expectRejected(
+      program.methods
+        .executeWithdraw()
+        .accounts({ executor: outsider.publicKey, mint, vaultState, vaultAta, pendingWithdrawal: pending, requester: payer.publicKey, destination: ownerAta, policy: null, tokenProgram } as any)
+        .signers([outsider])
+        .rpc(),
+      "PendingWithdrawalStale",
+    );
+
+    await program.methods
+      .cancelWithdraw()
+      .accounts({ authority: newOwner.publicKey, vaultState, pendingWithdrawal: pending, requester: payer.publicKey } as any)
+      .signers([newOwner])
+      .rpc();
+    expect(await conn.getAccountInfo(pending)).to.be.null;
+    expect((await program.account.vaultState.fetch(vaultState)).pendingWithdrawalCount).to.equal(0);
+  });
+
+  it("roles survive recovery until the new owner revokes them", async () => {
+    const role = await program.account.role.fetch(operatorRole);
+    expect(role.permissions).to.equal(ROLE_WITHDRAWER);
+
+    await program.methods
+      .revokeRole()
+      .accounts({ owner: newOwner.publicKey, vaultState, role: operatorRole } as any)
+      .signers([newOwner])
+      .rpc();
+    expect(await conn.getAccountInfo(operatorRole)).to.be.null;
+  });
+
+  it("a recovery drops the beneficiary, queued tiers and veto-key removals the old key set up", async () => {
+    const heir     = Keypair.generate();
+    const security = Keypair.generate();
+    const [withdrawTiers] = deriveWithdrawTiersPDA(vaultState);
+    const [vetoKey]       = deriveVetoKeyPDA(vaultState, security.publicKey);
+    const tier = (minAmount: number, delay: number) => ({ minAmount: new BN(minAmount), delay: new BN(delay) });
+    const setTiers = (tiers: object[]) =>
+      program.methods
+        .setWithdrawTiers(tiers as any)
+        .accounts({ owner: newOwner.publicKey, vaultState, withdrawTiers } as any)
+        .signers([newOwner])
+        .rpc();
+
+    await program.methods
+      .setBeneficiary(heir.publicKey, new BN(60), new BN(500))
+      .accounts({ owner: newOwner.publicKey, vaultState } as any)
+      .signers([newOwner])
+      .rpc();
+    await setTiers([tier(0, 60)]);
+    await setTiers([tier(0, 30)]);
+    expect((await program.account.withdrawTiers.fetch(withdrawTiers)).pendingTiers).to.have.length(1);
+    await program.methods
+      .addVetoKey()
+      .accounts({ owner: newOwner.publicKey, vaultState, key: security.publicKey, vetoKey } as any)
+      .signers([newOwner])
+      .rpc();
+    await program.methods
+      .requestVetoKeyRemoval()
+      .accounts({ owner: newOwner.publicKey, vaultState, vetoKey } as any)
+      .signers([newOwner])
+      .rpc();
+
+    // newOwner's key is now the lost one; the guardians hand the vault back
+    await initiate(guardians[0], payer.publicKey);
+    await approve(guardians[1]);
+    await sleep(3_000);
+    await expectRejected(complete(), "WithdrawTiersAccountMismatch");
+    await complete(withdrawTiers);
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.owner.equals(payer.publicKey)).to.be.true;
+    expect(state.beneficiary.equals(PublicKey.default)).to.be.true;
+    expect(state.inactivityPeriod.toNumber()).to.equal(0);
+    expect(state.claimLimit.toNumber()).to.equal(0);
+
+    const schedule = await program.account.withdrawTiers.fetch(withdrawTiers);
+    expect(schedule.tiers).to.have.length(1);
+    expect(schedule.pendingTiers).to.have.length(0);
+    expect(schedule.tiersEffectiveAt.toNumber()).to.equal(0);
+
+    await expectRejected(
+      program.methods
+        .removeVetoKey()
+        .accounts({ owner: payer.publicKey, vaultState, vetoKey } as any)
+        .rpc(),
+      "NoPendingConfig",
+    );
+  });
+});
@@ -0,0 +1,126 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveDelegatePDA,
+  getTokenBalance,
+} from "./helpers";
+
+const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
+
+describe("vault recurring delegate allowance", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const merchant = Keypair.generate();
+  const PER_PERIOD = 1_000;
+  const PERIOD     = 2; // seconds
+
+  let mint:        PublicKey;
+  let vaultState:  PublicKey;
+  let vaultAta:    PublicKey;
+  let merchantAta: PublicKey;
+  let merchantRec: PublicKey;
+
+  const pull = (amount: number) =>
+    program.methods
+      .delegateWithdraw(new BN(amount))
+      .accounts({
+        delegateSigner: merchant.publicKey,
+        mint,
+        vaultState,
+        vaultAta,
+        delegateAta: merchantAta,
+        delegateRecord: merchantRec,
+        tokenProgram,
+        policy: null,
+        cosigner: null,
+      } as any)
+      .signers([merchant])
+      .rpc();
+
+  before(async () => {
+    mint = await createTestMint(conn, payer);
+    const ownerAta = await fundAta(conn, payer, mint, payer.publicKey, 100_000);
+    merchantAta    = await fundAta(conn, payer, mint, merchant.publicKey, 0);
+
+    [vaultState]  = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta      = await getAssociatedTokenAddress(mint, vaultState, true);
+    [merchantRec] = deriveDelegatePDA(vaultState, merchant.publicKey);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await program.methods
+      .deposit(new BN(100_000), null)
+      .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
+      .rpc();
+  });
+
+  it("rejects max_periods without a period", async () => {
+    const other = Keypair.generate();
+    const [otherRec] = deriveDelegatePDA(vaultState, other.publicKey);
+    try {
+      await program.methods
+        .addDelegate(new BN(PER_PERIOD), new BN(Math.floor(Date.now() / 1000) + 3600), new BN(0), 3)
+        .accounts({
+          authority: payer.publicKey, mint, vaultState,
+          delegate: other.publicKey, delegateRecord: otherRec,
+          role: null,
+        } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("InvalidDelegatePeriod");
+    }
+  });
+
+  it("refills the allowance each period, up to max_periods", async () => {
+    await program.methods
+      .addDelegate(new BN(PER_PERIOD), new BN(Math.floor(Date.now() / 1000) + 3600), new BN(PERIOD), 2)
+      .accounts({
+        authority: payer.publicKey, mint, vaultState,
+        delegate: merchant.publicKey, delegateRecord: merchantRec,
+        role: null,
+      } as any)
+      .rpc();
+
+    // Period 0
+    await pull(PER_PERIOD);
+    try {
+      await pull(1);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("AllowanceExceeded");
+    }
+
+    // Period 1 — `used` resets without any owner action
+    await sleep((PERIOD + 1) * 1000);
+    await pull(PER_PERIOD);
+    expect(Number(await getTokenBalance(conn, merchantAta))).to.equal(2 * PER_PERIOD);
+
+    const rec = await program.account.delegateRecord.fetch(merchantRec);
+    expect(rec.periodIndex).to.equal(1);
+    expect(rec.used.toNumber()).to.equal(PER_PERIOD);
+
+    // Period 2 is beyond max_periods
+    await sleep((PERIOD + 1) * 1000);
+    try {
+      await pull(1);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("DelegatePeriodsExhausted");
+    }
+  });
+});
@@ -0,0 +1,190 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveRolePDA,
+  getTokenBalance,
+  MOCK_STRATEGY_ID,
+} from "./helpers";
+
+const ROLE_DEPOSITOR    = 1 << 0;
+const ROLE_WITHDRAWER   = 1 << 1;
+const ROLE_CONFIG_ADMIN = 1 << 3;
+const ROLE_PAUSER       = 1 << 4;
+const PAUSE_DEPOSITS    = 1;
+
+describe("vault roles", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const ops    = Keypair.generate();
+  const pauser = Keypair.generate();
+
+  let mint:       PublicKey;
+  let ownerAta:   PublicKey;
+  let opsAta:     PublicKey;
+  let vaultState: PublicKey;
+  let vaultAta:   PublicKey;
+  let opsRole:    PublicKey;
+  let pauserRole: PublicKey;
+
+  const grant = (member: PublicKey, role: PublicKey, permissions: number) =>
+    program.methods
+      .grantRole(permissions)
+      .accounts({ owner: payer.publicKey, vaultState, member, role } as any)
+      .rpc();
+
+  const opsWithdraw = (amount: number, role: PublicKey | null) =>
+    program.methods
+      .withdraw(new BN(amount))
+      .accounts({ authority: ops.publicKey, mint, vaultState, role, vaultAta, destination: opsAta, tokenProgram, policy: null, cosigner: null } as any)
+      .signers([ops])
+      .rpc();
+
+  before(async () => {
+    const sig = await conn.requestAirdrop(ops.publicKey, LAMPORTS_PER_SOL);
+    await conn.confirmTransaction(sig);
+
+    mint     = await createTestMint(conn, payer);
+    ownerAta = await fundAta(conn, payer, mint, payer.publicKey, 100_000);
+    opsAta   = await fundAta(conn, payer, mint, ops.publicKey, 10_000);
+
+    [vaultState] = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta     = await getAssociatedTokenAddress(mint, vaultState, true);
+    [opsRole]    = deriveRolePDA(vaultState, ops.publicKey);
+    [pauserRole] = deriveRolePDA(vaultState, pauser.publicKey);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await program.methods
+      .deposit(new BN(20_000), null)
+      .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
+      .rpc();
+  });
+
+  it("rejects an empty or unknown permission set", async () => {
+    for (const bad of [0, 1 << 7]) {
+      try {
+        await grant(ops.publicKey, opsRole, bad);
+        expect.fail("should have thrown");
+      } catch (e: any) {
+        expect(e.message).to.include("InvalidPermissions");
+      }
+    }
+  });
+
+  it("a withdrawer withdraws but cannot change config", async () => {
+    await grant(ops.publicKey, opsRole, ROLE_WITHDRAWER | ROLE_DEPOSITOR);
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.roleCount).to.equal(1);
+
+    await opsWithdraw(5_000, opsRole);
+    expect(Number(await getTokenBalance(conn, opsAta))).to.equal(15_000);
+
+    try {
+      await program.methods
+        .updateConfig(new BN(0), new BN(1))
+        .accounts({ authority: ops.publicKey, vaultState, role: opsRole } as any)
+        .signers([ops])
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("Unauthorised");
+    }
+  });
+
+  it("a depositor role deposits in owner-only mode", async () => {
+    await program.methods
+      .deposit(new BN(1_000), null)
+      .accounts({ depositor: ops.publicKey, mint, vaultState, depositorAta: opsAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: opsRole } as any)
+      .signers([ops])
+      .rpc();
+    expect(Number(await getTokenBalance(conn, vaultAta))).to.equal(16_000);
+  });
+
+  it("a pauser can pause", async () => {
+    await grant(pauser.publicKey, pauserRole, ROLE_PAUSER);
+    await program.methods
+      .pause(PAUSE_DEPOSITS)
+      .accounts({ authority: pauser.publicKey, vaultState, role: pauserRole } as any)
+      .signers([pauser])
+      .rpc();
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.paused).to.equal(PAUSE_DEPOSITS);
+
+    await program.methods
+      .unpause(PAUSE_DEPOSITS)
+      .accounts({ authority: payer.publicKey, vaultState } as any)
+      .rpc();
+  });
+
+  it("regranting replaces the permission set", async () => {
+    await grant(ops.publicKey, opsRole, ROLE_CONFIG_ADMIN);
+    const role = await program.account.role.fetch(opsRole);
+    expect(role.permissions).to.equal(ROLE_CONFIG_ADMIN);
+    expect((await program.account.vaultState.fetch(vaultState)).roleCount).to.equal(2);
+
+    try {
+      await opsWithdraw(1_000, opsRole);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("Unauthorised");
+    }
+  });
+
+  it("a config admin cannot point the vault at a strategy", async () => {
+    try {
+      await program.methods
+        .setStrategy(MOCK_STRATEGY_ID, PublicKey.default)
+        .accounts({ owner: ops.publicKey, vaultState, vaultAta } as any)
+        .signers([ops])
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("Unauthorised");
+    }
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.strategy.toBase58()).to.equal(PublicKey.default.toBase58());
+    expect(state.pendingStrategy.toBase58()).to.equal(PublicKey.default.toBase58());
+  });
+
+  it("close is refused while roles are outstanding; revoke clears them", async () => {
+    try {
+      await program.methods
+        .closeVault()
+        .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("RolesOutstanding");
+    }
+
+    for (const role of [opsRole, pauserRole]) {
+      await program.methods
+        .revokeRole()
+        .accounts({ owner: payer.publicKey, vaultState, role } as any)
+        .rpc();
+      expect(await conn.getAccountInfo(role)).to.be.null;
+    }
+    expect((await program.account.vaultState.fetch(vaultState)).roleCount).to.equal(0);
+
+    try {
+      await opsWithdraw(1_000, null);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("Unauthorised");
+    }
+  });
+});
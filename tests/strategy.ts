@@ -0,0 +1,239 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { MockStrategy } from "../target/types/mock_strategy";
+import { getAssociatedTokenAddress, mintTo, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveStrategyStatePDA,
+  deriveStrategyAuthorityPDA,
+  getTokenBalance,
+} from "./helpers";
+
+describe("vault yield strategy", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program  = anchor.workspace.Vault as Program<Vault>;
+  const strategy = anchor.workspace.MockStrategy as Program<MockStrategy>;
+  const conn     = provider.connection;
+  const payer    = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  let mint:          PublicKey;
+  let ownerAta:      PublicKey;
+  let vaultState:    PublicKey;
+  let vaultAta:      PublicKey;
+  let strategyState: PublicKey;
+  let strategyAta:   PublicKey;
+  let strategyAuthority: PublicKey;
+
+  const strategyAccounts = () => ({
+    mint,
+    vaultState,
+    vaultAta,
+    strategyProgram: strategy.programId,
+    strategyState,
+    strategyTokenAccount: strategyAta,
+    strategyAuthority,
+    tokenProgram,
+  });
+
+  const allocate = (amount: number) =>
+    program.methods
+      .allocate(new BN(amount))
+      .accounts({ authority: payer.publicKey, ...strategyAccounts(), role: null, policy: null, cosigner: null } as any)
+      .rpc();
+
+  const deallocate = (amount: number) =>
+    program.methods
+      .deallocate(new BN(amount))
+      .accounts({ authority: payer.publicKey, ...strategyAccounts(), role: null, policy: null, cosigner: null } as any)
+      .rpc();
+
+  const setStrategy = (key: PublicKey, state: PublicKey = strategyState) =>
+    program.methods
+      .setStrategy(key, state)
+      .accounts({ owner: payer.publicKey, vaultState, vaultAta } as any)
+      .rpc();
+
+  const withdraw = (amount: number) =>
+    program.methods
+      .withdraw(new BN(amount))
+      .accounts({ authority: payer.publicKey, mint, vaultState, vaultAta, destination: ownerAta, tokenProgram, role: null, policy: null, cosigner: null } as any)
+      .rpc();
+
+  before(async () => {
+    mint     = await createTestMint(conn, payer);
+    ownerAta = await fundAta(conn, payer, mint, payer.publicKey, 100_000);
+
+    [vaultState]    = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta        = await getAssociatedTokenAddress(mint, vaultState, true);
+    [strategyState] = deriveStrategyStatePDA(vaultState);
+    strategyAta     = await getAssociatedTokenAddress(mint, strategyState, true);
+    [strategyAuthority] = deriveStrategyAuthorityPDA(vaultState);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await strategy.methods
+      .initialize()
+      .accounts({ payer: payer.publicKey, vault: vaultState, mint, strategyState, strategyAta, tokenProgram } as any)
+      .rpc();
+  });
+
+  it("cannot allocate before a strategy is set", async () => {
+    try {
+      await allocate(1_000);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("NoStrategy");
+    }
+  });
+
+  it("a strategy set on an empty vault applies at once", async () => {
+    await setStrategy(strategy.programId);
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.strategy.toBase58()).to.equal(strategy.programId.toBase58());
+    expect(state.strategyEffectiveAt.toNumber()).to.equal(0);
+  });
+
+  it("allocates idle funds to the strategy", async () => {
+    await program.methods
+      .deposit(new BN(10_000), null)
+      .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
+      .rpc();
+    await allocate(6_000);
+
+    expect(Number(await getTokenBalance(conn, vaultAta))).to.equal(4_000);
+    expect(Number(await getTokenBalance(conn, strategyAta))).to.equal(6_000);
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.allocated.toNumber()).to.equal(6_000);
+    expect(state.strategyValue.toNumber()).to.equal(6_000);
+    expect(state.locked).to.equal(false);
+  });
+
+  it("nobody but the vault can pull funds out of the strategy", async () => {
+    try {
+      await strategy.methods
+        .withdraw(new BN(6_000))
+        .accounts({ vault: vaultState, strategyState, strategyAta, vaultAta, mint, tokenProgram, authority: payer.publicKey } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("ConstraintSeeds");
+    }
+    expect(Number(await getTokenBalance(conn, strategyAta))).to.equal(6_000);
+  });
+
+  it("only the pinned strategy state is accepted", async () => {
+    const [otherState] = deriveStrategyStatePDA(Keypair.generate().publicKey);
+    try {
+      await program.methods
+        .reportStrategy()
+        .accounts({ cranker: payer.publicKey, ...strategyAccounts(), strategyState: otherState } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("StrategyMismatch");
+    }
+  });
+
+  it("withdrawals beyond the idle balance report insufficient liquidity", async () => {
+    try {
+      await withdraw(5_000);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("InsufficientLiquidity");
+    }
+    try {
+      await withdraw(20_000);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("InsufficientFunds");
+    }
+  });
+
+  it("report picks up simulated yield", async () => {
+    await mintTo(conn, payer, mint, strategyAta, payer, 500);
+    await program.methods
+      .reportStrategy()
+      .accounts({ cranker: payer.publicKey, ...strategyAccounts() } as any)
+      .rpc();
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.strategyValue.toNumber()).to.equal(6_500);
+    expect(state.allocated.toNumber()).to.equal(6_000);
+  });
+
+  it("the strategy cannot be swapped while funds are out", async () => {
+    try {
+      await setStrategy(PublicKey.default);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("StrategyFundsOutstanding");
+    }
+  });
+
+  it("close is refused until everything is deallocated", async () => {
+    await withdraw(4_000);
+    try {
+      await program.methods
+        .closeVault()
+        .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("StrategyFundsOutstanding");
+    }
+  });
+
+  it("deallocate brings principal and yield back", async () => {
+    await deallocate(6_500);
+
+    expect(Number(await getTokenBalance(conn, vaultAta))).to.equal(6_500);
+    expect(Number(await getTokenBalance(conn, strategyAta))).to.equal(0);
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.allocated.toNumber()).to.equal(0);
+    expect(state.strategyValue.toNumber()).to.equal(0);
+  });
+
+  it("a new strategy is timelocked once the vault holds funds, clearing is not", async () => {
+    const other = Keypair.generate().publicKey;
+    await setStrategy(other);
+    let state = await program.account.vaultState.fetch(vaultState);
+    expect(state.strategy.toBase58()).to.equal(strategy.programId.toBase58());
+    expect(state.pendingStrategy.toBase58()).to.equal(other.toBase58());
+    expect(state.strategyEffectiveAt.toNumber()).to.be.greaterThan(0);
+
+    try {
+      await program.methods
+        .applyStrategy()
+        .accounts({ owner: payer.publicKey, vaultState, vaultAta } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("ConfigTimelockActive");
+    }
+
+    await setStrategy(PublicKey.default);
+    state = await program.account.vaultState.fetch(vaultState);
+    expect(state.strategy.toBase58()).to.equal(PublicKey.default.toBase58());
+    expect(state.strategyEffectiveAt.toNumber()).to.equal(0);
+  });
+
+  it("the vault closes once everything is back", async () => {
+    await withdraw(6_500);
+    await program.methods
+      .closeVault()
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    expect(await conn.getAccountInfo(vaultState)).to.be.null;
+  });
+});
@@ -0,0 +1,170 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveStreamPDA,
+  getTokenBalance,
+} from "./helpers";
+
+describe("vault payment streams", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const contributor = Keypair.generate();
+  const now = () => Math.floor(Date.now() / 1000);
+  const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
+
+  let mint:           PublicKey;
+  let ownerAta:       PublicKey;
+  let contributorAta: PublicKey;
+  let vaultState:     PublicKey;
+  let vaultAta:       PublicKey;
+
+  const createStream = (id: number, total: number, start: number, cliff: number, end: number) =>
+    program.methods
+      .createStream(new BN(id), new BN(total), new BN(start), new BN(cliff), new BN(end))
+      .accounts({
+        authority: payer.publicKey,
+        vaultState,
+        vaultAta,
+        recipient: contributor.publicKey,
+        stream: deriveStreamPDA(vaultState, id)[0],
+        role: null,
+      } as any)
+      .rpc();
+
+  const claim = (id: number) =>
+    program.methods
+      .claimStream()
+      .accounts({
+        recipient: contributor.publicKey,
+        mint,
+        vaultState,
+        vaultAta,
+        owner: payer.publicKey,
+        stream: deriveStreamPDA(vaultState, id)[0],
+        destination: contributorAta,
+        tokenProgram,
+        policy: null,
+        cosigner: null,
+      } as any)
+      .signers([contributor])
+      .rpc();
+
+  before(async () => {
+    mint           = await createTestMint(conn, payer);
+    ownerAta       = await fundAta(conn, payer, mint, payer.publicKey, 100_000);
+    contributorAta = await fundAta(conn, payer, mint, contributor.publicKey, 0);
+
+    [vaultState] = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta     = await getAssociatedTokenAddress(mint, vaultState, true);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await program.methods
+      .deposit(new BN(20_000), null)
+      .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
+      .rpc();
+  });
+
+  it("rejects an invalid schedule", async () => {
+    try {
+      await createStream(99, 1_000, now() + 100, now() + 50, now() + 200);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("InvalidStreamSchedule");
+    }
+  });
+
+  it("rejects a stream that starts in the past", async () => {
+    try {
+      await createStream(97, 1_000, now() - 1_000, now() - 1_000, now() + 1_000);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("InvalidStreamSchedule");
+    }
+  });
+
+  it("cannot reserve more than the free balance", async () => {
+    try {
+      await createStream(98, 20_001, now() + 5, now() + 5, now() + 100);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("InsufficientFunds");
+    }
+  });
+
+  it("nothing is claimable before the cliff", async () => {
+    await createStream(1, 5_000, now() + 5, now() + 3600, now() + 7200);
+    try {
+      await claim(1);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("NothingToClaim");
+    }
+  });
+
+  it("recipient claims what has accrued; owner cannot spend the reserve", async () => {
+    // Starts shortly and vests over 30 s
+    const start = now() + 2;
+    await createStream(2, 10_000, start, start, start + 30);
+    await sleep(5_000);
+    await claim(2);
+
+    const claimed = Number(await getTokenBalance(conn, contributorAta));
+    expect(claimed).to.be.greaterThan(0);
+    expect(claimed).to.be.lessThan(10_000);
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.streamReserved.toNumber()).to.equal(5_000 + 10_000 - claimed);
+
+    // 20k deposited − claimed; only the unreserved part can leave
+    try {
+      await program.methods
+        .withdraw(new BN(20_000 - claimed - state.streamReserved.toNumber() + 1))
+        .accounts({ authority: payer.publicKey, mint, vaultState, vaultAta, destination: ownerAta, tokenProgram, role: null, policy: null, cosigner: null } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("InsufficientFunds");
+    }
+  });
+
+  it("cancel releases the unvested part and closes a settled stream", async () => {
+    const [stream1] = deriveStreamPDA(vaultState, 1);
+    await program.methods
+      .cancelStream()
+      .accounts({ authority: payer.publicKey, owner: payer.publicKey, vaultState, stream: stream1, role: null } as any)
+      .rpc();
+    // Nothing had vested, so the stream is closed at once
+    expect(await conn.getAccountInfo(stream1)).to.be.null;
+
+    // Let more accrue after the earlier claim
+    await sleep(2_000);
+    const [stream2] = deriveStreamPDA(vaultState, 2);
+    await program.methods
+      .cancelStream()
+      .accounts({ authority: payer.publicKey, owner: payer.publicKey, vaultState, stream: stream2, role: null } as any)
+      .rpc();
+
+    // Accrued-but-unclaimed tokens remain claimable, then the stream closes
+    await claim(2);
+    expect(await conn.getAccountInfo(stream2)).to.be.null;
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.streamReserved.toNumber()).to.equal(0);
+    expect(state.streamCount).to.equal(0);
+  });
+});
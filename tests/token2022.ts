@@ -0,0 +1,144 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import {
+  getAssociatedTokenAddress,
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
+import { PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  createTransferFeeMint,
+  fundAta,
+  deriveVaultPDA,
+  getTokenBalance,
+} from "./helpers";
+
+const FEE_BPS = 100; // 1 %
+const DEPOSIT = 100_000;
+const WITHDRAW = 50_000;
+
+const cases = [
+  { name: "SPL Token", tokenProgram: TOKEN_PROGRAM_ID, feeBps: 0 },
+  { name: "Token-2022 with transfer fee", tokenProgram: TOKEN_2022_PROGRAM_ID, feeBps: FEE_BPS },
+];
+
+describe("vault token interface", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+
+  const net = (amount: number, feeBps: number) =>
+    amount - Math.floor((amount * feeBps) / 10_000);
+
+  for (const c of cases) {
+    describe(c.name, () => {
+      let mint:       PublicKey;
+      let ownerAta:   PublicKey;
+      let vaultState: PublicKey;
+      let vaultAta:   PublicKey;
+
+      before(async () => {
+        mint = c.feeBps > 0
+          ? await createTransferFeeMint(conn, payer, c.feeBps, BigInt(1_000_000))
+          : await createTestMint(conn, payer, c.tokenProgram);
+        ownerAta = await fundAta(conn, payer, mint, payer.publicKey, 1_000_000, c.tokenProgram);
+
+        [vaultState] = deriveVaultPDA(payer.publicKey, mint);
+        vaultAta = await getAssociatedTokenAddress(mint, vaultState, true, c.tokenProgram);
+
+        await program.methods
+          .initialize(new BN(0), new BN(0))
+          .accounts({
+            owner: payer.publicKey,
+            mint,
+            vaultState,
+            vaultAta,
+            tokenProgram: c.tokenProgram,
+          } as any)
+          .rpc();
+      });
+
+      it("records the amount the vault actually received", async () => {
+        await program.methods
+          .deposit(new BN(DEPOSIT), null)
+          .accounts({
+            depositor: payer.publicKey,
+            mint,
+            vaultState,
+            depositorAta: ownerAta,
+            vaultAta,
+            depositorRecord: null,
+            tranche: null,
+            shareMint: null,
+            depositorShares: null,
+            tokenProgram: c.tokenProgram,
+            role: null,
+          } as any)
+          .rpc();
+
+        const received = net(DEPOSIT, c.feeBps);
+        const balance  = await getTokenBalance(conn, vaultAta, c.tokenProgram);
+        const state    = await program.account.vaultState.fetch(vaultState);
+        expect(Number(balance)).to.equal(received);
+        expect(state.totalDeposited.toNumber()).to.equal(received);
+      });
+
+      it("records the amount the owner actually received", async () => {
+        const before = await getTokenBalance(conn, ownerAta, c.tokenProgram);
+
+        await program.methods
+          .withdraw(new BN(WITHDRAW))
+          .accounts({
+            authority: payer.publicKey,
+            mint,
+            vaultState,
+            vaultAta,
+            destination: ownerAta,
+            tokenProgram: c.tokenProgram,
+            role: null,
+            policy: null,
+            cosigner: null,
+          } as any)
+          .rpc();
+
+        const after    = await getTokenBalance(conn, ownerAta, c.tokenProgram);
+        const received = net(WITHDRAW, c.feeBps);
+        const state    = await program.account.vaultState.fetch(vaultState);
+        expect(Number(after - before)).to.equal(received);
+        expect(state.totalWithdrawn.toNumber()).to.equal(received);
+      });
+
+      it("rejects a token program that does not own the mint", async () => {
+        const wrong = c.tokenProgram.equals(TOKEN_PROGRAM_ID)
+          ? TOKEN_2022_PROGRAM_ID
+          : TOKEN_PROGRAM_ID;
+        try {
+          await program.methods
+            .deposit(new BN(1_000), null)
+            .accounts({
+              depositor: payer.publicKey,
+              mint,
+              vaultState,
+              depositorAta: ownerAta,
+              vaultAta,
+              depositorRecord: null,
+              tranche: null,
+              shareMint: null,
+              depositorShares: null,
+              tokenProgram: wrong,
+              role: null,
+            } as any)
+            .rpc();
+          expect.fail("should have thrown");
+        } catch (e: any) {
+          expect(e.message).to.match(/IncorrectProgramId|incorrect program id/i);
+        }
+      });
+    });
+  }
+});
@@ -0,0 +1,150 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveTranchePDA,
+} from "./helpers";
+
+const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
+
+describe("vault lock-up tranches", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const cranker = Keypair.generate();
+  const now = () => Math.floor(Date.now() / 1000);
+
+  let mint:       PublicKey;
+  let ownerAta:   PublicKey;
+  let vaultState: PublicKey;
+  let vaultAta:   PublicKey;
+
+  const deposit = (amount: number, unlockAt: number | null, tranche: PublicKey | null) =>
+    program.methods
+      .deposit(new BN(amount), unlockAt === null ? null : new BN(unlockAt))
+      .accounts({
+        depositor: payer.publicKey, mint, vaultState,
+        depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche,
+        shareMint: null, depositorShares: null, tokenProgram,
+        role: null,
+      } as any)
+      .rpc();
+
+  const withdraw = (amount: number) =>
+    program.methods
+      .withdraw(new BN(amount))
+      .accounts({ authority: payer.publicKey, mint, vaultState, vaultAta, destination: ownerAta, tokenProgram, role: null, policy: null, cosigner: null } as any)
+      .rpc();
+
+  const release = (tranche: PublicKey) =>
+    program.methods
+      .releaseTranche()
+      .accounts({ cranker: cranker.publicKey, vaultState, depositor: payer.publicKey, tranche } as any)
+      .signers([cranker])
+      .rpc();
+
+  before(async () => {
+    mint     = await createTestMint(conn, payer);
+    ownerAta = await fundAta(conn, payer, mint, payer.publicKey, 100_000);
+
+    [vaultState] = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta     = await getAssociatedTokenAddress(mint, vaultState, true);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await deposit(10_000, null, null);
+  });
+
+  it("requires the tranche account exactly when unlock_at is set", async () => {
+    try {
+      await deposit(1_000, now() + 3600, null);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("TrancheAccountMismatch");
+    }
+  });
+
+  it("rejects an unlock time in the past", async () => {
+    try {
+      await deposit(1_000, now() - 10, deriveTranchePDA(vaultState, 0)[0]);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("InvalidUnlockTime");
+    }
+  });
+
+  it("rejects a lock-up longer than MAX_LOCKUP_SECONDS", async () => {
+    try {
+      await deposit(1_000, now() + 366 * 86_400, deriveTranchePDA(vaultState, 0)[0]);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("InvalidUnlockTime");
+    }
+  });
+
+  it("locked deposits cannot be withdrawn", async () => {
+    const [t0] = deriveTranchePDA(vaultState, 0);
+    await deposit(5_000, now() + 3600, t0);
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.lockedAmount.toNumber()).to.equal(5_000);
+    expect(state.trancheCount).to.equal(1);
+
+    // 15k in the vault, 5k locked
+    try {
+      await withdraw(10_001);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("InsufficientFunds");
+    }
+    await withdraw(10_000);
+  });
+
+  it("refuses to release a tranche before it matures", async () => {
+    try {
+      await release(deriveTranchePDA(vaultState, 0)[0]);
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include("TrancheNotMatured");
+    }
+  });
+
+  it("a matured tranche is released and its tokens unlocked", async () => {
+    const [t1] = deriveTranchePDA(vaultState, 1);
+    await deposit(2_000, now() + 2, t1);
+    await sleep(4_000);
+
+    await release(t1);
+    expect(await conn.getAccountInfo(t1)).to.be.null;
+
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.lockedAmount.toNumber()).to.equal(5_000);
+    expect(state.trancheCount).to.equal(1);
+    await withdraw(2_000);
+  });
+
+  it("cannot close the vault while a tranche is outstanding", async () => {
+    try {
+      await program.methods
+        .closeVault()
+        .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+        .rpc();
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      // The locked 5k also keeps the ATA non-empty
+      expect(e.message).to.match(/VaultNotEmpty|TranchesOutstanding/);
+    }
+  });
+});
@@ -3,6 +3,7 @@ import { Program, BN }  from "@anchor-lang/core";
 import { Vault }        from "../target/types/vault";
 import {
   getAssociatedTokenAddress,
+  TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import { Keypair } from "@solana/web3.js";
 import { expect } from "chai";
@@ -20,6 +21,7 @@ describe("vault", () => {
   const program  = anchor.workspace.Vault as Program<Vault>;
   const conn     = provider.connection;
   const payer    = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
 
   let mint:       anchor.web3.PublicKey;
   let ownerAta:   anchor.web3.PublicKey;
@@ -48,6 +50,7 @@ describe("vault", () => {
           mint,
           vaultState,
           vaultAta,
+          tokenProgram,
         } as any)
         .rpc();
 
@@ -62,7 +65,7 @@ describe("vault", () => {
       try {
         await program.methods
           .initialize(new BN(MAX_DEPOSIT), new BN(DAILY_LIMIT))
-          .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta } as any)
+          .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
           .rpc();
         expect.fail("should have thrown");
       } catch (e: any) {
@@ -77,8 +80,8 @@ describe("vault", () => {
       const before = await getTokenBalance(conn, vaultAta);
 
       await program.methods
-        .deposit(new BN(500_000))
-        .accounts({ owner: payer.publicKey, mint, vaultState, ownerAta, vaultAta } as any)
+        .deposit(new BN(500_000), null)
+        .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
         .rpc();
 
       const after = await getTokenBalance(conn, vaultAta);
@@ -88,8 +91,8 @@ describe("vault", () => {
     it("rejects zero deposit", async () => {
       try {
         await program.methods
-          .deposit(new BN(0))
-          .accounts({ owner: payer.publicKey, mint, vaultState, ownerAta, vaultAta } as any)
+          .deposit(new BN(0), null)
+          .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
           .rpc();
         expect.fail("should have thrown");
       } catch (e: any) {
@@ -100,8 +103,8 @@ describe("vault", () => {
     it("rejects deposit exceeding max_deposit", async () => {
       try {
         await program.methods
-          .deposit(new BN(MAX_DEPOSIT + 1))
-          .accounts({ owner: payer.publicKey, mint, vaultState, ownerAta, vaultAta } as any)
+          .deposit(new BN(MAX_DEPOSIT + 1), null)
+          .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
           .rpc();
         expect.fail("should have thrown");
       } catch (e: any) {
@@ -109,25 +112,30 @@ describe("vault", () => {
       }
     });
 
-    it("rejects signer that is not the owner", async () => {
+    it("rejects a third-party depositor in owner-only mode", async () => {
       const attacker    = Keypair.generate();
       const attackerAta = await fundAta(conn, payer, mint, attacker.publicKey, 100_000);
       try {
         await program.methods
-          .deposit(new BN(100_000))
+          .deposit(new BN(100_000), null)
           .accounts({
-            owner:    attacker.publicKey,
+            depositor:    attacker.publicKey,
             mint,
             vaultState, // still the legitimate vault
-            ownerAta: attackerAta,
+            depositorAta: attackerAta,
             vaultAta,
+            depositorRecord: null,
+            tranche: null,
+            shareMint: null,
+            depositorShares: null,
+            tokenProgram,
+            role: null,
           } as any)
           .signers([attacker])
           .rpc();
         expect.fail("should have thrown");
       } catch (e: any) {
-        // Seeds mismatch OR has_one violation
-        expect(e.message).to.match(/Unauthorised|seeds constraint/i);
+        expect(e.message).to.include("DepositorNotAllowed");
       }
     });
   });
@@ -139,18 +147,37 @@ describe("vault", () => {
 
       await program.methods
         .withdraw(new BN(200_000))
-        .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, ownerAta } as any)
+        .accounts({ authority: payer.publicKey, mint, vaultState, vaultAta, destination: ownerAta, tokenProgram, role: null, policy: null, cosigner: null } as any)
         .rpc();
 
       const after = await getTokenBalance(conn, ownerAta);
       expect(Number(after - before)).to.equal(200_000);
     });
 
+    it("pays a third-party token account directly", async () => {
+      const vendor    = Keypair.generate();
+      const vendorAta = await fundAta(conn, payer, mint, vendor.publicKey, 0);
+
+      let recipient: anchor.web3.PublicKey | null = null;
+      const listener = program.addEventListener("withdrawMade", (ev: any) => {
+        recipient = ev.recipient;
+      });
+      await program.methods
+        .withdraw(new BN(20_000))
+        .accounts({ authority: payer.publicKey, mint, vaultState, vaultAta, destination: vendorAta, tokenProgram, role: null, policy: null, cosigner: null } as any)
+        .rpc();
+      await new Promise((r) => setTimeout(r, 500));
+      await program.removeEventListener(listener);
+
+      expect(Number(await getTokenBalance(conn, vendorAta))).to.equal(20_000);
+      expect(recipient!.toBase58()).to.equal(vendor.publicKey.toBase58());
+    });
+
     it("rejects withdraw exceeding vault balance", async () => {
       try {
         await program.methods
           .withdraw(new BN(400_000))
-          .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, ownerAta } as any)
+          .accounts({ authority: payer.publicKey, mint, vaultState, vaultAta, destination: ownerAta, tokenProgram, role: null, policy: null, cosigner: null } as any)
           .rpc();
         expect.fail("should have thrown");
       } catch (e: any) {
@@ -160,26 +187,26 @@ describe("vault", () => {
 
     it("enforces daily withdrawal limit", async () => {
       // DAILY_LIMIT = 5_000_000
-      // Current vault has 500k - 200k = 300k remaining
+      // Current vault has 500k - 200k - 20k = 280k remaining
 
       // First withdrawal within limit (2M)
       await program.methods
         .withdraw(new BN(100_000))
-        .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, ownerAta } as any)
+        .accounts({ authority: payer.publicKey, mint, vaultState, vaultAta, destination: ownerAta, tokenProgram, role: null, policy: null, cosigner: null } as any)
         .rpc();
 
       // Second withdrawal still within limit (total 2.1M in window)
       await program.methods
         .withdraw(new BN(100_000))
-        .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, ownerAta } as any)
+        .accounts({ authority: payer.publicKey, mint, vaultState, vaultAta, destination: ownerAta, tokenProgram, role: null, policy: null, cosigner: null } as any)
         .rpc();
 
-      // Try to exceed daily limit: already withdrawn 200k + 100k + 100k = 400k in total
-      // Attempting 4_600_001 more would be 5_000_001 > DAILY_LIMIT (5M).
+      // Try to exceed daily limit: already withdrawn 200k + 20k + 100k + 100k = 420k in total
+      // Attempting 4_600_001 more would be 5_020_001 > DAILY_LIMIT (5M).
       try {
         await program.methods
           .withdraw(new BN(4_600_001))
-          .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, ownerAta } as any)
+          .accounts({ authority: payer.publicKey, mint, vaultState, vaultAta, destination: ownerAta, tokenProgram, role: null, policy: null, cosigner: null } as any)
           .rpc();
         expect.fail("should have thrown DailyLimitExceeded");
       } catch (e: any) {
@@ -206,13 +233,14 @@ describe("vault", () => {
 
     it("adds a delegate record", async () => {
       await program.methods
-        .addDelegate(new BN(ALLOWANCE), new BN(EXPIRES_FUT))
+        .addDelegate(new BN(ALLOWANCE), new BN(EXPIRES_FUT), new BN(0), 0)
         .accounts({
-          owner:          payer.publicKey,
+          authority:      payer.publicKey,
           mint,
           vaultState,
           delegate:       delegateKp.publicKey,
           delegateRecord: delegateRec,
+          role:           null,
         } as any)
         .rpc();
 
@@ -229,11 +257,13 @@ describe("vault", () => {
         .accounts({
           delegateSigner: delegateKp.publicKey,
           mint,
-          owner:          payer.publicKey,
           vaultState,
           vaultAta,
           delegateAta,
           delegateRecord: delegateRec,
+          tokenProgram,
+          policy: null,
+          cosigner: null,
         } as any)
         .signers([delegateKp])
         .rpc();
@@ -249,11 +279,13 @@ describe("vault", () => {
           .accounts({
             delegateSigner: delegateKp.publicKey,
             mint,
-            owner:   payer.publicKey,
             vaultState,
             vaultAta,
             delegateAta,
             delegateRecord: delegateRec,
+            tokenProgram,
+            policy: null,
+            cosigner: null,
           } as any)
           .signers([delegateKp])
           .rpc();
@@ -272,10 +304,11 @@ describe("vault", () => {
       // add_delegate with past expiry should fail
       try {
         await program.methods
-          .addDelegate(new BN(10_000), new BN(EXPIRES_PAS))
+          .addDelegate(new BN(10_000), new BN(EXPIRES_PAS), new BN(0), 0)
           .accounts({
-            owner: payer.publicKey, mint, vaultState,
+            authority: payer.publicKey, mint, vaultState,
             delegate: exp2Kp.publicKey, delegateRecord: exp2Rec,
+            role: null,
           } as any)
           .rpc();
         expect.fail("should have thrown");
@@ -288,9 +321,11 @@ describe("vault", () => {
       await program.methods
         .removeDelegate()
         .accounts({
-          owner: payer.publicKey, mint, vaultState,
+          authority: payer.publicKey, owner: payer.publicKey, mint, vaultState,
           delegate: delegateKp.publicKey,
           delegateRecord: delegateRec,
+          asset: null,
+          role: null,
         } as any)
         .rpc();
 
@@ -309,7 +344,7 @@ describe("vault", () => {
       try {
         await program.methods
           .closeVault()
-          .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta } as any)
+          .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
           .rpc();
         expect.fail("should have thrown");
       } catch (e: any) {
@@ -324,13 +359,13 @@ describe("vault", () => {
       if (balance > 0) {
         await program.methods
           .withdraw(new BN(balance))
-          .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, ownerAta } as any)
+          .accounts({ authority: payer.publicKey, mint, vaultState, vaultAta, destination: ownerAta, tokenProgram, role: null, policy: null, cosigner: null } as any)
           .rpc();
       }
 
       await program.methods
         .closeVault()
-        .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta } as any)
+        .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
         .rpc();
 
       try {
@@ -16,7 +16,7 @@ import {
   deriveVaultPDA,
   getTokenBalance,
 } from "./helpers";
-import { getAssociatedTokenAddress } from "@solana/spl-token";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 
 // Pure logic extracted from on-chain state helpers — tested without RPC
 function checkedAdd(a: number, b: number): number | null {
@@ -112,19 +112,26 @@ describe("vault property tests", () => {
                 mint,
                 vaultState,
                 vaultAta,
+                tokenProgram: TOKEN_PROGRAM_ID,
               } as any)
               .rpc();
 
             // Deposit
             const balanceBefore = Number(await getTokenBalance(conn, ownerAta));
             await program.methods
-              .deposit(new BN(amount))
+              .deposit(new BN(amount), null)
               .accounts({
-                owner: payer.publicKey,
+                depositor: payer.publicKey,
                 mint,
                 vaultState,
-                ownerAta,
+                depositorAta: ownerAta,
                 vaultAta,
+                depositorRecord: null,
+                tranche: null,
+                shareMint: null,
+                depositorShares: null,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                role: null,
               } as any)
               .rpc();
 
@@ -132,11 +139,15 @@ describe("vault property tests", () => {
             await program.methods
               .withdraw(new BN(amount))
               .accounts({
-                owner: payer.publicKey,
+                authority: payer.publicKey,
                 mint,
                 vaultState,
                 vaultAta,
-                ownerAta,
+                destination: ownerAta,
+                tokenProgram: TOKEN_PROGRAM_ID,
+                role: null,
+                policy: null,
+                cosigner: null,
               } as any)
               .rpc();
 
@@ -181,19 +192,26 @@ describe("vault property tests", () => {
                 mint,
                 vaultState,
                 vaultAta,
+                tokenProgram: TOKEN_PROGRAM_ID,
               } as any)
               .rpc();
 
             let prevTotal = 0;
             for (const amt of amounts) {
               await program.methods
-                .deposit(new BN(amt))
+                .deposit(new BN(amt), null)
                 .accounts({
-                  owner: payer.publicKey,
+                  depositor: payer.publicKey,
                   mint,
                   vaultState,
-                  ownerAta,
+                  depositorAta: ownerAta,
                   vaultAta,
+                  depositorRecord: null,
+                  tranche: null,
+                  shareMint: null,
+                  depositorShares: null,
+                  tokenProgram: TOKEN_PROGRAM_ID,
+                  role: null,
                 } as any)
                 .rpc();
 
@@ -0,0 +1,209 @@
+import * as anchor from "@anchor-lang/core";
+import { Program, BN } from "@anchor-lang/core";
+import { Vault } from "../target/types/vault";
+import { getAssociatedTokenAddress, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { expect } from "chai";
+import {
+  createTestMint,
+  fundAta,
+  deriveVaultPDA,
+  deriveVetoKeyPDA,
+  deriveDelegatePDA,
+  derivePendingWithdrawalPDA,
+  deriveWithdrawTiersPDA,
+  getTokenBalance,
+} from "./helpers";
+
+const sleep = (ms: number) => new Promise((r) => setTimeout(r, ms));
+
+describe("vault veto window", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+  const program = anchor.workspace.Vault as Program<Vault>;
+  const conn    = provider.connection;
+  const payer   = (provider.wallet as anchor.Wallet).payer;
+  const tokenProgram = TOKEN_PROGRAM_ID;
+
+  const security = Keypair.generate();
+  const outsider = Keypair.generate();
+
+  let mint:       PublicKey;
+  let ownerAta:   PublicKey;
+  let vaultState: PublicKey;
+  let vaultAta:   PublicKey;
+  let vetoKey:    PublicKey;
+
+  const pendingPda = (id: number) => derivePendingWithdrawalPDA(vaultState, id)[0];
+
+  const request = (id: number, amount: number) =>
+    program.methods
+      .requestWithdraw(new BN(id), new BN(amount))
+      .accounts({ authority: payer.publicKey, vaultState, role: null, withdrawTiers: null, destination: ownerAta, cosigner: null, pendingWithdrawal: pendingPda(id) } as any)
+      .rpc();
+
+  const veto = (id: number, signer: Keypair, reason: number) =>
+    program.methods
+      .vetoWithdraw(reason)
+      .accounts({ vetoer: signer.publicKey, vaultState, vetoKey: deriveVetoKeyPDA(vaultState, signer.publicKey)[0], pendingWithdrawal: pendingPda(id), requester: payer.publicKey } as any)
+      .signers([signer])
+      .rpc();
+
+  const expectRejected = async (p: Promise<unknown>, error: string) => {
+    try {
+      await p;
+      expect.fail("should have thrown");
+    } catch (e: any) {
+      expect(e.message).to.include(error);
+    }
+  };
+
+  before(async () => {
+    mint     = await createTestMint(conn, payer);
+    ownerAta = await fundAta(conn, payer, mint, payer.publicKey, 100_000);
+
+    [vaultState] = deriveVaultPDA(payer.publicKey, mint);
+    vaultAta     = await getAssociatedTokenAddress(mint, vaultState, true);
+    [vetoKey]    = deriveVetoKeyPDA(vaultState, security.publicKey);
+
+    await program.methods
+      .initialize(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, mint, vaultState, vaultAta, tokenProgram } as any)
+      .rpc();
+    await program.methods
+      .deposit(new BN(50_000), null)
+      .accounts({ depositor: payer.publicKey, mint, vaultState, depositorAta: ownerAta, vaultAta, depositorRecord: null, tranche: null, shareMint: null, depositorShares: null, tokenProgram, role: null } as any)
+      .rpc();
+    await program.methods
+      .setVeto(new BN(1_000), new BN(2))
+      .accounts({ owner: payer.publicKey, vaultState } as any)
+      .rpc();
+    await program.methods
+      .addVetoKey()
+      .accounts({ owner: payer.publicKey, vaultState, key: security.publicKey, vetoKey } as any)
+      .rpc();
+  });
+
+  it("large withdrawals must be requested; small ones go straight through", async () => {
+    const withdraw = (amount: number) =>
+      program.methods
+        .withdraw(new BN(amount))
+        .accounts({ authority: payer.publicKey, mint, vaultState, role: null, vaultAta, destination: ownerAta, tokenProgram, policy: null, cosigner: null } as any)
+        .rpc();
+
+    await withdraw(1_000);
+    await expectRejected(withdraw(5_000), "VetoWindowRequired");
+    await expectRejected(request(1, 500), "WithdrawQueueDisabled");
+  });
+
+  it("a veto window needs a positive threshold", async () => {
+    await expectRejected(
+      program.methods
+        .setVeto(new BN(0), new BN(2))
+        .accounts({ owner: payer.publicKey, vaultState } as any)
+        .rpc(),
+      "InvalidVetoThreshold"
+    );
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.vetoThreshold.toNumber()).to.equal(1_000);
+  });
+
+  it("delegates are held to the same threshold", async () => {
+    const delegate    = Keypair.generate();
+    const delegateAta = await fundAta(conn, payer, mint, delegate.publicKey, 0);
+    const [delegateRecord] = deriveDelegatePDA(vaultState, delegate.publicKey);
+    await program.methods
+      .addDelegate(new BN(10_000), new BN(Math.floor(Date.now() / 1000) + 3600), new BN(0), 0)
+      .accounts({ authority: payer.publicKey, mint, vaultState, delegate: delegate.publicKey, delegateRecord, role: null } as any)
+      .rpc();
+
+    const pull = (amount: number) =>
+      program.methods
+        .delegateWithdraw(new BN(amount))
+        .accounts({ delegateSigner: delegate.publicKey, mint, vaultState, vaultAta, delegateAta, delegateRecord, tokenProgram, policy: null, cosigner: null } as any)
+        .signers([delegate])
+        .rpc();
+    await expectRejected(pull(5_000), "VetoWindowRequired");
+    await pull(1_000);
+    expect(Number(await getTokenBalance(conn, delegateAta))).to.equal(1_000);
+  });
+
+  it("a veto key cancels within the window and records its reason", async () => {
+    await request(1, 5_000);
+    await expectRejected(veto(1, outsider, 1), "AccountNotInitialized");
+
+    const reasons: number[] = [];
+    const listener = program.addEventListener("withdrawVetoed", (e: any) => reasons.push(e.reason));
+    await veto(1, security, 7);
+    await sleep(1_000);
+    await program.removeEventListener(listener);
+
+    expect(reasons).to.deep.equal([7]);
+    expect(await conn.getAccountInfo(pendingPda(1))).to.be.null;
+  });
+
+  it("removing a veto key is timelocked", async () => {
+    const remove = () =>
+      program.methods
+        .removeVetoKey()
+        .accounts({ owner: payer.publicKey, vaultState, vetoKey } as any)
+        .rpc();
+
+    await expectRejected(remove(), "NoPendingConfig");
+    await program.methods
+      .requestVetoKeyRemoval()
+      .accounts({ owner: payer.publicKey, vaultState, vetoKey } as any)
+      .rpc();
+    const key = await program.account.vetoKey.fetch(vetoKey);
+    expect(key.removableAt.toNumber()).to.be.greaterThan(Math.floor(Date.now() / 1000));
+    await expectRejected(remove(), "ConfigTimelockActive");
+  });
+
+  it("veto keys cannot be pulled while a withdrawal is pending", async () => {
+    await request(2, 5_000);
+    await expectRejected(
+      program.methods
+        .removeVetoKey()
+        .accounts({ owner: payer.publicKey, vaultState, vetoKey } as any)
+        .rpc(),
+      "PendingWithdrawalsOutstanding",
+    );
+  });
+
+  it("after the window the veto is closed and anyone can execute", async () => {
+    await sleep(3_000);
+    await expectRejected(veto(2, security, 1), "VetoWindowClosed");
+
+    const before = await getTokenBalance(conn, ownerAta);
+    await program.methods
+      .executeWithdraw()
+      .accounts({ executor: outsider.publicKey, mint, vaultState, vaultAta, pendingWithdrawal: pendingPda(2), requester: payer.publicKey, destination: ownerAta, policy: null, tokenProgram } as any)
+      .signers([outsider])
+      .rpc();
+    expect(Number((await getTokenBalance(conn, ownerAta)) - before)).to.equal(5_000);
+  });
+
+  it("turning veto mode off is timelocked", async () => {
+    await program.methods
+      .setVeto(new BN(0), new BN(0))
+      .accounts({ owner: payer.publicKey, vaultState } as any)
+      .rpc();
+    const state = await program.account.vaultState.fetch(vaultState);
+    expect(state.vetoWindow.toNumber()).to.equal(2);
+    expect(state.vetoEffectiveAt.toNumber()).to.be.greaterThan(0);
+  });
+
+  it("a veto key cannot cancel a withdrawal below the threshold", async () => {
+    const [withdrawTiers] = deriveWithdrawTiersPDA(vaultState);
+    await program.methods
+      .setWithdrawTiers([{ minAmount: new BN(0), delay: new BN(3_600) }] as any)
+      .accounts({ owner: payer.publicKey, vaultState, withdrawTiers } as any)
+      .rpc();
+    await program.methods
+      .requestWithdraw(new BN(3), new BN(500))
+      .accounts({ authority: payer.publicKey, vaultState, role: null, withdrawTiers, destination: ownerAta, cosigner: null, pendingWithdrawal: pendingPda(3) } as any)
+      .rpc();
+
+    await expectRejected(veto(3, security, 1), "VetoNotRequired");
+  });
+});